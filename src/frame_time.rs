@@ -0,0 +1,166 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// Number of rows a [`FrameTimeLog`] buffers before flushing them to the file.
+const FRAME_TIME_LOG_FLUSH_INTERVAL: u64 = 60;
+
+/// Distribution of the durations of the last frames, to spot stutters an average hides.
+///
+/// Frames are counted in buckets of `bucket_width`, the last bucket also counts every frame
+/// longer than the histogram covers.
+#[derive(Clone, Debug)]
+pub struct FrameTimeHistogram {
+    capacity: usize,
+    bucket_width: Duration,
+    frame_times: VecDeque<Duration>,
+    bucket_counts: Vec<u32>,
+}
+
+impl FrameTimeHistogram {
+    /// Creates a histogram of the last `capacity` frames over `buckets` buckets.
+    pub fn new(capacity: usize, bucket_width: Duration, buckets: usize) -> Self {
+        assert!(capacity > 0 && buckets > 0 && bucket_width > Duration::ZERO);
+        Self {
+            capacity,
+            bucket_width,
+            frame_times: VecDeque::with_capacity(capacity),
+            bucket_counts: vec![0; buckets],
+        }
+    }
+
+    /// Records the duration of a frame, forgetting the oldest one once full.
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == self.capacity {
+            if let Some(oldest) = self.frame_times.pop_front() {
+                let bucket = self.bucket(oldest);
+                self.bucket_counts[bucket] -= 1;
+            }
+        }
+        let bucket = self.bucket(frame_time);
+        self.bucket_counts[bucket] += 1;
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Gets the number of recorded frames in every bucket, bucket `i` starts at
+    /// `i * bucket_width`.
+    pub fn bucket_counts(&self) -> &[u32] {
+        &self.bucket_counts
+    }
+
+    /// Gets the frame time the fraction `p` of the recorded frames are not longer than, zero
+    /// before any frame is recorded.
+    pub fn percentile(&self, p: f64) -> Duration {
+        let mut frame_times = self.frame_times.iter().copied().collect::<Vec<_>>();
+        frame_times.sort_unstable();
+        // Nearest rank, the index of the smallest sample covering `p` of them.
+        let rank = (p.clamp(0.0, 1.0) * frame_times.len() as f64).ceil() as usize;
+        frame_times
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Gets the median frame time.
+    pub fn p50(&self) -> Duration {
+        self.percentile(0.5)
+    }
+
+    /// Gets the 95th percentile of the frame times.
+    pub fn p95(&self) -> Duration {
+        self.percentile(0.95)
+    }
+
+    /// Gets the 99th percentile of the frame times, the hitches of one frame in a hundred.
+    pub fn p99(&self) -> Duration {
+        self.percentile(0.99)
+    }
+
+    fn bucket(&self, frame_time: Duration) -> usize {
+        let bucket = frame_time.as_nanos() / self.bucket_width.as_nanos();
+        bucket.min(self.bucket_counts.len() as u128 - 1) as usize
+    }
+}
+
+/// CSV trace of the frame timings, one row per frame, to analyze a run offline.
+///
+/// The columns are `frame,frame_ms,cpu_ms,gpu_ms`. `gpu_ms` stays empty since no timestamp
+/// queries are recorded yet.
+pub struct FrameTimeLog {
+    writer: BufWriter<File>,
+    frame: u64,
+}
+
+impl FrameTimeLog {
+    /// Creates the log at `path` and writes the header, an existing file is truncated.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "frame,frame_ms,cpu_ms,gpu_ms")?;
+        Ok(Self { writer, frame: 0 })
+    }
+
+    /// Appends the row of the next frame, the rows are flushed every few frames.
+    pub fn record(&mut self, frame_time: Duration, cpu_time: Duration) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "{},{:.3},{:.3},",
+            self.frame,
+            frame_time.as_secs_f64() * 1000.0,
+            cpu_time.as_secs_f64() * 1000.0
+        )?;
+        self.frame += 1;
+        if self.frame.is_multiple_of(FRAME_TIME_LOG_FLUSH_INTERVAL) {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn percentiles_of_known_samples() {
+        let mut histogram = FrameTimeHistogram::new(100, millis(1), 200);
+        // Recorded out of order, the percentiles sort them.
+        for ms in (1..=100).rev() {
+            histogram.record(millis(ms));
+        }
+        assert_eq!(histogram.p50(), millis(50));
+        assert_eq!(histogram.p95(), millis(95));
+        assert_eq!(histogram.p99(), millis(99));
+        assert_eq!(histogram.percentile(1.0), millis(100));
+        assert_eq!(histogram.percentile(0.0), millis(1));
+        assert_eq!(histogram.percentile(2.0), millis(100));
+    }
+
+    #[test]
+    fn oldest_frames_are_forgotten_past_the_capacity() {
+        let mut histogram = FrameTimeHistogram::new(4, millis(1), 5);
+        for ms in [1, 2, 3, 4, 10, 20] {
+            histogram.record(millis(ms));
+        }
+        // 1ms and 2ms fell out, the last bucket counts 4ms and everything longer.
+        assert_eq!(histogram.bucket_counts(), [0, 0, 0, 1, 3]);
+        assert_eq!(histogram.percentile(0.25), millis(3));
+        assert_eq!(histogram.p50(), millis(4));
+        assert_eq!(histogram.percentile(1.0), millis(20));
+    }
+
+    #[test]
+    fn empty_histogram_reports_zero() {
+        let histogram = FrameTimeHistogram::new(10, millis(1), 4);
+        assert_eq!(histogram.bucket_counts(), [0, 0, 0, 0]);
+        assert_eq!(histogram.p50(), Duration::ZERO);
+        assert_eq!(histogram.p99(), Duration::ZERO);
+    }
+}
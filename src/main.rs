@@ -1,49 +1,552 @@
-use std::time;
+#![allow(clippy::empty_docs)]
+
+use render::context::{
+    bind_group::{BindGroupDescriptor, BindGroupEntry},
+    buffer::{BufferDescriptor, BufferUsages},
+    compute_pass::ComputeCommand,
+    features::Features,
+    RenderContext, RenderContextDescriptor,
+};
+use render_thread::Command;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time,
+};
 use winit::{
-    event::{Event, WindowEvent},
+    dpi::PhysicalSize,
+    event::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
 };
 
+mod frame_time;
+mod metrics;
+mod monitor;
 mod pipeline;
+mod recorder;
 mod render;
+mod render_thread;
+
+/// Time advanced by a recorded frame or by a single step of a paused loop, the `1.0 / 60.0`
+/// seconds recordings always advanced by.
+const FIXED_TIMESTEP: time::Duration = time::Duration::from_nanos(16_666_667);
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
+    // Record frames headlessly: --record <dir> <frames> [<key>@<seconds>...]
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // Draw the shadertoy example instead of the triangle: --toy
+    let desc = match args.iter().position(|arg| arg == "--toy") {
+        Some(index) => {
+            args.remove(index);
+            pipeline::RenderPipelineDescriptor::toy()
+        }
+        None => pipeline::RenderPipelineDescriptor::default(),
+    };
+    // Fill the scene with a fragment shader instead: --fragment <file>
+    let fragment = args
+        .iter()
+        .position(|arg| arg == "--fragment")
+        .map(|index| {
+            assert!(index + 1 < args.len(), "usage: cgtoy --fragment <file>");
+            PathBuf::from(args.drain(index..index + 2).nth(1).unwrap())
+        });
+    // Write a CSV row of timings per frame: --frametime-log <file>
+    let frame_time_log = args
+        .iter()
+        .position(|arg| arg == "--frametime-log")
+        .map(|index| {
+            assert!(
+                index + 1 < args.len(),
+                "usage: cgtoy --frametime-log <file>"
+            );
+            let path = args.drain(index..index + 2).nth(1).unwrap();
+            frame_time::FrameTimeLog::create(Path::new(&path))
+                .unwrap_or_else(|error| panic!("Fail to create `{}`: {}", path, error))
+        });
+    // Go fullscreen on another monitor than the window one with F11: --monitor <index>
+    let monitor_index = args.iter().position(|arg| arg == "--monitor").map(|index| {
+        const USAGE: &str = "usage: cgtoy --monitor <index>";
+        assert!(index + 1 < args.len(), "{}", USAGE);
+        let index = args.drain(index..index + 2).nth(1).unwrap();
+        index.parse::<usize>().expect(USAGE)
+    });
+    // Disable the features known to break some drivers and present with vsync: --safe-mode
+    let safe_mode = match args.iter().position(|arg| arg == "--safe-mode") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    // Record frames at another size than the headless state: --capture-size <w>x<h>
+    let capture_size = args
+        .iter()
+        .position(|arg| arg == "--capture-size")
+        .map(|index| {
+            const USAGE: &str = "usage: cgtoy --capture-size <w>x<h>";
+            assert!(index + 1 < args.len(), "{}", USAGE);
+            let value = args.drain(index..index + 2).nth(1).unwrap();
+            parse_size(&value, USAGE)
+        });
+    // Write a wgpu API trace, if built with the trace feature: --trace <dir>
+    let trace_path = args.iter().position(|arg| arg == "--trace").map(|index| {
+        assert!(index + 1 < args.len(), "usage: cgtoy --trace <dir>");
+        PathBuf::from(args.drain(index..index + 2).nth(1).unwrap())
+    });
+    // Enable more features if the adapter supports them: --features <NAME>[,<NAME>...]
+    let features =
+        args.iter()
+            .position(|arg| arg == "--features")
+            .map_or(wgpu::Features::empty(), |index| {
+                assert!(
+                    index + 1 < args.len(),
+                    "usage: cgtoy --features <NAME>[,<NAME>...]"
+                );
+                let names = args.drain(index..index + 2).nth(1).unwrap();
+                let names: Vec<&str> = names.split(',').collect();
+                Features::from_names(&names).unwrap_or_else(|error| {
+                    panic!(
+                        "unknown feature `{}`, expected one of: {}",
+                        error.name,
+                        error.valid.join(", ")
+                    )
+                })
+            });
+    // Size the window: [--size <w>x<h>] [--min-size <w>x<h>] [--max-size <w>x<h>]
+    let window_config = WindowConfig::from_args(&mut args);
+    // Add two vectors on the GPU without a window and check the sum: --vector-add
+    if args.first().map(String::as_str) == Some("--vector-add") {
+        return vector_add(safe_mode, features).await;
+    }
+    // Compare a frame to its golden image, exiting with 1 if they differ:
+    // --compare <image> <golden> <tolerance> [<diff>]
+    if args.first().map(String::as_str) == Some("--compare") {
+        return compare(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("--record") {
+        return record(
+            &args[1..],
+            &desc,
+            fragment.as_deref(),
+            safe_mode,
+            features,
+            capture_size,
+            trace_path.as_deref(),
+        )
+        .await;
+    }
     let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
-        .with_title("CGToy")
-        .build(&event_loop)
-        .unwrap();
-    let mut pipeline_state = pipeline::PipelineState::new(&window).await;
-    let mut time_last = time::Instant::now();
+    let window = Arc::new(
+        window_config
+            .apply(WindowBuilder::new().with_title("CGToy"))
+            .build(&event_loop)
+            .unwrap(),
+    );
+    let mut monitors = monitor::Monitors::new(&window, monitor_index);
+    log::info!("Monitors: {}", monitors.describe().join(", "));
+    let context = RenderContext::new(context_descriptor(
+        Some(&window),
+        safe_mode,
+        features,
+        trace_path.as_deref(),
+    ))
+    .await
+    .unwrap_or_else(|error| panic!("Fail to create render context: {:?}", error));
+    log_features(&context, features);
+    // The surface is created here, next to the window, before the state moves to the render thread
+    let mut pipeline_state = pipeline::PipelineState::new(&context, &window, &desc)
+        .await
+        .unwrap_or_else(|error| panic!("Fail to create pipeline: {:?}", error));
+    if let Some(fragment) = &fragment {
+        load_fragment_shader(&mut pipeline_state, fragment);
+    }
+    // Frame pacing, shared so readers off the render thread never block it
+    let metrics = Arc::new(metrics::Metrics::default());
+    let mut render_thread =
+        render_thread::RenderThread::spawn(pipeline_state, window.clone(), metrics, frame_time_log);
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Poll;
+        // The render thread draws continuously, the event loop only wakes up for events
+        *control_flow = ControlFlow::Wait;
         match event {
-            Event::WindowEvent { window_id, event } => {
-                if window_id == window.id() {
-                    match event {
-                        WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                        WindowEvent::Resized(new_size) => pipeline_state.resize(new_size),
-                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                            pipeline_state.resize(*new_inner_size)
-                        }
-                        _ => (),
+            Event::WindowEvent { window_id, event } if window_id == window.id() => match event {
+                WindowEvent::CloseRequested => {
+                    render_thread.join();
+                    *control_flow = ControlFlow::Exit;
+                }
+                WindowEvent::Resized(new_size) => render_thread.send(Command::Resize(new_size)),
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                } => render_thread.send(Command::ScaleFactorChanged {
+                    scale_factor,
+                    new_size: *new_inner_size,
+                }),
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key),
+                            ..
+                        },
+                    ..
+                } => match key {
+                    // Not every platform sends a resize when the fullscreen state changes
+                    VirtualKeyCode::F11 => {
+                        monitors.toggle_fullscreen(&window);
+                        render_thread.send(Command::Resize(window.inner_size()));
                     }
+                    VirtualKeyCode::M => {
+                        monitors.select_next();
+                        monitors.move_fullscreen(&window);
+                        log::info!("Monitors: {}", monitors.describe().join(", "));
+                        render_thread.send(Command::Resize(window.inner_size()));
+                    }
+                    _ => render_thread.send(Command::Key(key)),
+                },
+                WindowEvent::CursorMoved { position, .. } => {
+                    render_thread.send(Command::CursorMoved(position))
                 }
-            }
-            Event::MainEventsCleared => window.request_redraw(),
-            Event::RedrawRequested(_) => {
-                pipeline_state.render();
-                // Calculate fps
-                let time_now = time::Instant::now();
-                let render_time = time_now - time_last;
-                let fps = 1_000_000 / render_time.as_micros();
-                window.set_title(format!("CGToy - fps:{}", fps).as_str());
-                time_last = time_now;
-            }
+                WindowEvent::MouseInput {
+                    state,
+                    button: MouseButton::Left,
+                    ..
+                } => render_thread.send(Command::LeftButton(state == ElementState::Pressed)),
+                _ => (),
+            },
             _ => (),
         }
     });
 }
+
+/// Initial size and size constraints of the window, in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct WindowConfig {
+    /// Initial inner size, the OS picks one if `None`.
+    inner_size: Option<PhysicalSize<u32>>,
+    /// Smallest inner size the window can be resized to.
+    min_inner_size: Option<PhysicalSize<u32>>,
+    /// Largest inner size the window can be resized to.
+    max_inner_size: Option<PhysicalSize<u32>>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            inner_size: None,
+            min_inner_size: Some(PhysicalSize::new(160, 120)),
+            max_inner_size: None,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Takes the size options out of `args`, each is followed by `<width>x<height>`.
+    fn from_args(args: &mut Vec<String>) -> Self {
+        const USAGE: &str =
+            "usage: cgtoy [--size <w>x<h>] [--min-size <w>x<h>] [--max-size <w>x<h>]";
+        let mut config = Self::default();
+        let mut index = 0;
+        while index < args.len() {
+            let size = match args[index].as_str() {
+                "--size" => &mut config.inner_size,
+                "--min-size" => &mut config.min_inner_size,
+                "--max-size" => &mut config.max_inner_size,
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            };
+            assert!(index + 1 < args.len(), "{}", USAGE);
+            let value = args.drain(index..index + 2).nth(1).unwrap();
+            *size = Some(parse_size(&value, USAGE));
+        }
+        config
+    }
+
+    /// Applies the size and constraints to a window about to be built.
+    fn apply(&self, mut builder: WindowBuilder) -> WindowBuilder {
+        if let Some(size) = self.inner_size {
+            builder = builder.with_inner_size(size);
+        }
+        if let Some(size) = self.min_inner_size {
+            builder = builder.with_min_inner_size(size);
+        }
+        if let Some(size) = self.max_inner_size {
+            builder = builder.with_max_inner_size(size);
+        }
+        builder
+    }
+}
+
+async fn record(
+    args: &[String],
+    desc: &pipeline::RenderPipelineDescriptor<'_>,
+    fragment: Option<&Path>,
+    safe_mode: bool,
+    features: wgpu::Features,
+    capture_size: Option<PhysicalSize<u32>>,
+    trace_path: Option<&Path>,
+) {
+    const USAGE: &str = "usage: cgtoy --record <dir> <frames> [<key>@<seconds>...]";
+    let (dir, frames, inputs) = match args {
+        [dir, frames, inputs @ ..] => (dir, frames, inputs),
+        _ => panic!("{}", USAGE),
+    };
+    let frames: u32 = frames.parse().expect(USAGE);
+    let inputs = inputs
+        .iter()
+        .map(|input| {
+            let (key, seconds) = input.split_once('@').expect(USAGE);
+            let key = match key {
+                "A" => VirtualKeyCode::A,
+                "B" => VirtualKeyCode::B,
+                "C" => VirtualKeyCode::C,
+                "D" => VirtualKeyCode::D,
+                "F" => VirtualKeyCode::F,
+                "G" => VirtualKeyCode::G,
+                "K" => VirtualKeyCode::K,
+                "N" => VirtualKeyCode::N,
+                "S" => VirtualKeyCode::S,
+                "T" => VirtualKeyCode::T,
+                "U" => VirtualKeyCode::U,
+                "V" => VirtualKeyCode::V,
+                "W" => VirtualKeyCode::W,
+                "Minus" => VirtualKeyCode::Minus,
+                "Equals" => VirtualKeyCode::Equals,
+                "LBracket" => VirtualKeyCode::LBracket,
+                "RBracket" => VirtualKeyCode::RBracket,
+                _ => panic!("unsupported key `{}`", key),
+            };
+            recorder::ScriptedInput {
+                time: time::Duration::from_secs_f64(seconds.parse().expect(USAGE)),
+                key,
+            }
+        })
+        .collect();
+    let context = RenderContext::new(context_descriptor(None, safe_mode, features, trace_path))
+        .await
+        .unwrap_or_else(|error| panic!("Fail to create render context: {:?}", error));
+    log_features(&context, features);
+    let mut pipeline_state =
+        pipeline::PipelineState::new_headless(&context, PhysicalSize::new(800, 600), desc)
+            .await
+            .unwrap_or_else(|error| panic!("Fail to create pipeline: {:?}", error));
+    if let Some(fragment) = fragment {
+        load_fragment_shader(&mut pipeline_state, fragment);
+    }
+    let mut recorder = recorder::FrameRecorder::new(FIXED_TIMESTEP, inputs);
+    if let Some(size) = capture_size {
+        recorder = recorder.with_size(size);
+    }
+    recorder
+        .record(&mut pipeline_state, frames, Path::new(dir))
+        .await
+        .unwrap_or_else(|error| match error {
+            recorder::RecordError::Image(error) => panic!("Fail to save frame: {}", error),
+            recorder::RecordError::Capture { frame, error } => {
+                panic!("Fail to capture frame {}: {:?}", frame, error)
+            }
+        });
+}
+
+/// Compares two images with [`recorder::compare_images`], optionally saving the differences,
+/// and exits with 1 if a pixel differs beyond the tolerance.
+fn compare(args: &[String]) {
+    const USAGE: &str = "usage: cgtoy --compare <image> <golden> <tolerance> [<diff>]";
+    let (image, golden, tolerance, diff_path) = match args {
+        [image, golden, tolerance] => (image, golden, tolerance, None),
+        [image, golden, tolerance, diff] => (image, golden, tolerance, Some(Path::new(diff))),
+        _ => panic!("{}", USAGE),
+    };
+    let tolerance: f32 = tolerance.parse().expect(USAGE);
+    let open = |path: &String| {
+        image::open(path)
+            .unwrap_or_else(|error| panic!("Fail to open `{}`: {}", path, error))
+            .into_rgba8()
+    };
+    let (image, golden) = (open(image), open(golden));
+    if image.dimensions() != golden.dimensions() {
+        log::error!(
+            "Image is {:?} but golden image is {:?}.",
+            image.dimensions(),
+            golden.dimensions()
+        );
+        std::process::exit(1);
+    }
+    let (width, height) = image.dimensions();
+    let diff = recorder::compare_images(&image, &golden, width, height, tolerance).unwrap();
+    if let Some(path) = diff_path {
+        diff.save(path).unwrap();
+    }
+    log::info!(
+        "Pixels differing:{} max:{} mean:{}",
+        diff.differing_pixels(),
+        diff.max,
+        diff.mean
+    );
+    if !diff.matches() {
+        std::process::exit(1);
+    }
+}
+
+/// Parses a `<width>x<height>` size, panicking with `usage` if it is malformed.
+fn parse_size(value: &str, usage: &str) -> PhysicalSize<u32> {
+    let (width, height) = value.split_once('x').expect(usage);
+    PhysicalSize::new(width.parse().expect(usage), height.parse().expect(usage))
+}
+
+/// Runs the whole compute path with a context created without a window: uploads two vectors,
+/// adds them with `vector_add.wgsl` and reads the sum back, panicking if it is wrong.
+async fn vector_add(safe_mode: bool, features: wgpu::Features) {
+    const LEN: u32 = 1000;
+    const WORKGROUP_SIZE: u32 = 64;
+    let context = RenderContext::new(RenderContextDescriptor {
+        downlevel_flags: wgpu::DownlevelFlags::COMPUTE_SHADERS,
+        ..context_descriptor(None, safe_mode, features, None)
+    })
+    .await
+    .unwrap_or_else(|error| panic!("Fail to create render context: {:?}", error));
+    let resource = context.resource();
+    // Upload the inputs and create the output
+    let a: Vec<f32> = (0..LEN).map(|i| i as f32).collect();
+    let b: Vec<f32> = (0..LEN).map(|i| (2 * i) as f32 + 0.5).collect();
+    let expected: Vec<f32> = a.iter().zip(&b).map(|(a, b)| a + b).collect();
+    let a = resource.create_buffer_owned(a, BufferUsages::STORAGE).await;
+    let b = resource.create_buffer_owned(b, BufferUsages::STORAGE).await;
+    let sum = resource
+        .create_buffer(&BufferDescriptor {
+            label: Some("CGToy - Buffer(sum)"),
+            size: u64::from(LEN) * 4,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        })
+        .await;
+    // Create the pipeline and bind the buffers with the layout reflected from the shader
+    let shader = resource
+        .create_shader_module(
+            Some("CGToy - Shader(vector add)"),
+            include_str!("shaders/vector_add.wgsl"),
+        )
+        .await
+        .unwrap_or_else(|error| panic!("Fail to create shader: {:?}", error));
+    let layouts = resource
+        .auto_layout(shader)
+        .await
+        .unwrap_or_else(|error| panic!("Fail to reflect layouts: {:?}", error));
+    let pipeline = resource
+        .create_compute_pipeline(
+            Some("CGToy - Pipeline(vector add)"),
+            shader,
+            "main",
+            &layouts,
+        )
+        .await
+        .unwrap_or_else(|error| panic!("Fail to create pipeline: {:?}", error));
+    let entries: Vec<BindGroupEntry> = [a, b, sum]
+        .iter()
+        .enumerate()
+        .map(|(binding, &buffer)| BindGroupEntry {
+            binding: binding as u32,
+            resource: buffer.into(),
+        })
+        .collect();
+    let bind_group = resource
+        .create_bind_group(&BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(vector add)"),
+            layout: layouts[0],
+            entries: &entries,
+        })
+        .await
+        .unwrap_or_else(|error| panic!("Fail to create bind group: {:?}", error));
+    // Dispatch and read the sum back
+    let mut encoder = context
+        .device()
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(vector add)"),
+        });
+    resource
+        .record_compute_pass(
+            &mut encoder,
+            Some("CGToy - ComputePass(vector add)"),
+            &[
+                ComputeCommand::SetPipeline(&pipeline),
+                ComputeCommand::SetBindGroup {
+                    index: 0,
+                    bind_group,
+                },
+                ComputeCommand::Dispatch {
+                    x: LEN.div_ceil(WORKGROUP_SIZE),
+                    y: 1,
+                    z: 1,
+                },
+            ],
+        )
+        .await
+        .unwrap_or_else(|error| panic!("Fail to record compute pass: {:?}", error));
+    resource.submit(std::iter::once(encoder.finish()));
+    let result: Vec<f32> = resource
+        .read_buffer_typed(sum)
+        .await
+        .unwrap_or_else(|error| panic!("Fail to read sum: {:?}", error));
+    if let Some(index) = (0..expected.len()).find(|&i| result[i] != expected[i]) {
+        panic!(
+            "Wrong sum at {}: {} instead of {}",
+            index, result[index], expected[index]
+        );
+    }
+    log::info!("Added {} floats on the GPU.", LEN);
+}
+
+/// Replaces the scene shader by the fragment shader given with `--fragment`.
+fn load_fragment_shader(pipeline_state: &mut pipeline::PipelineState, fragment: &Path) {
+    pipeline_state
+        .load_fragment_shader(fragment)
+        .unwrap_or_else(|error| panic!("Fail to set fragment shader: {:?}", error));
+}
+
+/// Describes the render context the pipeline state draws with, `window` is `None` when
+/// recording headlessly, `safe_mode` works around known-bad drivers and `trace_path` receives
+/// the wgpu API trace.
+fn context_descriptor<'a>(
+    window: Option<&'a winit::window::Window>,
+    safe_mode: bool,
+    features: wgpu::Features,
+    trace_path: Option<&Path>,
+) -> RenderContextDescriptor<'a> {
+    #[cfg(not(feature = "trace"))]
+    if trace_path.is_some() {
+        log::warn!("CGToy is built without the trace feature, no API trace is written.");
+    }
+    RenderContextDescriptor {
+        features: wgpu::Features::empty(),
+        // Features asked for on the command line are optional too, an adapter lacking some
+        // still runs the toy
+        optional_features: wgpu::Features::NON_FILL_POLYGON_MODE | features,
+        downlevel_flags: wgpu::DownlevelFlags::empty(),
+        window,
+        latency_mode: Default::default(),
+        validation: Default::default(),
+        debug_names: false,
+        force_fallback_adapter: false,
+        safe_mode,
+        #[cfg(feature = "trace")]
+        trace_path: trace_path.map(Path::to_path_buf),
+    }
+}
+
+/// Logs the features of the device, and the `requested` ones it was created without.
+fn log_features(context: &RenderContext, requested: wgpu::Features) {
+    let enabled = context.device().features();
+    log::info!("Features: {}", Features::from(enabled).names().join(", "));
+    let missing = requested - enabled;
+    if !missing.is_empty() {
+        log::warn!(
+            "Features not enabled: {}",
+            Features::from(missing).names().join(", ")
+        );
+    }
+}
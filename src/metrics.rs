@@ -0,0 +1,75 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Frame pacing statistics updated by the render loop and readable from any thread.
+///
+/// Share it through an `Arc`, every field is an atomic so neither [`Self::record`] nor
+/// [`Self::snapshot`] ever blocks.
+#[derive(Debug)]
+pub struct Metrics {
+    frames: AtomicU64,
+    current_nanos: AtomicU64,
+    total_nanos: AtomicU64,
+    min_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            frames: AtomicU64::new(0),
+            current_nanos: AtomicU64::new(0),
+            total_nanos: AtomicU64::new(0),
+            min_nanos: AtomicU64::new(u64::MAX),
+            max_nanos: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Metrics {
+    /// Records the duration of a frame.
+    pub fn record(&self, frame_time: Duration) {
+        let nanos = frame_time.as_nanos().min(u64::MAX as u128) as u64;
+        self.current_nanos.store(nanos, Ordering::Relaxed);
+        self.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.min_nanos.fetch_min(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+        // Counted last, a reader seeing the frame sees its time too.
+        self.frames.fetch_add(1, Ordering::Release);
+    }
+
+    /// Reads the statistics of the frames recorded so far.
+    ///
+    /// Fields are read one by one, a frame recorded meanwhile may only show in some of them.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let frames = self.frames.load(Ordering::Acquire);
+        if frames == 0 {
+            return MetricsSnapshot::default();
+        }
+        let nanos = |atomic: &AtomicU64| atomic.load(Ordering::Relaxed);
+        MetricsSnapshot {
+            frames,
+            current: Duration::from_nanos(nanos(&self.current_nanos)),
+            average: Duration::from_nanos(nanos(&self.total_nanos) / frames),
+            min: Duration::from_nanos(nanos(&self.min_nanos)),
+            max: Duration::from_nanos(nanos(&self.max_nanos)),
+        }
+    }
+}
+
+/// Frame pacing statistics read by [`Metrics::snapshot`], all zero before the first frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Number of frames recorded.
+    pub frames: u64,
+    /// Duration of the last frame.
+    pub current: Duration,
+    /// Mean duration of every frame.
+    pub average: Duration,
+    /// Shortest frame.
+    pub min: Duration,
+    /// Longest frame.
+    pub max: Duration,
+}
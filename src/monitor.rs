@@ -0,0 +1,81 @@
+use winit::{
+    dpi::PhysicalSize,
+    monitor::MonitorHandle,
+    window::{Fullscreen, Window},
+};
+
+/// Monitors a window can go fullscreen on, one of them is selected as the fullscreen target.
+pub struct Monitors {
+    handles: Vec<MonitorHandle>,
+    selected: usize,
+}
+
+impl Monitors {
+    /// Lists the monitors available to `window`, selecting `index` if given and valid, the
+    /// monitor the window is on otherwise.
+    pub fn new(window: &Window, index: Option<usize>) -> Self {
+        let handles = window.available_monitors().collect::<Vec<_>>();
+        let current = window.current_monitor();
+        let selected = index
+            .filter(|&index| index < handles.len())
+            .or_else(|| {
+                handles
+                    .iter()
+                    .position(|handle| Some(handle) == current.as_ref())
+            })
+            .unwrap_or(0);
+        Self { handles, selected }
+    }
+
+    /// Describes every monitor with its index, name and resolution, e.g.
+    /// `1: DP-2 2560x1440`, the selected one is marked with a `*`.
+    pub fn describe(&self) -> Vec<String> {
+        self.handles
+            .iter()
+            .enumerate()
+            .map(|(index, handle)| {
+                let PhysicalSize { width, height } = handle.size();
+                format!(
+                    "{}{}: {} {}x{}",
+                    if index == self.selected { "*" } else { "" },
+                    index,
+                    handle.name().unwrap_or_else(|| "unknown".to_owned()),
+                    width,
+                    height
+                )
+            })
+            .collect()
+    }
+
+    /// Selects the next monitor, wrapping around after the last one.
+    pub fn select_next(&mut self) {
+        if !self.handles.is_empty() {
+            self.selected = (self.selected + 1) % self.handles.len();
+        }
+    }
+
+    /// Gets the selected monitor, `None` if the platform lists no monitor.
+    pub fn selected(&self) -> Option<&MonitorHandle> {
+        self.handles.get(self.selected)
+    }
+
+    /// Leaves fullscreen, or enters borderless fullscreen on the selected monitor.
+    pub fn toggle_fullscreen(&self, window: &Window) {
+        if window.fullscreen().is_some() {
+            window.set_fullscreen(None);
+        } else {
+            self.apply_fullscreen(window);
+        }
+    }
+
+    /// Moves a fullscreen window to the selected monitor, windowed windows are left as is.
+    pub fn move_fullscreen(&self, window: &Window) {
+        if window.fullscreen().is_some() {
+            self.apply_fullscreen(window);
+        }
+    }
+
+    fn apply_fullscreen(&self, window: &Window) {
+        window.set_fullscreen(Some(Fullscreen::Borderless(self.selected().cloned())));
+    }
+}
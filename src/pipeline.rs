@@ -1,46 +1,918 @@
-use std::iter;
-use winit::{dpi::PhysicalSize, window::Window};
+use self::{
+    camera::{Camera, Ray},
+    deferred::Deferred,
+    gizmos::Gizmos,
+    viewports::{ViewportRect, Viewports, MAX_VIEWPORTS},
+};
+use crate::render::context::{
+    bind_group::{BindingType, BufferBindingType, TextureSampleType},
+    color_target::ColorTargetState,
+    preprocess::preprocess_file,
+    shader,
+    submission::SubmissionIndex,
+    surface::{self, SurfaceError},
+    texture::{TextureFormat, TextureViewDimension},
+    RenderContext, RenderContextError,
+};
+use bytemuck::{Pod, Zeroable};
+use std::{
+    collections::VecDeque,
+    iter,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time,
+};
+use wgpu::util::DeviceExt;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::VirtualKeyCode,
+    window::{Window, WindowId},
+};
+
+pub mod camera;
+mod deferred;
+mod gizmos;
+pub mod viewports;
+
+/// Colors cycled through by pressing `C`.
+const TRIANGLE_COLORS: [[f32; 3]; 4] = [
+    [0.3, 0.2, 0.1],
+    [0.9, 0.2, 0.2],
+    [0.2, 0.9, 0.2],
+    [0.2, 0.2, 0.9],
+];
+
+/// Colors of the color parameter cycled through by pressing `K`, white leaves a shader as is.
+const PARAM_COLORS: [[f32; 4]; 4] = [
+    [1.0, 1.0, 1.0, 1.0],
+    [1.0, 0.6, 0.3, 1.0],
+    [0.4, 1.0, 0.6, 1.0],
+    [0.5, 0.6, 1.0, 1.0],
+];
+
+/// Backgrounds cycled through by pressing `B`, the first is the default.
+const BACKGROUNDS: [Background; 3] = [
+    Background::Gradient(
+        wgpu::Color {
+            r: 0.1,
+            g: 0.2,
+            b: 0.3,
+            a: 1.0,
+        },
+        wgpu::Color {
+            r: 0.02,
+            g: 0.03,
+            b: 0.06,
+            a: 1.0,
+        },
+    ),
+    Background::SolidColor(wgpu::Color {
+        r: 0.1,
+        g: 0.2,
+        b: 0.3,
+        a: 1.0,
+    }),
+    Background::Checker(
+        wgpu::Color {
+            r: 0.2,
+            g: 0.2,
+            b: 0.2,
+            a: 1.0,
+        },
+        wgpu::Color {
+            r: 0.3,
+            g: 0.3,
+            b: 0.3,
+            a: 1.0,
+        },
+        32.0,
+    ),
+];
+
+/// What the scene color is filled with before the scene is drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Background {
+    /// Cleared to one color.
+    SolidColor(wgpu::Color),
+    /// Vertical gradient from the top color to the bottom color.
+    Gradient(wgpu::Color, wgpu::Color),
+    /// Checkerboard of two colors, with square cells of the size in scene pixels.
+    Checker(wgpu::Color, wgpu::Color, f32),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        BACKGROUNDS[0]
+    }
+}
+
+/// Uniform data of the background shader, mirrors `Background` in `background.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BackgroundUniforms {
+    color_a: [f32; 4],
+    color_b: [f32; 4],
+    /// 0 for a gradient, 1 for a checker.
+    mode: u32,
+    cell_size: f32,
+    _padding: [f32; 2],
+}
+
+impl BackgroundUniforms {
+    /// Gets the uniforms drawing `background`, a solid color is cleared and never drawn.
+    fn new(background: Background) -> Self {
+        let rgba = |color: wgpu::Color| {
+            [
+                color.r as f32,
+                color.g as f32,
+                color.b as f32,
+                color.a as f32,
+            ]
+        };
+        let (color_a, color_b, mode, cell_size) = match background {
+            Background::SolidColor(color) => (color, color, 0, 1.0),
+            Background::Gradient(top, bottom) => (top, bottom, 0, 1.0),
+            Background::Checker(a, b, cell_size) => (a, b, 1, cell_size.max(1.0)),
+        };
+        Self {
+            color_a: rgba(color_a),
+            color_b: rgba(color_b),
+            mode,
+            cell_size,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Uniform data of the color shader, mirrors `Uniforms` in `color.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ColorUniforms {
+    /// RGB color of the triangle, the alpha channel is always 1.0.
+    color: [f32; 4],
+}
+
+impl ColorUniforms {
+    fn new(color: [f32; 3]) -> Self {
+        Self {
+            color: [color[0], color[1], color[2], 1.0],
+        }
+    }
+}
+
+/// Metrics accumulated while recording and submitting a frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// Time spent on the CPU from acquiring the frame to submitting its commands.
+    pub cpu_time: time::Duration,
+    /// Number of draw calls recorded.
+    pub draw_calls: u32,
+    /// Number of triangles submitted by the draw calls.
+    pub triangles: u64,
+}
+
+/// Render pass recording the draw calls it issues into a [`FrameStats`].
+///
+/// Every pipeline drawn through it is assumed to use a triangle list topology.
+struct CountedRenderPass<'a, 'b> {
+    pass: wgpu::RenderPass<'a>,
+    stats: &'b mut FrameStats,
+}
+
+impl<'a, 'b> CountedRenderPass<'a, 'b> {
+    fn new(pass: wgpu::RenderPass<'a>, stats: &'b mut FrameStats) -> Self {
+        Self { pass, stats }
+    }
+
+    fn set_pipeline(&mut self, pipeline: &'a wgpu::RenderPipeline) {
+        self.pass.set_pipeline(pipeline);
+    }
+
+    fn set_bind_group(&mut self, index: u32, bind_group: &'a wgpu::BindGroup) {
+        self.pass.set_bind_group(index, bind_group, &[]);
+    }
+
+    fn set_vertex_buffer(&mut self, slot: u32, buffer: &'a wgpu::Buffer) {
+        self.pass.set_vertex_buffer(slot, buffer.slice(..));
+    }
+
+    fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.stats.draw_calls += 1;
+        self.stats.triangles += u64::from(vertices.len() as u32 / 3) * instances.len() as u64;
+        self.pass.draw(vertices, instances);
+    }
+
+    /// Draws with a line list pipeline, which submits no triangles.
+    fn draw_lines(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.stats.draw_calls += 1;
+        self.pass.draw(vertices, instances);
+    }
+
+    /// Groups the following commands under `label` in graphics debuggers, until
+    /// [`Self::pop_debug_group`].
+    fn push_debug_group(&mut self, label: &str) {
+        self.pass.push_debug_group(label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        self.pass.pop_debug_group();
+    }
+
+    /// Restricts the following draws to the `(x, y, width, height)` pixels of the targets.
+    fn set_viewport(&mut self, (x, y, width, height): (u32, u32, u32, u32)) {
+        self.pass
+            .set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        self.pass.set_scissor_rect(x, y, width, height);
+    }
+}
+
+/// Shadertoy style inputs bound at group 1 of the scene pipeline, mirrors `ToyUniforms` in `toy.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+struct ToyUniforms {
+    /// Size of the viewport drawn in pixels.
+    resolution: [f32; 2],
+    /// Seconds elapsed over every update.
+    time: f32,
+    _padding: f32,
+    /// Pixel position of the cursor while the left button is held in xy, and of the last click
+    /// in zw, z is negative once the button is released. Positions are relative to the bottom
+    /// left corner of the viewport.
+    mouse: [f32; 4],
+    /// Pixel position of the top left corner of the viewport in the scene target, fragment
+    /// coordinates minus it are relative to the viewport.
+    origin: [f32; 2],
+    /// Size of the whole scene target in pixels, fragment coordinates divided by it address
+    /// the previous frame.
+    scene_resolution: [f32; 2],
+}
+
+/// Names of the float parameters of [`ToyParams`], in field order.
+const PARAM_NAMES: [&str; 4] = ["intensity", "speed", "scale", "offset"];
+
+/// Host controlled parameters bound at group 1 binding 1 of the scene pipeline, mirrors
+/// `ToyParams` in `toy.wgsl`.
+///
+/// The defaults leave a shader unchanged: it is scaled by 1 and offset by 0.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ToyParams {
+    /// Float parameters named by [`PARAM_NAMES`].
+    values: [f32; 4],
+    /// RGBA color parameter.
+    color: [f32; 4],
+}
+
+impl Default for ToyParams {
+    fn default() -> Self {
+        Self {
+            values: [1.0, 1.0, 1.0, 0.0],
+            color: [1.0; 4],
+        }
+    }
+}
+
+/// Offscreen color and depth targets the scene is rendered into before being upscaled to the surface.
+struct SceneTarget {
+    size: wgpu::Extent3d,
+    /// Targets the scene and gizmos are drawn into with MSAA, resolved into the others.
+    multisampled: Option<MultisampledTarget>,
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    _extra_textures: Vec<wgpu::Texture>,
+    extra_views: Vec<wgpu::TextureView>,
+    _depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+    blit_bind_group: wgpu::BindGroup,
+}
+
+/// Copy of the previous frame, sampled by the scene shaders at group 1 binding 2.
+struct FeedbackTarget {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl FeedbackTarget {
+    /// Creates a black target matching the output of `sc_config`, frames are blitted to it.
+    fn new(device: &wgpu::Device, sc_config: &wgpu::SurfaceConfiguration) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("CGToy - Texture(feedback)"),
+            size: wgpu::Extent3d {
+                width: sc_config.width.max(1),
+                height: sc_config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: sc_config.format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self {
+            _texture: texture,
+            view,
+        }
+    }
+}
+
+/// Multisampled scene color, extra and depth targets.
+struct MultisampledTarget {
+    _textures: Vec<wgpu::Texture>,
+    /// Views of the scene color then of every extra target.
+    color_views: Vec<wgpu::TextureView>,
+    depth_view: wgpu::TextureView,
+}
+
+impl MultisampledTarget {
+    fn new(
+        device: &wgpu::Device,
+        size: wgpu::Extent3d,
+        color_formats: &[wgpu::TextureFormat],
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let create = |label, format| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size,
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            })
+        };
+        let mut textures: Vec<_> = color_formats
+            .iter()
+            .map(|&format| create("CGToy - Texture(scene msaa)", format))
+            .collect();
+        textures.push(create("CGToy - Texture(scene msaa depth)", depth_format));
+        let mut views: Vec<_> = textures
+            .iter()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+        let depth_view = views.pop().unwrap();
+        Self {
+            _textures: textures,
+            color_views: views,
+            depth_view,
+        }
+    }
+}
+
+impl SceneTarget {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        device: &wgpu::Device,
+        sc_config: &wgpu::SurfaceConfiguration,
+        format: wgpu::TextureFormat,
+        extra_targets: &[ColorTargetState],
+        depth_format: wgpu::TextureFormat,
+        render_scale: f32,
+        upscale_filter: wgpu::FilterMode,
+        sample_count: u32,
+        blit_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        // Scale the surface size, keeping at least one texel
+        let scale = |length: u32| ((length as f32 * render_scale).round() as u32).max(1);
+        let size = wgpu::Extent3d {
+            width: scale(sc_config.width),
+            height: scale(sc_config.height),
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("CGToy - Texture(scene)"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let extra_textures: Vec<_> = extra_targets
+            .iter()
+            .map(|target| {
+                device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("CGToy - Texture(scene extra)"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: target.format.into(),
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                })
+            })
+            .collect();
+        let extra_views = extra_textures
+            .iter()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("CGToy - Texture(scene depth)"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: depth_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("CGToy - Sampler(blit)"),
+            mag_filter: upscale_filter,
+            min_filter: upscale_filter,
+            ..Default::default()
+        });
+        let blit_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(blit)"),
+            layout: blit_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let multisampled = if sample_count > 1 {
+            let color_formats: Vec<_> = iter::once(format)
+                .chain(extra_targets.iter().map(|target| target.format.into()))
+                .collect();
+            Some(MultisampledTarget::new(
+                device,
+                size,
+                &color_formats,
+                depth_format,
+                sample_count,
+            ))
+        } else {
+            None
+        };
+        Self {
+            size,
+            multisampled,
+            _texture: texture,
+            view,
+            _extra_textures: extra_textures,
+            extra_views,
+            _depth_texture: depth_texture,
+            depth_view,
+            blit_bind_group,
+        }
+    }
+}
+
+/// Shader the scene is drawn with, kept to recreate the scene pipeline on reload.
+#[derive(Clone, Debug)]
+enum SceneShader {
+    /// The shader of the descriptor the state was created with.
+    Descriptor {
+        source: String,
+        vertex_entry_point: String,
+        fragment_entry_point: String,
+    },
+    /// A fragment shader filling the scene, `path` is the file it is read from if any.
+    Fragment {
+        source: String,
+        path: Option<PathBuf>,
+    },
+}
+
+/// How the edges of the scene are smoothed.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum AntiAliasing {
+    /// Edges are left aliased.
+    #[default]
+    None,
+    /// The scene and the gizmos are drawn with this many samples per texel, then resolved.
+    ///
+    /// The count must be supported by every scene target, see [`ResourceContext::sample_count`],
+    /// and every color target must be a float format.
+    ///
+    /// [`ResourceContext::sample_count`]: crate::render::context::ResourceContext::sample_count
+    Msaa(u32),
+    /// The resolved scene is smoothed by FXAA while it is blitted to the output, for formats
+    /// or backends where multisampling is costly or unsupported.
+    Fxaa,
+}
+
+impl AntiAliasing {
+    /// Gets the number of samples per texel of the scene targets.
+    pub fn sample_count(self) -> u32 {
+        match self {
+            Self::Msaa(count) => count,
+            _ => 1,
+        }
+    }
+
+    /// Gets the mode cycled to by pressing `A`.
+    fn next(self) -> Self {
+        match self {
+            Self::None => Self::Msaa(4),
+            Self::Msaa(_) => Self::Fxaa,
+            Self::Fxaa => Self::None,
+        }
+    }
+}
+
+/// Describes the shader and depth setup of the scene pipeline.
+///
+/// Besides the color uniform at group 0, the pipeline binds shadertoy style inputs at group 1,
+/// which shaders read by declaring the `ToyUniforms` struct of `toy.wgsl`, and the parameters
+/// of [`PipelineState::set_param`] at binding 1 of the same group, see `ToyParams`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderPipelineDescriptor<'a> {
+    /// WGSL source of the shader module.
+    pub source: &'a str,
+    /// Name of the vertex entry point in the module.
+    pub vertex_entry_point: &'a str,
+    /// Name of the fragment entry point in the module.
+    pub fragment_entry_point: &'a str,
+    /// Format of the depth buffer.
+    ///
+    /// A format the device can not render to falls back to `Depth32Float` with a warning, or
+    /// to `Depth24PlusStencil8` for a format with stencil, see
+    /// [`ResourceContext::depth_format`].
+    ///
+    /// [`ResourceContext::depth_format`]: crate::render::context::ResourceContext::depth_format
+    pub depth_format: wgpu::TextureFormat,
+    /// Value the depth buffer is cleared to at the start of every frame.
+    pub depth_clear: f32,
+    /// Comparison a fragment depth must pass against the depth buffer.
+    ///
+    /// Use `Greater` with a `depth_clear` of 0.0 for reverse-Z, which spreads the depth
+    /// precision more evenly over the view distance.
+    pub depth_compare: wgpu::CompareFunction,
+    /// Targets of the fragment outputs from `[[location(1)]]` on, each with its own format
+    /// and blending.
+    ///
+    /// `[[location(0)]]` is always the scene color, which is shown on the surface. The extra
+    /// targets are offscreen textures of the scene size cleared to transparent every frame.
+    pub extra_targets: &'a [ColorTargetState],
+    /// Number of submitted frames the GPU may still be working on when the next frame is
+    /// acquired, at least 1.
+    ///
+    /// Rendering waits for the oldest of them to complete beyond that, so the CPU does not run
+    /// ahead of the GPU with growing latency and per-frame memory.
+    pub max_frames_in_flight: usize,
+}
+
+impl Default for RenderPipelineDescriptor<'_> {
+    /// Uses the color shader, whose entry points are both named `main`, with standard depth.
+    fn default() -> Self {
+        Self {
+            source: include_str!("shaders/color.wgsl"),
+            vertex_entry_point: "main",
+            fragment_entry_point: "main",
+            depth_format: wgpu::TextureFormat::Depth32Float,
+            depth_clear: 1.0,
+            depth_compare: wgpu::CompareFunction::Less,
+            extra_targets: &[],
+            max_frames_in_flight: 2,
+        }
+    }
+}
+
+impl RenderPipelineDescriptor<'_> {
+    /// Uses the example shader animating a fullscreen triangle with the shadertoy inputs.
+    pub fn toy() -> Self {
+        Self {
+            source: include_str!("shaders/toy.wgsl"),
+            vertex_entry_point: "vs_main",
+            fragment_entry_point: "fs_main",
+            ..Default::default()
+        }
+    }
+
+    /// Whether greater depths are closer to the camera.
+    fn reverse_z(&self) -> bool {
+        matches!(
+            self.depth_compare,
+            wgpu::CompareFunction::Greater | wgpu::CompareFunction::GreaterEqual
+        )
+    }
+
+    /// Checks the source parses and exports the requested entry points.
+    fn validate(&self) -> Result<(), PipelineError> {
+        let module = naga::front::wgsl::parse_str(self.source)
+            .map_err(|error| PipelineError::InvalidShader(error.emit_to_string(self.source)))?;
+        for (stage, name) in [
+            (naga::ShaderStage::Vertex, self.vertex_entry_point),
+            (naga::ShaderStage::Fragment, self.fragment_entry_point),
+        ] {
+            let exported = |entry_point: &naga::EntryPoint| {
+                entry_point.stage == stage && entry_point.name == name
+            };
+            if !module.entry_points.iter().any(exported) {
+                let exports: Vec<String> = module
+                    .entry_points
+                    .iter()
+                    .map(|entry_point| format!("`{}` ({:?})", entry_point.name, entry_point.stage))
+                    .collect();
+                return Err(PipelineError::MissingEntryPoint(format!(
+                    "no {:?} entry point `{}`, the module exports {}",
+                    stage,
+                    name,
+                    exports.join(", ")
+                )));
+            }
+        }
+        // Every fragment output needs a color target at its location
+        match module.entry_points.iter().find(|entry_point| {
+            entry_point.stage == naga::ShaderStage::Fragment
+                && entry_point.name == self.fragment_entry_point
+        }) {
+            Some(fragment) => {
+                check_color_targets(&module, fragment, 1 + self.extra_targets.len() as u32)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks every output of the fragment entry point has one of the `target_count` color targets.
+fn check_color_targets(
+    module: &naga::Module,
+    fragment: &naga::EntryPoint,
+    target_count: u32,
+) -> Result<(), PipelineError> {
+    let bindings = match &fragment.function.result {
+        Some(naga::FunctionResult {
+            binding: Some(binding),
+            ..
+        }) => vec![binding],
+        Some(result) => match &module.types[result.ty].inner {
+            naga::TypeInner::Struct { members, .. } => members
+                .iter()
+                .filter_map(|member| member.binding.as_ref())
+                .collect(),
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+    for binding in bindings {
+        if let naga::Binding::Location { location, .. } = *binding {
+            if location >= target_count {
+                return Err(PipelineError::MissingColorTarget(format!(
+                    "`{}` writes `[[location({})]]` but the pipeline has {} color targets",
+                    fragment.name, location, target_count
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the shader binds only what the scene pipeline layout has: the color uniform at group
+/// 0, the shadertoy inputs, the parameters and the previous frame at group 1.
+fn check_scene_bindings(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+) -> Result<(), PipelineError> {
+    let layouts = shader::reflect_layouts(module, info)
+        .map_err(|error| PipelineError::MismatchedBinding(format!("{:?}", error)))?;
+    // Uniform structures must fit the buffers bound at their binding.
+    let uniform = |ty: BindingType, size: usize| match ty {
+        BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            min_binding_size,
+            ..
+        } => min_binding_size.is_none_or(|min| min.get() <= size as u64),
+        _ => false,
+    };
+    for (group, entries) in layouts.iter().enumerate() {
+        for entry in entries {
+            let bound = match (group, entry.binding) {
+                (0, 0) => uniform(entry.ty, std::mem::size_of::<ColorUniforms>()),
+                (1, 0) => uniform(entry.ty, std::mem::size_of::<ToyUniforms>()),
+                (1, 1) => uniform(entry.ty, std::mem::size_of::<ToyParams>()),
+                (1, 2) => matches!(
+                    entry.ty,
+                    BindingType::Texture {
+                        sample_type: TextureSampleType::Float { .. },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    }
+                ),
+                (1, 3) => matches!(
+                    entry.ty,
+                    BindingType::Sampler {
+                        comparison: false,
+                        ..
+                    }
+                ),
+                _ => false,
+            };
+            if !bound {
+                return Err(PipelineError::MismatchedBinding(format!(
+                    "`[[group({}), binding({})]]` does not match the scene pipeline: {:?}",
+                    group, entry.binding, entry.ty
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Error of a pipeline creation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PipelineError {
+    /// Neither a hardware nor a software adapter is available.
+    NoAdapter,
+    /// The WGSL source failed to parse or validate, holds the formatted error.
+    InvalidShader(String),
+    /// The shader binds a resource the scene pipeline layout does not have at its group and
+    /// binding, holds the binding and what the shader declares.
+    MismatchedBinding(String),
+    /// An entry point is missing, holds its name along the entry points the module exports.
+    MissingEntryPoint(String),
+    /// A fragment output has no color target, holds its location and the target count.
+    MissingColorTarget(String),
+    /// The render context can not draw the pipeline.
+    Context(RenderContextError),
+    /// A shader file could not be read, holds its path and the IO error.
+    ShaderFile(String),
+    /// The sample count is invalid or a scene target format can not be resolved.
+    UnsupportedAntiAliasing(AntiAliasing),
+    /// The device lacks features a mode needs, such as the hidden-line overlay.
+    MissingFeatures(wgpu::Features),
+    /// No shader parameter has the name.
+    UnknownParam(String),
+    /// No camera is registered at the index.
+    CameraNotFound(usize),
+    /// The number of viewports is not between 1 and [`MAX_VIEWPORTS`].
+    ViewportCount(usize),
+    /// A capture was asked for a size without area.
+    EmptyCapture,
+    /// The captured frame could not be mapped, or its pixels do not fill the capture size.
+    CaptureFailed,
+    /// The surface could not be reconfigured, such as for frame feedback.
+    Surface(SurfaceError),
+}
+
+impl From<RenderContextError> for PipelineError {
+    fn from(error: RenderContextError) -> Self {
+        match error {
+            RenderContextError::NoAdapter => Self::NoAdapter,
+            error => Self::Context(error),
+        }
+    }
+}
 
 pub struct PipelineState {
+    /// Size of the targets the frames are rendered to.
     size: PhysicalSize<u32>,
-    surface: wgpu::Surface,
+    /// Size of the window, or of the headless state, the targets are resized back to for the
+    /// frames after a capture at another size.
+    window_size: PhysicalSize<u32>,
+    scale_factor: f64,
+    context: RenderContext,
+    window_id: Option<WindowId>,
     sc_config: wgpu::SurfaceConfiguration,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
+    scene_format: wgpu::TextureFormat,
+    extra_targets: Vec<ColorTargetState>,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    scene_shader: SceneShader,
+    /// Vertex stage of the scene pipeline, the hidden-line overlay draws the same geometry.
+    scene_vertex: (wgpu::ShaderModule, String),
+    render_pipeline_layout: wgpu::PipelineLayout,
     render_pipeline: wgpu::RenderPipeline,
+    /// Pipeline drawing the edges of the scene in hidden-line mode.
+    wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    color_buffer: wgpu::Buffer,
+    color_bind_group: wgpu::BindGroup,
+    render_scale: f32,
+    upscale_filter: wgpu::FilterMode,
+    anti_aliasing: AntiAliasing,
+    hidden_line: bool,
+    /// Whether the last resize was to a zero size, no frame is rendered until the next one.
+    minimized: bool,
+    blit_pipeline: wgpu::RenderPipeline,
+    blit_bind_group_layout: wgpu::BindGroupLayout,
+    /// Pipeline filling the frame in magenta while the scene shader is broken.
+    error_pipeline: wgpu::RenderPipeline,
+    /// Why the scene shader last failed to build, cleared once a shader is accepted.
+    pipeline_error: Option<PipelineError>,
+    background: Background,
+    background_index: usize,
+    background_buffer: wgpu::Buffer,
+    background_bind_group_layout: wgpu::BindGroupLayout,
+    background_bind_group: wgpu::BindGroup,
+    /// Pipeline drawing gradient and checker backgrounds into the scene color.
+    background_pipeline: wgpu::RenderPipeline,
+    scene_target: SceneTarget,
+    color_index: usize,
+    /// Shadertoy inputs buffer and bind group of every viewport.
+    toy_buffers: Vec<wgpu::Buffer>,
+    toy_bind_group_layout: wgpu::BindGroupLayout,
+    toy_bind_groups: Vec<wgpu::BindGroup>,
+    toy_uniforms: ToyUniforms,
+    params_buffer: wgpu::Buffer,
+    params: ToyParams,
+    /// Copy of the previous frame, see [`Self::set_frame_feedback`].
+    feedback: FeedbackTarget,
+    feedback_sampler: wgpu::Sampler,
+    frame_feedback: bool,
+    /// Whether the scene is drawn over the previous frames, see [`Self::set_accumulate`].
+    accumulate: bool,
+    /// Whether the next frame clears the scene while accumulating.
+    clear_accumulation: bool,
+    param_color_index: usize,
+    cursor: PhysicalPosition<f64>,
+    /// Whether the left button is held, the sign of the shadertoy mouse input is ambiguous for
+    /// clicks on the edges of the scene.
+    mouse_pressed: bool,
+    /// Registered cameras, each keeps its state while another is active.
+    cameras: Vec<Camera>,
+    active_camera: usize,
+    /// Split of the scene into viewports, the active camera draws the whole scene if `None`.
+    viewports: Option<Viewports>,
+    gizmos: Gizmos,
+    deferred: Deferred,
+    /// Format of the scene depth buffer, [`RenderPipelineDescriptor::depth_format`] or its
+    /// fallback.
+    depth_format: wgpu::TextureFormat,
+    depth_clear: f32,
+    depth_compare: wgpu::CompareFunction,
+    reverse_z: bool,
+    /// Completions of the frames submitted by [`Self::render`], oldest first.
+    frames_in_flight: VecDeque<SubmissionIndex>,
+    max_frames_in_flight: usize,
 }
 
 impl PipelineState {
+    /// Creates a state presenting to `window` through the surfaces of `context`, the scene is
+    /// drawn with the shader of `desc`.
     ///
-    pub async fn new(window: &Window) -> Self {
+    /// The adapter of `context` must have been requested compatible with `window`, otherwise
+    /// [`SurfaceError::UnsupportedSurface`] is returned.
+    pub async fn new(
+        context: &RenderContext,
+        window: &Window,
+        desc: &RenderPipelineDescriptor<'_>,
+    ) -> Result<Self, PipelineError> {
         // Get window size
         let size = window.inner_size();
-        // Create WGPU instance
-        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
         // Create surface
-        let surface = unsafe { instance.create_surface(window) };
-        // Request adapter
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptionsBase {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-            })
+        let resource = context.resource();
+        resource
+            .create_surface(window)
             .await
-            .unwrap();
-        // Request device and queue
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("CGToy - Device(default)"),
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::default(),
-                },
-                None,
-            )
+            .map_err(PipelineError::Surface)?;
+        let sc_format = resource
+            .surface_format(window.id())
             .await
-            .unwrap();
-        // Configure surface
-        let sc_format = surface.get_preferred_format(&adapter).unwrap();
+            .expect("surface has just been created")
+            .into();
+        let mut state =
+            Self::from_context(context, Some(window.id()), sc_format, size, desc).await?;
+        state.scale_factor = window.scale_factor();
+        Ok(state)
+    }
+
+    /// Creates a state without a window, frames can only be read back by [`Self::capture`].
+    ///
+    /// A context created without a window falls back to a software adapter when no GPU is
+    /// available.
+    pub async fn new_headless(
+        context: &RenderContext,
+        size: PhysicalSize<u32>,
+        desc: &RenderPipelineDescriptor<'_>,
+    ) -> Result<Self, PipelineError> {
+        Self::from_context(
+            context,
+            None,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            size,
+            desc,
+        )
+        .await
+    }
+
+    async fn from_context(
+        context: &RenderContext,
+        window_id: Option<WindowId>,
+        sc_format: wgpu::TextureFormat,
+        size: PhysicalSize<u32>,
+        desc: &RenderPipelineDescriptor<'_>,
+    ) -> Result<Self, PipelineError> {
+        // Check the shader before wgpu gets to reject it
+        desc.validate()?;
+        let device = context.device().clone();
+        let queue = context.queue().clone();
+        let depth_format = context
+            .resource()
+            .depth_format(desc.depth_format.into())
+            .into();
+        // Mirror the surface configuration, the resource context configures the surface
         let sc_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: sc_format,
@@ -48,73 +920,1024 @@ impl PipelineState {
             height: size.height,
             present_mode: wgpu::PresentMode::Mailbox,
         };
-        surface.configure(&device, &sc_config);
         // Create shader module
         let shader_color = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
             label: Some("CGToy - Shader(color)"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/color.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(desc.source.into()),
+        });
+        // Create color uniform buffer
+        let color_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(color)"),
+            contents: bytemuck::bytes_of(&ColorUniforms::new([0.3, 0.2, 0.1])),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
+        // Create color bind group
+        let color_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(color)"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let color_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(color)"),
+            layout: &color_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: color_buffer.as_entire_binding(),
+            }],
+        });
+        // Create toy uniform buffers and bind groups, one per viewport
+        let toy_buffers: Vec<_> = (0..MAX_VIEWPORTS)
+            .map(|_| {
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("CGToy - Buffer(toy)"),
+                    contents: bytemuck::bytes_of(&ToyUniforms::default()),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                })
+            })
+            .collect();
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(params)"),
+            contents: bytemuck::bytes_of(&ToyParams::default()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        // Create the previous frame copy, black until frame feedback is enabled
+        let feedback = FeedbackTarget::new(&device, &sc_config);
+        let feedback_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("CGToy - Sampler(feedback)"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniform_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let toy_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(toy)"),
+                entries: &[
+                    uniform_entry(0),
+                    uniform_entry(1),
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler {
+                            filtering: true,
+                            comparison: false,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+        let toy_bind_groups = toy_buffers
+            .iter()
+            .map(|toy_buffer| {
+                create_toy_bind_group(
+                    &device,
+                    &toy_bind_group_layout,
+                    (toy_buffer, &params_buffer),
+                    (&feedback.view, &feedback_sampler),
+                )
+            })
+            .collect();
         // Create pipeline layout
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("CGToy - PipelineLayout(default)"),
-                bind_group_layouts: &[],
+                bind_group_layouts: &[&color_bind_group_layout, &toy_bind_group_layout],
                 push_constant_ranges: &[],
             });
         // Create pipeline
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("CGToy - Pipeline(color)"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader_color,
-                entry_point: "main",
-                buffers: &[],
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                clamp_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader_color,
-                entry_point: "main",
-                targets: &[sc_format.into()],
-            }),
+        let render_pipeline = create_scene_pipeline(
+            &device,
+            &render_pipeline_layout,
+            (&shader_color, desc.vertex_entry_point),
+            (&shader_color, desc.fragment_entry_point),
+            sc_format,
+            desc.extra_targets,
+            depth_format,
+            desc.depth_compare,
+            1,
+        );
+        // Create blit bind group layout
+        let blit_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(blit)"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler {
+                            filtering: true,
+                            comparison: false,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+        // Create blit pipeline
+        let anti_aliasing = AntiAliasing::default();
+        let blit_pipeline =
+            create_blit_pipeline(&device, &blit_bind_group_layout, sc_format, anti_aliasing);
+        let error_pipeline = create_error_pipeline(&device, sc_format);
+        // Create background uniform buffer, bind group and pipeline
+        let background = Background::default();
+        let background_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(background)"),
+            contents: bytemuck::bytes_of(&BackgroundUniforms::new(background)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
-        Self {
+        let background_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(background)"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let background_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(background)"),
+            layout: &background_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: background_buffer.as_entire_binding(),
+            }],
+        });
+        let background_pipeline = create_background_pipeline(
+            &device,
+            &background_bind_group_layout,
+            sc_format,
+            anti_aliasing.sample_count(),
+        );
+        // Create scene target at full resolution
+        let render_scale = 1.0;
+        let upscale_filter = wgpu::FilterMode::Linear;
+        let scene_target = SceneTarget::new(
+            &device,
+            &sc_config,
+            sc_format,
+            desc.extra_targets,
+            depth_format,
+            render_scale,
+            upscale_filter,
+            anti_aliasing.sample_count(),
+            &blit_bind_group_layout,
+        );
+        // Create gizmos
+        let reverse_z = desc.reverse_z();
+        let gizmos = Gizmos::new(&device, sc_format, depth_format, 1, reverse_z, 10, 1.0);
+        let camera = Camera::new(size, reverse_z);
+        gizmos.set_view_proj(&queue, 0, camera.view_proj());
+        // Create deferred shading example
+        let deferred = Deferred::new(
+            &device,
+            scene_target.size,
+            sc_format,
+            depth_format,
+            desc.depth_compare,
+        );
+        deferred.set_view_proj(&queue, 0, camera.view_proj());
+        let mut state = Self {
             size,
-            surface,
+            window_size: size,
+            scale_factor: 1.0,
+            context: context.clone(),
+            window_id,
             sc_config,
+            scene_format: sc_format,
+            extra_targets: desc.extra_targets.to_vec(),
             device,
             queue,
+            scene_shader: SceneShader::Descriptor {
+                source: desc.source.to_owned(),
+                vertex_entry_point: desc.vertex_entry_point.to_owned(),
+                fragment_entry_point: desc.fragment_entry_point.to_owned(),
+            },
+            scene_vertex: (shader_color, desc.vertex_entry_point.to_owned()),
+            render_pipeline_layout,
             render_pipeline,
+            wireframe_pipeline: None,
+            color_buffer,
+            color_bind_group,
+            render_scale,
+            upscale_filter,
+            anti_aliasing,
+            hidden_line: false,
+            minimized: false,
+            blit_pipeline,
+            blit_bind_group_layout,
+            error_pipeline,
+            pipeline_error: None,
+            background,
+            background_index: 0,
+            background_buffer,
+            background_bind_group_layout,
+            background_bind_group,
+            background_pipeline,
+            scene_target,
+            color_index: 0,
+            toy_buffers,
+            toy_bind_group_layout,
+            toy_bind_groups,
+            toy_uniforms: Default::default(),
+            params_buffer,
+            params: Default::default(),
+            feedback,
+            feedback_sampler,
+            frame_feedback: false,
+            accumulate: false,
+            clear_accumulation: true,
+            param_color_index: 0,
+            cursor: PhysicalPosition::new(0.0, 0.0),
+            mouse_pressed: false,
+            cameras: vec![camera],
+            active_camera: 0,
+            viewports: None,
+            gizmos,
+            deferred,
+            depth_format,
+            depth_clear: desc.depth_clear,
+            depth_compare: desc.depth_compare,
+            reverse_z,
+            frames_in_flight: VecDeque::new(),
+            max_frames_in_flight: desc.max_frames_in_flight.max(1),
+        };
+        // Keep the clear value in the depth range
+        state.set_depth_clear(desc.depth_clear);
+        // Register the debug camera
+        state.add_camera(Camera::top_down(size, reverse_z));
+        Ok(state)
+    }
+
+    /// Replaces the scene shader by the fragment shader `wgsl` filling the scene, its first
+    /// fragment entry point is used.
+    ///
+    /// No bind group is needed, the shadertoy inputs can still be read by declaring the
+    /// `ToyUniforms` of `toy.wgsl` at group 1, the parameters by declaring its `ToyParams` at
+    /// binding 1, and the previous frame as a `texture_2d<f32>` at binding 2 with its sampler at
+    /// binding 3, see [`Self::set_frame_feedback`]. The current shader is kept if `wgsl` is
+    /// rejected, but the frames are filled in magenta until a shader is accepted, see
+    /// [`Self::pipeline_error`].
+    pub fn set_fragment_shader(&mut self, wgsl: &str) -> Result<(), PipelineError> {
+        let result = self.build_fragment_shader(wgsl);
+        self.track_pipeline_error(result)
+    }
+
+    /// Builds the scene pipeline of [`Self::set_fragment_shader`].
+    fn build_fragment_shader(&mut self, wgsl: &str) -> Result<(), PipelineError> {
+        // Check the shader before wgpu gets to reject it
+        let module = naga::front::wgsl::parse_str(wgsl)
+            .map_err(|error| PipelineError::InvalidShader(error.emit_to_string(wgsl)))?;
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|error| PipelineError::InvalidShader(error.to_string()))?;
+        check_scene_bindings(&module, &info)?;
+        let fragment = module
+            .entry_points
+            .iter()
+            .find(|entry_point| entry_point.stage == naga::ShaderStage::Fragment)
+            .ok_or_else(|| {
+                PipelineError::MissingEntryPoint("no Fragment entry point".to_string())
+            })?;
+        check_color_targets(&module, fragment, 1 + self.extra_targets.len() as u32)?;
+        // Pair it with a fullscreen triangle
+        let shader_fullscreen = self
+            .device
+            .create_shader_module(&wgpu::ShaderModuleDescriptor {
+                label: Some("CGToy - Shader(fullscreen)"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("shaders/fullscreen.wgsl").into()),
+            });
+        let shader_fragment = self
+            .device
+            .create_shader_module(&wgpu::ShaderModuleDescriptor {
+                label: Some("CGToy - Shader(fragment)"),
+                source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+            });
+        self.render_pipeline = create_scene_pipeline(
+            &self.device,
+            &self.render_pipeline_layout,
+            (&shader_fullscreen, "main"),
+            (&shader_fragment, &fragment.name),
+            self.scene_format,
+            &self.extra_targets,
+            self.depth_format,
+            self.depth_compare,
+            self.anti_aliasing.sample_count(),
+        );
+        self.scene_vertex = (shader_fullscreen, "main".to_owned());
+        self.recreate_wireframe_pipeline();
+        self.scene_shader = SceneShader::Fragment {
+            source: wgsl.to_owned(),
+            path: None,
+        };
+        Ok(())
+    }
+
+    /// Reads the fragment shader at `path` and sets it as by [`Self::set_fragment_shader`],
+    /// [`Self::reload`] then reads it again.
+    ///
+    /// Its `//!include "path"` directives are resolved relative to the including file, see
+    /// [`preprocess_file`].
+    pub fn load_fragment_shader(&mut self, path: &Path) -> Result<(), PipelineError> {
+        let wgsl = match preprocess_file(path) {
+            Ok(wgsl) => wgsl,
+            Err(error) => {
+                let error = PipelineError::ShaderFile(format!("`{}`: {:?}", path.display(), error));
+                return self.track_pipeline_error(Err(error));
+            }
+        };
+        self.set_fragment_shader(&wgsl)?;
+        self.scene_shader = SceneShader::Fragment {
+            source: wgsl,
+            path: Some(path.to_owned()),
+        };
+        Ok(())
+    }
+
+    /// Recreates the scene and blit pipelines along the scene target, keeping the surface,
+    /// the device and the queue.
+    ///
+    /// A fragment shader loaded from a file is read again, so edits show without restarting.
+    /// Everything is kept as is if the shader is rejected, the frames are filled in magenta
+    /// until the shader is fixed.
+    pub fn reload(&mut self) -> Result<(), PipelineError> {
+        let result = match self.scene_shader.clone() {
+            SceneShader::Fragment {
+                path: Some(path), ..
+            } => self.load_fragment_shader(&path),
+            _ => self.recreate_scene_pipeline(),
+        };
+        self.track_pipeline_error(result)?;
+        self.recreate_blit_pipeline();
+        self.recreate_scene_target();
+        Ok(())
+    }
+
+    /// Gets why the scene shader last failed to build, the frames are filled in magenta
+    /// instead of the scene while it is set.
+    ///
+    /// The error is cleared once a shader is accepted by [`Self::set_fragment_shader`],
+    /// [`Self::load_fragment_shader`] or [`Self::reload`].
+    pub fn pipeline_error(&self) -> Option<&PipelineError> {
+        self.pipeline_error.as_ref()
+    }
+
+    /// Falls back to the error pipeline if `result` is an error, or recovers from it otherwise.
+    fn track_pipeline_error<T>(
+        &mut self,
+        result: Result<T, PipelineError>,
+    ) -> Result<T, PipelineError> {
+        match &result {
+            Ok(_) => {
+                if self.pipeline_error.take().is_some() {
+                    log::info!("Scene pipeline recovered.");
+                }
+            }
+            Err(error) => self.pipeline_error = Some(error.clone()),
+        }
+        result
+    }
+
+    /// Recreates the scene pipeline from the shader it was last created with.
+    fn recreate_scene_pipeline(&mut self) -> Result<(), PipelineError> {
+        match self.scene_shader.clone() {
+            SceneShader::Descriptor {
+                source,
+                vertex_entry_point,
+                fragment_entry_point,
+            } => {
+                let desc = RenderPipelineDescriptor {
+                    source: &source,
+                    vertex_entry_point: &vertex_entry_point,
+                    fragment_entry_point: &fragment_entry_point,
+                    depth_format: self.depth_format,
+                    depth_clear: self.depth_clear,
+                    depth_compare: self.depth_compare,
+                    extra_targets: &self.extra_targets,
+                    max_frames_in_flight: self.max_frames_in_flight,
+                };
+                desc.validate()?;
+                let shader = self
+                    .device
+                    .create_shader_module(&wgpu::ShaderModuleDescriptor {
+                        label: Some("CGToy - Shader(color)"),
+                        source: wgpu::ShaderSource::Wgsl(desc.source.into()),
+                    });
+                self.render_pipeline = create_scene_pipeline(
+                    &self.device,
+                    &self.render_pipeline_layout,
+                    (&shader, desc.vertex_entry_point),
+                    (&shader, desc.fragment_entry_point),
+                    self.scene_format,
+                    desc.extra_targets,
+                    self.depth_format,
+                    desc.depth_compare,
+                    self.anti_aliasing.sample_count(),
+                );
+                self.scene_vertex = (shader, vertex_entry_point);
+                self.recreate_wireframe_pipeline();
+                Ok(())
+            }
+            SceneShader::Fragment { source, path } => {
+                self.build_fragment_shader(&source)?;
+                self.scene_shader = SceneShader::Fragment { source, path };
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets how the edges of the scene are smoothed, the current mode is kept if `anti_aliasing`
+    /// is not supported.
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: AntiAliasing) -> Result<(), PipelineError> {
+        if anti_aliasing == self.anti_aliasing {
+            return Ok(());
+        }
+        if let AntiAliasing::Msaa(count) = anti_aliasing {
+            // Every target needs the count, integer targets can not be resolved
+            let color_formats: Vec<TextureFormat> = iter::once(self.scene_format.into())
+                .chain(self.extra_targets.iter().map(|target| target.format))
+                .collect();
+            let resolvable = color_formats.iter().all(|&format| {
+                matches!(
+                    wgpu::TextureFormat::from(format).describe().sample_type,
+                    wgpu::TextureSampleType::Float { .. }
+                )
+            });
+            let formats: Vec<TextureFormat> = color_formats
+                .iter()
+                .copied()
+                .chain(iter::once(self.depth_format.into()))
+                .collect();
+            if !resolvable || self.context.resource().sample_count(&formats, count) != count {
+                return Err(PipelineError::UnsupportedAntiAliasing(anti_aliasing));
+            }
+        }
+        let previous = self.anti_aliasing;
+        self.anti_aliasing = anti_aliasing;
+        if let Err(error) = self.recreate_scene_pipeline() {
+            self.anti_aliasing = previous;
+            return Err(error);
+        }
+        self.gizmos
+            .set_sample_count(&self.device, anti_aliasing.sample_count());
+        self.background_pipeline = create_background_pipeline(
+            &self.device,
+            &self.background_bind_group_layout,
+            self.scene_format,
+            anti_aliasing.sample_count(),
+        );
+        self.recreate_blit_pipeline();
+        self.recreate_scene_target();
+        Ok(())
+    }
+
+    /// Draws the edges of the scene in a constant color over its filled triangles, depth
+    /// tested against them so hidden edges stay hidden.
+    ///
+    /// Lines need [`wgpu::Features::NON_FILL_POLYGON_MODE`], the mode is left off without it.
+    pub fn set_hidden_line(&mut self, hidden_line: bool) -> Result<(), PipelineError> {
+        let missing = wgpu::Features::NON_FILL_POLYGON_MODE - self.device.features();
+        if hidden_line && !missing.is_empty() {
+            return Err(PipelineError::MissingFeatures(missing));
+        }
+        self.hidden_line = hidden_line;
+        self.recreate_wireframe_pipeline();
+        Ok(())
+    }
+
+    /// Recreates the hidden-line overlay pipeline along the scene pipeline.
+    fn recreate_wireframe_pipeline(&mut self) {
+        self.wireframe_pipeline = self.hidden_line.then(|| {
+            create_wireframe_pipeline(
+                &self.device,
+                &self.render_pipeline_layout,
+                (&self.scene_vertex.0, &self.scene_vertex.1),
+                self.scene_format,
+                self.depth_format,
+                self.depth_compare,
+                self.anti_aliasing.sample_count(),
+                self.reverse_z,
+            )
+        });
+    }
+
+    /// Sets the value the depth buffer is cleared to at the start of every frame, clamped to
+    /// the `[0, 1]` depth range.
+    ///
+    /// The depth compare function is fixed at creation, the far plane is 0.0 with reverse-Z and
+    /// 1.0 otherwise, see [`RenderPipelineDescriptor::depth_compare`].
+    pub fn set_depth_clear(&mut self, depth_clear: f32) {
+        self.depth_clear = depth_clear.clamp(0.0, 1.0);
+    }
+
+    pub async fn resize(&mut self, new_size: PhysicalSize<u32>) {
+        // A minimized window has no area, the surface keeps its size until it is restored
+        self.minimized = new_size.width == 0 || new_size.height == 0;
+        if !self.minimized {
+            if let Some(window_id) = self.window_id {
+                self.context
+                    .resource()
+                    .update_surface(window_id, new_size)
+                    .await;
+            }
+            self.window_size = new_size;
+            self.set_output_size(new_size);
+        }
+    }
+
+    /// Whether the window is minimized, [`Self::render`] skips frames until it is restored.
+    pub fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
+    /// Resizes the targets the frames are rendered to if they are not `size` yet.
+    fn fit_output_size(&mut self, size: PhysicalSize<u32>) {
+        if size != self.size {
+            self.set_output_size(size);
+        }
+    }
+
+    /// Resizes the targets the frames are rendered to, along the camera aspect ratio.
+    fn set_output_size(&mut self, new_size: PhysicalSize<u32>) {
+        self.size = new_size;
+        self.sc_config.width = new_size.width;
+        self.sc_config.height = new_size.height;
+        self.recreate_scene_target();
+        self.recreate_feedback_target();
+        for camera in &mut self.cameras {
+            camera.set_viewport_size(new_size);
+        }
+        self.upload_camera();
+    }
+
+    /// Registers a camera, returning the index to activate it with.
+    ///
+    /// Its aspect ratio is fitted to the output, then kept up to date on resize. Its depth
+    /// convention is replaced with the one of the depth buffer, so it can come from anywhere.
+    pub fn add_camera(&mut self, mut camera: Camera) -> usize {
+        camera.set_viewport_size(self.size);
+        camera.reverse_z = self.reverse_z;
+        self.cameras.push(camera);
+        self.cameras.len() - 1
+    }
+
+    /// Views the scene through the camera registered at `index`.
+    ///
+    /// The first camera is the one the state is created with, the second looks down from above.
+    pub fn set_active_camera(&mut self, index: usize) -> Result<(), PipelineError> {
+        if index >= self.cameras.len() {
+            return Err(PipelineError::CameraNotFound(index));
+        }
+        self.active_camera = index;
+        self.upload_camera();
+        Ok(())
+    }
+
+    /// Gets the camera the scene is viewed through.
+    pub fn camera(&self) -> &Camera {
+        &self.cameras[self.active_camera]
+    }
+
+    /// Splits the scene into viewports each drawn through a registered camera, or draws the
+    /// whole scene through the active camera with `None`.
+    ///
+    /// The cameras are fitted to the aspect ratio of their viewport. The scene shader, the
+    /// hidden-line overlay, the gizmos and the deferred example are drawn in every viewport,
+    /// the shadertoy inputs are sized to the viewport.
+    pub fn set_viewports(&mut self, viewports: Option<Viewports>) -> Result<(), PipelineError> {
+        if let Some(viewports) = &viewports {
+            if let Some(&(_, camera)) = viewports
+                .iter()
+                .find(|&&(_, camera)| camera >= self.cameras.len())
+            {
+                return Err(PipelineError::CameraNotFound(camera));
+            }
+        }
+        self.viewports = viewports;
+        self.upload_camera();
+        Ok(())
+    }
+
+    /// Gets the regions and camera indices of the viewports the scene is drawn into, a single
+    /// one if it is not split.
+    fn viewports(&self) -> impl Iterator<Item = (ViewportRect, usize)> + '_ {
+        let single = match self.viewports {
+            Some(_) => None,
+            None => Some((ViewportRect::FULL, self.active_camera)),
+        };
+        self.viewports
+            .iter()
+            .flat_map(Viewports::iter)
+            .copied()
+            .chain(single)
+    }
+
+    /// Feeds the view projections of the cameras to the passes drawing in world space.
+    ///
+    /// What was accumulated through the previous cameras is cleared.
+    fn upload_camera(&mut self) {
+        self.reset_accumulation();
+        for (index, (rect, camera)) in self.viewports().enumerate() {
+            let mut camera = self.cameras[camera];
+            camera.set_viewport_size(rect.size_in(self.size));
+            self.gizmos
+                .set_view_proj(&self.queue, index, camera.view_proj());
+            self.deferred
+                .set_view_proj(&self.queue, index, camera.view_proj());
+        }
+    }
+
+    /// Gets the ratio between the physical and logical pixels of the window.
+    ///
+    /// 2D overlays size themselves in logical pixels and multiply by it, so they keep the same
+    /// apparent size on HiDPI displays. Headless states always have a scale factor of 1.0.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Applies a change of the window scale factor along the physical size it comes with.
+    pub async fn set_scale_factor(&mut self, scale_factor: f64, new_size: PhysicalSize<u32>) {
+        self.scale_factor = scale_factor;
+        self.resize(new_size).await;
+    }
+
+    /// Advances the clock of the shadertoy inputs by `dt`.
+    pub fn update(&mut self, dt: time::Duration) {
+        self.toy_uniforms.time += dt.as_secs_f32();
+    }
+
+    /// Tracks the cursor, which drives the shadertoy mouse input while the left button is held.
+    pub fn handle_cursor_moved(&mut self, position: PhysicalPosition<f64>) {
+        self.cursor = position;
+        if self.mouse_pressed {
+            let [x, y] = self.scene_cursor();
+            self.toy_uniforms.mouse[0] = x;
+            self.toy_uniforms.mouse[1] = y;
+        }
+    }
+
+    /// Presses or releases the left button of the shadertoy mouse input.
+    pub fn handle_left_button(&mut self, pressed: bool) {
+        if pressed {
+            log::debug!("Cursor ray: {:?}", self.cursor_ray());
+        }
+        self.mouse_pressed = pressed;
+        let [x, y] = self.scene_cursor();
+        self.toy_uniforms.mouse = if pressed {
+            [x, y, x, y]
+        } else {
+            let [x, y, click_x, click_y] = self.toy_uniforms.mouse;
+            [x, y, -click_x.abs(), -click_y.abs()]
+        };
+    }
+
+    /// Gets the world space ray through the cursor, to pick or drag what is under it.
+    pub fn cursor_ray(&self) -> Ray {
+        self.camera().screen_to_ray(
+            (self.cursor.x as f32, self.cursor.y as f32),
+            (self.size.width as f32, self.size.height as f32),
+        )
+    }
+
+    /// Gets the cursor in scene pixels measured from the bottom left corner, as in shadertoy.
+    fn scene_cursor(&self) -> [f32; 2] {
+        let x = self.cursor.x as f32 / self.size.width.max(1) as f32;
+        let y = 1.0 - self.cursor.y as f32 / self.size.height.max(1) as f32;
+        let size = self.scene_target.size;
+        [x * size.width as f32, y * size.height as f32]
+    }
+
+    /// Applies the action bound to a key press.
+    ///
+    /// `C` cycles the triangle color, `-` and `=` step the render scale, `F` toggles
+    /// the upscale filter, `G` toggles the gizmos, `S` toggles the srgb output, `D` toggles
+    /// the deferred shading example, `A` cycles the anti-aliasing modes, `W` toggles the
+    /// hidden-line overlay, `[` and `]` step the `intensity` parameter, `K` cycles the color
+    /// parameter, `V` cycles the cameras, `B` cycles the backgrounds, `T` toggles the frame
+    /// feedback, `U` toggles the accumulation and `N` cycles between a single viewport, two
+    /// side by side and a 2x2 grid, other keys are ignored.
+    pub async fn handle_key(&mut self, key: VirtualKeyCode) {
+        match key {
+            VirtualKeyCode::C => {
+                self.color_index = (self.color_index + 1) % TRIANGLE_COLORS.len();
+                self.set_triangle_color(TRIANGLE_COLORS[self.color_index]);
+            }
+            VirtualKeyCode::Minus | VirtualKeyCode::Equals => {
+                let step = if key == VirtualKeyCode::Minus {
+                    -0.25
+                } else {
+                    0.25
+                };
+                self.set_render_scale((self.render_scale + step).clamp(0.25, 2.0));
+            }
+            VirtualKeyCode::F => self.set_upscale_filter(match self.upscale_filter {
+                wgpu::FilterMode::Linear => wgpu::FilterMode::Nearest,
+                wgpu::FilterMode::Nearest => wgpu::FilterMode::Linear,
+            }),
+            VirtualKeyCode::G => self.gizmos.toggle(),
+            VirtualKeyCode::S => self.toggle_srgb_output().await,
+            VirtualKeyCode::D => self.deferred.toggle(),
+            VirtualKeyCode::A => {
+                let anti_aliasing = self.anti_aliasing.next();
+                match self.set_anti_aliasing(anti_aliasing) {
+                    Ok(()) => log::info!("Anti-aliasing: {:?}", anti_aliasing),
+                    Err(error) => log::warn!("Fail to set anti-aliasing: {:?}", error),
+                }
+            }
+            VirtualKeyCode::LBracket | VirtualKeyCode::RBracket => {
+                let step = if key == VirtualKeyCode::LBracket {
+                    -0.1
+                } else {
+                    0.1
+                };
+                let intensity = self.param("intensity").unwrap_or(1.0) + step;
+                let intensity = intensity.clamp(0.0, 4.0);
+                match self.set_param("intensity", intensity) {
+                    Ok(()) => log::info!("Param intensity: {:.1}", intensity),
+                    Err(error) => log::warn!("Fail to set param: {:?}", error),
+                }
+            }
+            VirtualKeyCode::K => {
+                self.param_color_index = (self.param_color_index + 1) % PARAM_COLORS.len();
+                self.set_param_color(PARAM_COLORS[self.param_color_index]);
+            }
+            VirtualKeyCode::V => {
+                let index = (self.active_camera + 1) % self.cameras.len();
+                match self.set_active_camera(index) {
+                    Ok(()) => log::info!("Camera: {}", index),
+                    Err(error) => log::warn!("Fail to switch camera: {:?}", error),
+                }
+            }
+            VirtualKeyCode::N => {
+                // Split views start with the active camera, then the next registered ones
+                let camera = |offset| (self.active_camera + offset) % self.cameras.len();
+                let viewports = match self.viewports.as_ref().map(|v| v.iter().count()) {
+                    None => Viewports::side_by_side([camera(0), camera(1)]).map(Some),
+                    Some(2) => {
+                        Viewports::grid([camera(0), camera(1), camera(2), camera(3)]).map(Some)
+                    }
+                    _ => Ok(None),
+                };
+                if let Err(error) = viewports.and_then(|viewports| self.set_viewports(viewports)) {
+                    log::warn!("Fail to split viewports: {:?}", error);
+                }
+            }
+            VirtualKeyCode::T => {
+                self.set_frame_feedback(!self.frame_feedback);
+            }
+            VirtualKeyCode::B => {
+                self.background_index = (self.background_index + 1) % BACKGROUNDS.len();
+                self.set_background(BACKGROUNDS[self.background_index]);
+            }
+            VirtualKeyCode::W => {
+                if let Err(error) = self.set_hidden_line(!self.hidden_line) {
+                    log::warn!("Fail to toggle hidden-line mode: {:?}", error);
+                }
+            }
+            VirtualKeyCode::U => {
+                self.set_accumulate(!self.accumulate);
+                log::info!("Accumulate: {}", self.accumulate);
+            }
+            _ => (),
+        }
+    }
+
+    /// Sets the ratio between the scene resolution and the surface resolution.
+    ///
+    /// The scene is rendered offscreen at the scaled resolution and then blitted to the surface.
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        if render_scale > 0.0 && render_scale != self.render_scale {
+            self.render_scale = render_scale;
+            self.recreate_scene_target();
+        }
+    }
+
+    /// Sets the filter used when blitting the scene to the surface.
+    pub fn set_upscale_filter(&mut self, upscale_filter: wgpu::FilterMode) {
+        if upscale_filter != self.upscale_filter {
+            self.upscale_filter = upscale_filter;
+            self.recreate_scene_target();
+        }
+    }
+
+    fn recreate_scene_target(&mut self) {
+        self.scene_target = SceneTarget::new(
+            &self.device,
+            &self.sc_config,
+            self.scene_format,
+            &self.extra_targets,
+            self.depth_format,
+            self.render_scale,
+            self.upscale_filter,
+            self.anti_aliasing.sample_count(),
+            &self.blit_bind_group_layout,
+        );
+        self.deferred.resize(&self.device, self.scene_target.size);
+        self.reset_accumulation();
+    }
+
+    /// Draws every frame over the scene of the previous ones instead of clearing it, for
+    /// progressive rendering.
+    ///
+    /// The scene color, the extra targets and the background are only drawn on the first frame
+    /// and after [`Self::reset_accumulation`], the depth is still cleared every frame. Resizing
+    /// the scene and changing the cameras or the viewports reset the accumulation.
+    pub fn set_accumulate(&mut self, accumulate: bool) {
+        self.accumulate = accumulate;
+        self.reset_accumulation();
+    }
+
+    /// Clears the accumulated scene on the next frame, such as when what it shows moves.
+    pub fn reset_accumulation(&mut self) {
+        self.clear_accumulation = true;
+    }
+
+    /// Switches the output between the srgb and linear variants of its format.
+    ///
+    /// The scene is still rendered in its original format, only the blit to the output
+    /// changes, so the gamma difference shows on screen. wgpu can not list the formats a
+    /// surface supports, switching assumes the surface accepts both variants.
+    pub async fn toggle_srgb_output(&mut self) {
+        let format = TextureFormat::from(self.sc_config.format);
+        let toggled = if format.is_srgb() {
+            format.remove_srgb_suffix()
+        } else {
+            format.add_srgb_suffix()
+        };
+        if toggled != format {
+            self.sc_config.format = toggled.into();
+            if let Some(window_id) = self.window_id {
+                self.context
+                    .resource()
+                    .set_surface_format(window_id, toggled)
+                    .await;
+            }
+            self.recreate_blit_pipeline();
+            self.recreate_feedback_target();
         }
     }
 
-    pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
-        if new_size.width != 0 && new_size.height != 0 {
-            self.sc_config.width = new_size.width;
-            self.sc_config.height = new_size.height;
-            self.surface.configure(&self.device, &self.sc_config);
+    /// Copies every frame into a texture the scene shaders sample the next frame, for trails and
+    /// other feedback effects.
+    ///
+    /// Shaders read it as a `texture_2d<f32>` at group 1 binding 2 with a filtering sampler at
+    /// binding 3, it covers the output and stays black while feedback is disabled. The scene is
+    /// blitted to it like to the frame, surfaces can only be rendered to.
+    pub fn set_frame_feedback(&mut self, frame_feedback: bool) {
+        if frame_feedback == self.frame_feedback {
+            return;
         }
+        self.frame_feedback = frame_feedback;
+        // Starts from black instead of the last frame copied before disabling
+        self.recreate_feedback_target();
+    }
+
+    fn recreate_feedback_target(&mut self) {
+        self.feedback = FeedbackTarget::new(&self.device, &self.sc_config);
+        self.toy_bind_groups = self
+            .toy_buffers
+            .iter()
+            .map(|toy_buffer| {
+                create_toy_bind_group(
+                    &self.device,
+                    &self.toy_bind_group_layout,
+                    (toy_buffer, &self.params_buffer),
+                    (&self.feedback.view, &self.feedback_sampler),
+                )
+            })
+            .collect();
+    }
+
+    fn recreate_blit_pipeline(&mut self) {
+        self.blit_pipeline = create_blit_pipeline(
+            &self.device,
+            &self.blit_bind_group_layout,
+            self.sc_config.format,
+            self.anti_aliasing,
+        );
+        self.error_pipeline = create_error_pipeline(&self.device, self.sc_config.format);
+    }
+
+    /// Sets what the scene color is filled with before the scene is drawn.
+    ///
+    /// A solid color only clears the scene color, the other backgrounds are drawn by a
+    /// fullscreen pass.
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+        self.queue.write_buffer(
+            &self.background_buffer,
+            0,
+            bytemuck::bytes_of(&BackgroundUniforms::new(background)),
+        );
+    }
+
+    /// Sets the RGB color of the triangle drawn by the color shader.
+    pub fn set_triangle_color(&mut self, color: [f32; 3]) {
+        self.queue.write_buffer(
+            &self.color_buffer,
+            0,
+            bytemuck::bytes_of(&ColorUniforms::new(color)),
+        );
+    }
+
+    /// Sets the float shader parameter `name`, one of `intensity`, `speed`, `scale` and `offset`.
+    ///
+    /// Shaders read the parameters by declaring the `ToyParams` struct of `toy.wgsl`.
+    pub fn set_param(&mut self, name: &str, value: f32) -> Result<(), PipelineError> {
+        let index = PARAM_NAMES
+            .iter()
+            .position(|&param| param == name)
+            .ok_or_else(|| PipelineError::UnknownParam(name.to_owned()))?;
+        self.params.values[index] = value;
+        self.write_params();
+        Ok(())
+    }
+
+    /// Gets the float shader parameter `name`, `None` if no parameter has the name.
+    pub fn param(&self, name: &str) -> Option<f32> {
+        PARAM_NAMES
+            .iter()
+            .position(|&param| param == name)
+            .map(|index| self.params.values[index])
     }
 
-    pub fn render(&mut self) {
-        // Get the current frame from swap chain
-        let frame = match self.surface.get_current_frame() {
-            Ok(frame) => frame.output,
-            Err(wgpu::SurfaceError::Lost) => return self.resize(self.size),
-            _ => return,
+    /// Sets the RGBA color shader parameter.
+    pub fn set_param_color(&mut self, color: [f32; 4]) {
+        self.params.color = color;
+        self.write_params();
+    }
+
+    fn write_params(&self) {
+        self.queue
+            .write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&self.params));
+    }
+
+    /// Renders and presents a frame, returning the metrics gathered while doing so.
+    ///
+    /// Empty stats are returned when no frame could be acquired.
+    pub async fn render(&mut self) -> FrameStats {
+        let begin = time::Instant::now();
+        let mut stats = FrameStats::default();
+        // Get the current frame from swap chain, a lost surface is reconfigured by the context
+        let window_id = match self.window_id {
+            Some(window_id) if !self.minimized => window_id,
+            _ => return stats,
+        };
+        self.fit_output_size(self.window_size);
+        self.wait_frames_in_flight(self.max_frames_in_flight - 1)
+            .await;
+        let frame = match self
+            .context
+            .resource()
+            .surface_current_texture(window_id)
+            .await
+        {
+            Some(frame) => frame,
+            None => return stats,
         };
         let frame_view = frame
             .texture
@@ -125,29 +1948,716 @@ impl PipelineState {
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("CGToy - Default Encoder"),
             });
-        // Do clear render pass
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("CGToy - ClearPass"),
+        self.encode_frame(&mut encoder, &frame_view, &mut stats);
+        self.clear_accumulation = false;
+        // Submit the commands, then present the frame they draw into
+        let index = self.context.resource().submit(iter::once(encoder.finish()));
+        self.frames_in_flight.push_back(index);
+        surface::present(frame);
+        stats.cpu_time = begin.elapsed();
+        stats
+    }
+
+    /// Waits until at most `max` frames submitted by [`Self::render`] are still in flight.
+    async fn wait_frames_in_flight(&mut self, max: usize) {
+        while self.frames_in_flight.len() > max {
+            let oldest = self.frames_in_flight.pop_front().unwrap();
+            self.context.resource().wait_for(oldest).await;
+        }
+    }
+
+    /// Renders a frame offscreen and reads it back, instead of presenting it.
+    ///
+    /// The pixels are sRGB encoded whatever the output format, so saved images look as intended:
+    /// a linear output, see [`Self::toggle_srgb_output`], is encoded on readback. Fails with
+    /// [`PipelineError::CaptureFailed`] if the frame can not be read back.
+    pub async fn capture(&mut self) -> Result<(image::RgbaImage, FrameStats), PipelineError> {
+        self.fit_output_size(self.window_size);
+        self.capture_frame().await
+    }
+
+    /// Renders a frame into an offscreen texture of the output size and reads it back.
+    async fn capture_frame(&mut self) -> Result<(image::RgbaImage, FrameStats), PipelineError> {
+        let begin = time::Instant::now();
+        let mut stats = FrameStats::default();
+        let (width, height) = (self.sc_config.width, self.sc_config.height);
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("CGToy - Texture(capture)"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.sc_config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Rows of a texture copy must be aligned
+        let row_pitch = TextureFormat::from(self.sc_config.format).row_pitch(width);
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CGToy - Buffer(capture)"),
+            size: u64::from(row_pitch.padded * height),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        // Create command encoder
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("CGToy - Capture Encoder"),
+            });
+        self.encode_frame(&mut encoder, &view, &mut stats);
+        self.clear_accumulation = false;
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(row_pitch.padded),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        // Submit the commands
+        let resource = self.context.resource();
+        let submission = resource.submit(iter::once(encoder.finish()));
+        stats.cpu_time = begin.elapsed();
+        // Map the buffer and wait for the copy, polling the device on a blocking thread
+        let slice = readback_buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        resource.wait_for(submission).await;
+        mapping.await.map_err(|_| PipelineError::CaptureFailed)?;
+        let mut pixels = row_pitch.unpad(&slice.get_mapped_range());
+        readback_buffer.unmap();
+        // Surfaces usually prefer a bgra format
+        if matches!(
+            self.sc_config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        // A linear output holds the colors unencoded
+        if !TextureFormat::from(self.sc_config.format).is_srgb() {
+            encode_srgb(&mut pixels);
+        }
+        let image = image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or(PipelineError::CaptureFailed)?;
+        Ok((image, stats))
+    }
+
+    /// Renders a frame offscreen at exactly `width` by `height` and reads it back as tightly
+    /// packed sRGB encoded RGBA8 rows, whatever the size of the window.
+    ///
+    /// The scene targets and the camera projection are resized to the capture, e.g. to record
+    /// 1080p frames from a small window. The render scale still applies to the scene. They
+    /// keep the capture size until the next frame rendered or captured at the window size, so
+    /// a recording at one size creates them once and accumulates across its frames.
+    pub async fn capture_at(&mut self, width: u32, height: u32) -> Result<Vec<u8>, PipelineError> {
+        if width == 0 || height == 0 {
+            return Err(PipelineError::EmptyCapture);
+        }
+        self.fit_output_size(PhysicalSize::new(width, height));
+        let (image, _) = self.capture_frame().await?;
+        Ok(image.into_raw())
+    }
+
+    fn encode_frame(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        stats: &mut FrameStats,
+    ) {
+        // Fill the frame in magenta instead of the scene while its shader is broken
+        if self.pipeline_error.is_some() {
+            let error_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - ErrorPass"),
                 color_attachments: &[wgpu::RenderPassColorAttachment {
-                    view: &frame_view,
+                    view: frame_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            });
+            let mut error_pass = CountedRenderPass::new(error_pass, stats);
+            error_pass.set_pipeline(&self.error_pipeline);
+            error_pass.draw(0..3, 0..1);
+            return;
+        }
+        let size = self.scene_target.size;
+        let size = PhysicalSize::new(size.width, size.height);
+        // With MSAA the scene is drawn into the multisampled targets and resolved into the others
+        let multisampled = self.scene_target.multisampled.as_ref();
+        let scene_depth_view =
+            multisampled.map_or(&self.scene_target.depth_view, |target| &target.depth_view);
+        let color_views: Vec<_> = iter::once(&self.scene_target.view)
+            .chain(&self.scene_target.extra_views)
+            .enumerate()
+            .map(|(index, view)| match multisampled {
+                Some(target) => (&target.color_views[index], Some(view)),
+                None => (view, None),
+            })
+            .collect();
+        // Draw the background into the scene color, resolved along the scene, unless the scene
+        // is drawn over the accumulated frames
+        let clear = !self.accumulate || self.clear_accumulation;
+        let extra_load = if clear {
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        let scene_load = match self.background {
+            _ if !clear => wgpu::LoadOp::Load,
+            Background::SolidColor(color) => wgpu::LoadOp::Clear(color),
+            _ => {
+                let background_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("CGToy - BackgroundPass"),
+                    color_attachments: &[wgpu::RenderPassColorAttachment {
+                        view: color_views[0].0,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: true,
+                        },
+                    }],
+                    depth_stencil_attachment: None,
+                });
+                let mut background_pass = CountedRenderPass::new(background_pass, stats);
+                background_pass.set_pipeline(&self.background_pipeline);
+                background_pass.set_bind_group(0, &self.background_bind_group);
+                background_pass.draw(0..3, 0..1);
+                wgpu::LoadOp::Load
+            }
+        };
+        // Multisampled depth can not be resolved, the deferred example gets a cleared depth
+        if multisampled.is_some() {
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - DepthClearPass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.scene_target.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(self.depth_clear),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+        }
+        // Draw every viewport in turn, the scene of each is depth tested on its own
+        for (index, (rect, _)) in self.viewports().enumerate() {
+            let viewport = rect.to_pixels(size);
+            // Upload the shadertoy inputs of the viewport
+            let (x, y, width, height) = viewport;
+            let toy_uniforms = ToyUniforms {
+                resolution: [width as f32, height as f32],
+                mouse: viewport_mouse(
+                    self.toy_uniforms.mouse,
+                    [x as f32, (size.height - y - height) as f32],
+                ),
+                origin: [x as f32, y as f32],
+                scene_resolution: [size.width as f32, size.height as f32],
+                ..self.toy_uniforms
+            };
+            self.queue.write_buffer(
+                &self.toy_buffers[index],
+                0,
+                bytemuck::bytes_of(&toy_uniforms),
+            );
+            // Do render pass into the scene color and every extra target, the first viewport
+            // clears them and every viewport clears the depth drawn by the previous one
+            {
+                let load = |clear| {
+                    if index == 0 {
+                        clear
+                    } else {
+                        wgpu::LoadOp::Load
+                    }
+                };
+                let attachment = |(view, resolve_target), load| wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target,
+                    ops: wgpu::Operations { load, store: true },
+                };
+                let color_attachments: Vec<_> =
+                    iter::once(attachment(color_views[0], load(scene_load)))
+                        .chain(
+                            color_views[1..]
+                                .iter()
+                                .map(|&views| attachment(views, load(extra_load))),
+                        )
+                        .collect();
+                let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("CGToy - ClearPass"),
+                    color_attachments: &color_attachments,
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: scene_depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(self.depth_clear),
+                            store: true,
+                        }),
+                        stencil_ops: None,
+                    }),
+                });
+                let mut render_pass = CountedRenderPass::new(render_pass, stats);
+                render_pass.set_viewport(viewport);
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.color_bind_group);
+                render_pass.set_bind_group(1, &self.toy_bind_groups[index]);
+                render_pass.draw(0..3, 0..1);
+            }
+            // Draw the hidden-line overlay and the gizmos over the scene color only
+            {
+                let gizmo_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("CGToy - GizmoPass"),
+                    color_attachments: &[wgpu::RenderPassColorAttachment {
+                        view: color_views[0].0,
+                        resolve_target: color_views[0].1,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
+                        },
+                    }],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: scene_depth_view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
                         }),
+                        stencil_ops: None,
+                    }),
+                });
+                let mut gizmo_pass = CountedRenderPass::new(gizmo_pass, stats);
+                gizmo_pass.set_viewport(viewport);
+                if let Some(wireframe_pipeline) = &self.wireframe_pipeline {
+                    gizmo_pass.push_debug_group("Wireframe");
+                    gizmo_pass.set_pipeline(wireframe_pipeline);
+                    gizmo_pass.set_bind_group(0, &self.color_bind_group);
+                    gizmo_pass.set_bind_group(1, &self.toy_bind_groups[index]);
+                    gizmo_pass.draw(0..3, 0..1);
+                    gizmo_pass.pop_debug_group();
+                }
+                gizmo_pass.push_debug_group("Gizmos");
+                self.gizmos.draw(&mut gizmo_pass, index);
+                gizmo_pass.pop_debug_group();
+            }
+            // Draw the deferred example into the G-buffer through the camera of the viewport
+            self.deferred.draw_geometry(
+                encoder,
+                (index, viewport),
+                &self.scene_target.depth_view,
+                stats,
+            );
+        }
+        // Shade the deferred example over the scene
+        self.deferred
+            .draw_lighting(encoder, &self.scene_target.view, stats);
+        // Upscale the scene to the frame
+        {
+            let blit_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - BlitPass"),
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: frame_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                         store: true,
                     },
                 }],
                 depth_stencil_attachment: None,
             });
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.draw(0..3, 0..1);
+            let mut blit_pass = CountedRenderPass::new(blit_pass, stats);
+            blit_pass.set_pipeline(&self.blit_pipeline);
+            blit_pass.set_bind_group(0, &self.scene_target.blit_bind_group);
+            blit_pass.draw(0..3, 0..1);
         }
-        // Submit the commands
-        self.queue.submit(iter::once(encoder.finish()));
+        // Keep the frame for the next one to sample
+        if self.frame_feedback {
+            let feedback_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - FeedbackPass"),
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: &self.feedback.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            });
+            let mut feedback_pass = CountedRenderPass::new(feedback_pass, stats);
+            feedback_pass.set_pipeline(&self.blit_pipeline);
+            feedback_pass.set_bind_group(0, &self.scene_target.blit_bind_group);
+            feedback_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+/// Moves the shadertoy mouse input of the whole scene to a viewport whose bottom left corner is
+/// at `origin`, the sign of the click position is kept.
+fn viewport_mouse(mouse: [f32; 4], origin: [f32; 2]) -> [f32; 4] {
+    let [x, y, click_x, click_y] = mouse;
+    [
+        x - origin[0],
+        y - origin[1],
+        click_x - origin[0].copysign(click_x),
+        click_y - origin[1].copysign(click_y),
+    ]
+}
+
+/// Encodes the color channels of linear RGBA8 `pixels` to sRGB in place, alpha stays linear.
+fn encode_srgb(pixels: &mut [u8]) {
+    let mut table = [0u8; 256];
+    for (value, encoded) in table.iter_mut().enumerate() {
+        let linear = value as f32 / 255.0;
+        let srgb = if linear <= 0.003_130_8 {
+            linear * 12.92
+        } else {
+            1.055 * linear.powf(1.0 / 2.4) - 0.055
+        };
+        *encoded = (srgb * 255.0).round() as u8;
+    }
+    for pixel in pixels.chunks_mut(4) {
+        for channel in &mut pixel[..3] {
+            *channel = table[usize::from(*channel)];
+        }
+    }
+}
+
+/// Creates the bind group of the shadertoy inputs and parameters, then of the previous frame.
+fn create_toy_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    (toy_buffer, params_buffer): (&wgpu::Buffer, &wgpu::Buffer),
+    (feedback_view, feedback_sampler): (&wgpu::TextureView, &wgpu::Sampler),
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("CGToy - BindGroup(toy)"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: toy_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(feedback_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(feedback_sampler),
+            },
+        ],
+    })
+}
+
+/// Creates a pipeline drawing the scene, the scene color is the first of the color targets.
+#[allow(clippy::too_many_arguments)]
+fn create_scene_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    (vertex_module, vertex_entry_point): (&wgpu::ShaderModule, &str),
+    (fragment_module, fragment_entry_point): (&wgpu::ShaderModule, &str),
+    scene_format: wgpu::TextureFormat,
+    extra_targets: &[ColorTargetState],
+    depth_format: wgpu::TextureFormat,
+    depth_compare: wgpu::CompareFunction,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    let color_targets: Vec<wgpu::ColorTargetState> = iter::once(scene_format.into())
+        .chain(extra_targets.iter().map(|&target| target.into()))
+        .collect();
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(color)"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: vertex_module,
+            entry_point: vertex_entry_point,
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: depth_format,
+            depth_write_enabled: true,
+            depth_compare,
+            stencil: Default::default(),
+            bias: Default::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fragment_module,
+            entry_point: fragment_entry_point,
+            targets: &color_targets,
+        }),
+    })
+}
+
+/// Creates the pipeline drawing the edges of the scene triangles as lines, biased towards the
+/// camera so they pass the depth test against the filled triangles.
+#[allow(clippy::too_many_arguments)]
+fn create_wireframe_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    (vertex_module, vertex_entry_point): (&wgpu::ShaderModule, &str),
+    scene_format: wgpu::TextureFormat,
+    depth_format: wgpu::TextureFormat,
+    depth_compare: wgpu::CompareFunction,
+    sample_count: u32,
+    reverse_z: bool,
+) -> wgpu::RenderPipeline {
+    let shader_wireframe = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: Some("CGToy - Shader(wireframe)"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/wireframe.wgsl").into()),
+    });
+    // Nearer is smaller depth, or greater depth with reverse-Z
+    let towards_camera = if reverse_z { 1.0 } else { -1.0 };
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(wireframe)"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: vertex_module,
+            entry_point: vertex_entry_point,
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Line,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: depth_format,
+            depth_write_enabled: false,
+            depth_compare,
+            stencil: Default::default(),
+            bias: wgpu::DepthBiasState {
+                constant: 2 * towards_camera as i32,
+                slope_scale: towards_camera,
+                clamp: 0.0,
+            },
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_wireframe,
+            entry_point: "main",
+            targets: &[scene_format.into()],
+        }),
+    })
+}
+
+/// Creates the pipeline drawing gradient and checker backgrounds into the scene color.
+fn create_background_pipeline(
+    device: &wgpu::Device,
+    background_bind_group_layout: &wgpu::BindGroupLayout,
+    scene_format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    let shader_background = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: Some("CGToy - Shader(background)"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/background.wgsl").into()),
+    });
+    let background_pipeline_layout =
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("CGToy - PipelineLayout(background)"),
+            bind_group_layouts: &[background_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(background)"),
+        layout: Some(&background_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_background,
+            entry_point: "main",
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_background,
+            entry_point: "main",
+            targets: &[scene_format.into()],
+        }),
+    })
+}
+
+/// Creates the pipeline filling a target of `format` in magenta, drawn in place of the scene
+/// while its shader is broken.
+fn create_error_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader_error = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: Some("CGToy - Shader(error)"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/error.wgsl").into()),
+    });
+    let error_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("CGToy - PipelineLayout(error)"),
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(error)"),
+        layout: Some(&error_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_error,
+            entry_point: "main",
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_error,
+            entry_point: "main",
+            targets: &[format.into()],
+        }),
+    })
+}
+
+/// Creates the pipeline blitting the scene to a target of `format`, smoothing it with FXAA if
+/// `anti_aliasing` asks for it.
+fn create_blit_pipeline(
+    device: &wgpu::Device,
+    blit_bind_group_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    anti_aliasing: AntiAliasing,
+) -> wgpu::RenderPipeline {
+    // Create blit shader module, FXAA binds the same inputs
+    let source = match anti_aliasing {
+        AntiAliasing::Fxaa => include_str!("shaders/fxaa.wgsl"),
+        _ => include_str!("shaders/blit.wgsl"),
+    };
+    let shader_blit = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: Some("CGToy - Shader(blit)"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let blit_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("CGToy - PipelineLayout(blit)"),
+        bind_group_layouts: &[blit_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(blit)"),
+        layout: Some(&blit_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_blit,
+            entry_point: "main",
+            buffers: &[],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_blit,
+            entry_point: "main",
+            targets: &[format.into()],
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_bindings(wgsl: &str) -> Result<(), PipelineError> {
+        let module = naga::front::wgsl::parse_str(wgsl).unwrap();
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .unwrap();
+        check_scene_bindings(&module, &info)
+    }
+
+    #[test]
+    fn bundled_fragment_shaders_match_the_scene_layout() {
+        assert_eq!(check_bindings(include_str!("shaders/toy.wgsl")), Ok(()));
+        assert_eq!(
+            check_bindings(include_str!("shaders/feedback.wgsl")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unknown_bindings_are_rejected() {
+        let wgsl = "
+            [[group(2), binding(0)]]
+            var t: texture_2d<f32>;
+
+            [[stage(fragment)]]
+            fn main() -> [[location(0)]] vec4<f32> {
+                return textureLoad(t, vec2<i32>(0, 0), 0);
+            }
+        ";
+        assert!(matches!(
+            check_bindings(wgsl),
+            Err(PipelineError::MismatchedBinding(_))
+        ));
     }
 }
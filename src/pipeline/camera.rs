@@ -0,0 +1,175 @@
+use glam::{Mat4, Vec3, Vec4};
+use winit::dpi::PhysicalSize;
+
+/// Perspective camera the scene, the gizmos and the deferred example are viewed through.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    /// Position of the camera.
+    pub eye: Vec3,
+    /// Point the camera looks at.
+    pub target: Vec3,
+    /// Direction of the top of the view.
+    pub up: Vec3,
+    /// Vertical field of view in radians.
+    pub fov_y: f32,
+    /// Ratio of the viewport width over its height.
+    pub aspect: f32,
+    /// Distance of the near plane.
+    pub near: f32,
+    /// Distance of the far plane.
+    pub far: f32,
+    /// Maps the near plane to a depth of 1.0 and the far plane to 0.0.
+    pub reverse_z: bool,
+}
+
+impl Camera {
+    /// Creates the camera looking at the origin from above the XZ plane, over a viewport of
+    /// `size`.
+    pub fn new(size: PhysicalSize<u32>, reverse_z: bool) -> Self {
+        Self {
+            eye: Vec3::new(6.0, 4.0, 8.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            fov_y: 45f32.to_radians(),
+            aspect: size.width.max(1) as f32 / size.height.max(1) as f32,
+            near: 0.1,
+            far: 100.0,
+            reverse_z,
+        }
+    }
+
+    /// Creates the debug camera looking straight down at the origin from high above, over a
+    /// viewport of `size`.
+    pub fn top_down(size: PhysicalSize<u32>, reverse_z: bool) -> Self {
+        Self {
+            eye: Vec3::new(0.0, 16.0, 0.0),
+            // Looking along the up axis needs another up direction
+            up: -Vec3::Z,
+            ..Self::new(size, reverse_z)
+        }
+    }
+
+    /// Fits the aspect ratio to a viewport of `size`, the rest of the camera is kept.
+    pub fn set_viewport_size(&mut self, size: PhysicalSize<u32>) {
+        self.aspect = size.width.max(1) as f32 / size.height.max(1) as f32;
+    }
+
+    /// Gets the matrix from world space to clip space.
+    pub fn view_proj(&self) -> Mat4 {
+        // Swapping the planes reverses the depth range
+        let (near, far) = if self.reverse_z {
+            (self.far, self.near)
+        } else {
+            (self.near, self.far)
+        };
+        let proj = Mat4::perspective_rh(self.fov_y, self.aspect, near, far);
+        let view = Mat4::look_at_rh(self.eye, self.target, self.up);
+        proj * view
+    }
+
+    /// Gets the world space ray through `pixel` of a viewport of `viewport_size`, measured from
+    /// the top left corner.
+    ///
+    /// Only the ratio of `pixel` to `viewport_size` matters, they just need the same unit:
+    /// winit reports cursor positions in physical pixels, so pair them with the physical size
+    /// of the window whatever the scale factor. The ray starts on the near plane and goes
+    /// through the far plane.
+    pub fn screen_to_ray(&self, pixel: (f32, f32), viewport_size: (f32, f32)) -> Ray {
+        let ndc_x = 2.0 * pixel.0 / viewport_size.0.max(1.0) - 1.0;
+        let ndc_y = 1.0 - 2.0 * pixel.1 / viewport_size.1.max(1.0);
+        let (near_depth, far_depth) = if self.reverse_z {
+            (1.0, 0.0)
+        } else {
+            (0.0, 1.0)
+        };
+        let inverse = self.view_proj().inverse();
+        let unproject = |depth| {
+            let point = inverse * Vec4::new(ndc_x, ndc_y, depth, 1.0);
+            point.truncate() / point.w
+        };
+        let near = unproject(near_depth);
+        let far = unproject(far_depth);
+        Ray {
+            origin: near,
+            direction: (far - near).normalize(),
+        }
+    }
+}
+
+/// Half line in world space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    /// Start of the ray.
+    pub origin: Vec3,
+    /// Unit direction of the ray.
+    pub direction: Vec3,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at the origin looking down -Z with a 90 degrees field of view over `size`.
+    fn camera(size: PhysicalSize<u32>, reverse_z: bool) -> Camera {
+        Camera {
+            eye: Vec3::ZERO,
+            target: -Vec3::Z,
+            fov_y: 90f32.to_radians(),
+            ..Camera::new(size, reverse_z)
+        }
+    }
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 1e-4,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn center_pixel_looks_forward() {
+        let camera = camera(PhysicalSize::new(100, 100), false);
+        let ray = camera.screen_to_ray((50.0, 50.0), (100.0, 100.0));
+        assert_near(ray.direction, -Vec3::Z);
+        // The ray starts on the near plane.
+        assert_near(ray.origin, Vec3::new(0.0, 0.0, -camera.near));
+    }
+
+    #[test]
+    fn corner_pixels_look_towards_their_corner() {
+        let camera = camera(PhysicalSize::new(100, 100), false);
+        let size = (100.0, 100.0);
+        let direction = |x: f32, y: f32| Vec3::new(x, y, -1.0).normalize();
+        let ray = |pixel| camera.screen_to_ray(pixel, size).direction;
+        // Pixels are measured from the top left corner, with y going down.
+        assert_near(ray((0.0, 0.0)), direction(-1.0, 1.0));
+        assert_near(ray((100.0, 0.0)), direction(1.0, 1.0));
+        assert_near(ray((0.0, 100.0)), direction(-1.0, -1.0));
+        assert_near(ray((100.0, 100.0)), direction(1.0, -1.0));
+    }
+
+    #[test]
+    fn reverse_z_gives_the_same_ray() {
+        let size = PhysicalSize::new(100, 100);
+        let (standard, reversed) = (camera(size, false), camera(size, true));
+        for pixel in [(50.0, 50.0), (10.0, 80.0), (100.0, 0.0)] {
+            let a = standard.screen_to_ray(pixel, (100.0, 100.0));
+            let b = reversed.screen_to_ray(pixel, (100.0, 100.0));
+            assert_near(a.origin, b.origin);
+            assert_near(a.direction, b.direction);
+        }
+    }
+
+    #[test]
+    fn wide_viewport_keeps_the_aspect() {
+        let camera = camera(PhysicalSize::new(200, 100), false);
+        let size = (200.0, 100.0);
+        // The vertical field of view is kept, the horizontal one widens with the aspect.
+        let right = camera.screen_to_ray((200.0, 50.0), size).direction;
+        assert_near(right, Vec3::new(2.0, 0.0, -1.0).normalize());
+        let top = camera.screen_to_ray((100.0, 0.0), size).direction;
+        assert_near(top, Vec3::new(0.0, 1.0, -1.0).normalize());
+    }
+}
@@ -0,0 +1,384 @@
+use super::{viewports::MAX_VIEWPORTS, CountedRenderPass, FrameStats};
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+
+/// Formats of the albedo, normal and position attachments of the G-buffer.
+const GBUFFER_FORMATS: [wgpu::TextureFormat; 3] = [
+    wgpu::TextureFormat::Rgba8Unorm,
+    wgpu::TextureFormat::Rgba16Float,
+    wgpu::TextureFormat::Rgba16Float,
+];
+
+/// Vertex of the deferred cube, mirrors the vertex input of `gbuffer.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct CubeVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    albedo: [f32; 3],
+}
+
+/// Albedo, normal and position textures written by the geometry pass.
+struct GBuffer {
+    _textures: Vec<wgpu::Texture>,
+    views: Vec<wgpu::TextureView>,
+    lighting_bind_group: wgpu::BindGroup,
+}
+
+impl GBuffer {
+    fn new(
+        device: &wgpu::Device,
+        size: wgpu::Extent3d,
+        lighting_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let textures: Vec<_> = GBUFFER_FORMATS
+            .iter()
+            .map(|&format| {
+                device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("CGToy - Texture(gbuffer)"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                })
+            })
+            .collect();
+        let views: Vec<_> = textures
+            .iter()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+        let entries: Vec<_> = views
+            .iter()
+            .enumerate()
+            .map(|(binding, view)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: wgpu::BindingResource::TextureView(view),
+            })
+            .collect();
+        let lighting_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(lighting)"),
+            layout: lighting_bind_group_layout,
+            entries: &entries,
+        });
+        Self {
+            _textures: textures,
+            views,
+            lighting_bind_group,
+        }
+    }
+}
+
+/// Deferred shading example, a cube is rendered into a G-buffer with multiple render targets
+/// and then lit by a fullscreen pass sampling every target.
+pub struct Deferred {
+    enabled: bool,
+    geometry_pipeline: wgpu::RenderPipeline,
+    lighting_pipeline: wgpu::RenderPipeline,
+    lighting_bind_group_layout: wgpu::BindGroupLayout,
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+    /// Camera uniform buffer and bind group of every viewport.
+    cameras: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+    gbuffer: GBuffer,
+}
+
+impl Deferred {
+    /// Creates the example for a scene of `size` texels, the lit cube is blended onto
+    /// `color_format` and depth tested against `depth_format` with `depth_compare`.
+    pub fn new(
+        device: &wgpu::Device,
+        size: wgpu::Extent3d,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        depth_compare: wgpu::CompareFunction,
+    ) -> Self {
+        // Build a cube standing on the grid, one color per face
+        let faces: [([f32; 3], [f32; 3]); 6] = [
+            ([1.0, 0.0, 0.0], [0.9, 0.3, 0.3]),
+            ([-1.0, 0.0, 0.0], [0.3, 0.9, 0.3]),
+            ([0.0, 1.0, 0.0], [0.3, 0.3, 0.9]),
+            ([0.0, -1.0, 0.0], [0.9, 0.9, 0.3]),
+            ([0.0, 0.0, 1.0], [0.3, 0.9, 0.9]),
+            ([0.0, 0.0, -1.0], [0.9, 0.3, 0.9]),
+        ];
+        let mut vertices = Vec::with_capacity(36);
+        for (normal, albedo) in faces {
+            // Two axes spanning the face
+            let axis = normal.iter().position(|&n| n != 0.0).unwrap();
+            let u = (axis + 1) % 3;
+            let v = (axis + 2) % 3;
+            for (du, dv) in [
+                (-1.0, -1.0),
+                (1.0, -1.0),
+                (1.0, 1.0),
+                (-1.0, -1.0),
+                (1.0, 1.0),
+                (-1.0, 1.0),
+            ] {
+                let mut position = normal;
+                position[u] = du;
+                position[v] = dv;
+                position[1] += 1.0;
+                vertices.push(CubeVertex {
+                    position,
+                    normal,
+                    albedo,
+                });
+            }
+        }
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(deferred cube)"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        // Create camera uniform buffers and bind groups, one per viewport
+        let camera_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(deferred camera)"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let cameras = (0..MAX_VIEWPORTS)
+            .map(|_| {
+                let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("CGToy - Buffer(deferred camera)"),
+                    contents: bytemuck::bytes_of(&Mat4::IDENTITY.to_cols_array_2d()),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("CGToy - BindGroup(deferred camera)"),
+                    layout: &camera_bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: camera_buffer.as_entire_binding(),
+                    }],
+                });
+                (camera_buffer, camera_bind_group)
+            })
+            .collect();
+        // Create geometry pipeline writing every G-buffer target
+        let geometry_shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(gbuffer)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/gbuffer.wgsl").into()),
+        });
+        let geometry_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("CGToy - PipelineLayout(gbuffer)"),
+                bind_group_layouts: &[&camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let geometry_targets: Vec<wgpu::ColorTargetState> = GBUFFER_FORMATS
+            .iter()
+            .map(|&format| format.into())
+            .collect();
+        let geometry_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("CGToy - Pipeline(gbuffer)"),
+            layout: Some(&geometry_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &geometry_shader,
+                entry_point: "main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<CubeVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x3,
+                        1 => Float32x3,
+                        2 => Float32x3
+                    ],
+                }],
+            },
+            primitive: Default::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: true,
+                depth_compare,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &geometry_shader,
+                entry_point: "main",
+                targets: &geometry_targets,
+            }),
+        });
+        // Create lighting pipeline reading the G-buffer
+        let lighting_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(lighting)"),
+                entries: &[0, 1, 2].map(|binding| wgpu::BindGroupLayoutEntry {
+                    binding,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                }),
+            });
+        let lighting_shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(lighting)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/lighting.wgsl").into()),
+        });
+        let lighting_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("CGToy - PipelineLayout(lighting)"),
+                bind_group_layouts: &[&lighting_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let lighting_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("CGToy - Pipeline(lighting)"),
+            layout: Some(&lighting_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &lighting_shader,
+                entry_point: "main",
+                buffers: &[],
+            },
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &lighting_shader,
+                entry_point: "main",
+                targets: &[wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+        });
+        let gbuffer = GBuffer::new(device, size, &lighting_bind_group_layout);
+        Self {
+            enabled: false,
+            geometry_pipeline,
+            lighting_pipeline,
+            lighting_bind_group_layout,
+            vertex_buffer,
+            vertex_count: vertices.len() as u32,
+            cameras,
+            gbuffer,
+        }
+    }
+
+    /// Enables the example if it is disabled and disables it otherwise.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Recreates the G-buffer for a scene of `size` texels.
+    pub fn resize(&mut self, device: &wgpu::Device, size: wgpu::Extent3d) {
+        self.gbuffer = GBuffer::new(device, size, &self.lighting_bind_group_layout);
+    }
+
+    /// Sets the view projection matrix the cube is drawn with in the viewport at `viewport`.
+    pub fn set_view_proj(&self, queue: &wgpu::Queue, viewport: usize, view_proj: Mat4) {
+        queue.write_buffer(
+            &self.cameras[viewport].0,
+            0,
+            bytemuck::bytes_of(&view_proj.to_cols_array_2d()),
+        );
+    }
+
+    /// Renders the cube into the G-buffer through the camera of the viewport at `index`, if the
+    /// example is enabled.
+    ///
+    /// The cube is depth tested against the scene already in `depth_view`. The first viewport
+    /// clears the G-buffer, the others draw next to it.
+    pub(super) fn draw_geometry(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        (index, viewport): (usize, (u32, u32, u32, u32)),
+        depth_view: &wgpu::TextureView,
+        stats: &mut FrameStats,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let load = if index == 0 {
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        // Group the passes in graphics debuggers
+        encoder.push_debug_group("Deferred");
+        // Do geometry pass into every G-buffer target at once
+        {
+            let color_attachments: Vec<_> = self
+                .gbuffer
+                .views
+                .iter()
+                .map(|view| wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load, store: true },
+                })
+                .collect();
+            let geometry_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - GeometryPass"),
+                color_attachments: &color_attachments,
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            let mut geometry_pass = CountedRenderPass::new(geometry_pass, stats);
+            geometry_pass.set_viewport(viewport);
+            geometry_pass.set_pipeline(&self.geometry_pipeline);
+            geometry_pass.set_bind_group(0, &self.cameras[index].1);
+            geometry_pass.set_vertex_buffer(0, &self.vertex_buffer);
+            geometry_pass.draw(0..self.vertex_count, 0..1);
+        }
+        encoder.pop_debug_group();
+    }
+
+    /// Blends the lighting of the G-buffer onto `color_view` once the geometry of every
+    /// viewport is drawn, if the example is enabled.
+    pub(super) fn draw_lighting(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        stats: &mut FrameStats,
+    ) {
+        if !self.enabled {
+            return;
+        }
+        encoder.push_debug_group("Deferred");
+        // Do lighting pass over the scene
+        {
+            let lighting_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - LightingPass"),
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            });
+            let mut lighting_pass = CountedRenderPass::new(lighting_pass, stats);
+            lighting_pass.set_pipeline(&self.lighting_pipeline);
+            lighting_pass.set_bind_group(0, &self.gbuffer.lighting_bind_group);
+            lighting_pass.draw(0..3, 0..1);
+        }
+        encoder.pop_debug_group();
+    }
+}
@@ -0,0 +1,224 @@
+use super::{viewports::MAX_VIEWPORTS, CountedRenderPass};
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+
+/// Color of the grid lines.
+const GRID_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+
+/// Vertex of a gizmo line, mirrors the vertex input of `gizmo.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct LineVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+/// World grid on the XZ plane and XYZ axes at the origin, drawn as depth tested lines.
+pub struct Gizmos {
+    visible: bool,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: wgpu::TextureFormat,
+    reverse_z: bool,
+    pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+    /// Camera uniform buffer and bind group of every viewport.
+    cameras: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+}
+
+impl Gizmos {
+    /// Creates gizmos with a grid of `2 * half_cells` cells of `spacing` per side.
+    ///
+    /// The axes are as long as half the grid and colored red, green and blue for X, Y and Z.
+    /// With `reverse_z` the lines pass the depth test when they are at a greater depth. The
+    /// targets have `sample_count` samples per texel.
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        reverse_z: bool,
+        half_cells: u32,
+        spacing: f32,
+    ) -> Self {
+        // Build the grid lines, then the axes so they win depth ties with the grid
+        let extent = half_cells as f32 * spacing;
+        let mut vertices = Vec::new();
+        let mut line = |from: [f32; 3], to: [f32; 3], color: [f32; 3]| {
+            vertices.push(LineVertex {
+                position: from,
+                color,
+            });
+            vertices.push(LineVertex {
+                position: to,
+                color,
+            });
+        };
+        for index in -(half_cells as i32)..=half_cells as i32 {
+            let offset = index as f32 * spacing;
+            line([offset, 0.0, -extent], [offset, 0.0, extent], GRID_COLOR);
+            line([-extent, 0.0, offset], [extent, 0.0, offset], GRID_COLOR);
+        }
+        line([0.0; 3], [extent, 0.0, 0.0], [1.0, 0.0, 0.0]);
+        line([0.0; 3], [0.0, extent, 0.0], [0.0, 1.0, 0.0]);
+        line([0.0; 3], [0.0, 0.0, extent], [0.0, 0.0, 1.0]);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(gizmos)"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        // Create camera uniform buffers and bind groups, one per viewport
+        let camera_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(gizmos)"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let cameras = (0..MAX_VIEWPORTS)
+            .map(|_| {
+                let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("CGToy - Buffer(gizmos camera)"),
+                    contents: bytemuck::bytes_of(&Mat4::IDENTITY.to_cols_array_2d()),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("CGToy - BindGroup(gizmos)"),
+                    layout: &camera_bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: camera_buffer.as_entire_binding(),
+                    }],
+                });
+                (camera_buffer, camera_bind_group)
+            })
+            .collect();
+        // Create line pipeline
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(gizmos)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/gizmo.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("CGToy - PipelineLayout(gizmos)"),
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = create_pipeline(
+            device,
+            &shader,
+            &pipeline_layout,
+            color_format,
+            depth_format,
+            sample_count,
+            reverse_z,
+        );
+        Self {
+            visible: true,
+            shader,
+            pipeline_layout,
+            color_format,
+            depth_format,
+            reverse_z,
+            pipeline,
+            vertex_buffer,
+            vertex_count: vertices.len() as u32,
+            cameras,
+        }
+    }
+
+    /// Shows the gizmos if they are hidden and hides them otherwise.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Recreates the pipeline for targets of `sample_count` samples per texel.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline = create_pipeline(
+            device,
+            &self.shader,
+            &self.pipeline_layout,
+            self.color_format,
+            self.depth_format,
+            sample_count,
+            self.reverse_z,
+        );
+    }
+
+    /// Sets the view projection matrix the lines are drawn with in the viewport at `viewport`.
+    pub fn set_view_proj(&self, queue: &wgpu::Queue, viewport: usize, view_proj: Mat4) {
+        queue.write_buffer(
+            &self.cameras[viewport].0,
+            0,
+            bytemuck::bytes_of(&view_proj.to_cols_array_2d()),
+        );
+    }
+
+    /// Draws the gizmos through the camera of the viewport at `viewport` if they are visible.
+    pub(super) fn draw<'a>(&'a self, pass: &mut CountedRenderPass<'a, '_>, viewport: usize) {
+        if self.visible {
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.cameras[viewport].1);
+            pass.set_vertex_buffer(0, &self.vertex_buffer);
+            pass.draw_lines(0..self.vertex_count, 0..1);
+        }
+    }
+}
+
+/// Creates the line pipeline drawing into targets of the given formats and sample count.
+fn create_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    pipeline_layout: &wgpu::PipelineLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: wgpu::TextureFormat,
+    sample_count: u32,
+    reverse_z: bool,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(gizmos)"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<LineVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3],
+            }],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::LineList,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: depth_format,
+            depth_write_enabled: true,
+            depth_compare: if reverse_z {
+                wgpu::CompareFunction::GreaterEqual
+            } else {
+                wgpu::CompareFunction::LessEqual
+            },
+            stencil: Default::default(),
+            bias: Default::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "main",
+            targets: &[color_format.into()],
+        }),
+    })
+}
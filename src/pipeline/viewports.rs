@@ -0,0 +1,169 @@
+use super::PipelineError;
+use winit::dpi::PhysicalSize;
+
+/// Most viewports the scene can be split into.
+pub const MAX_VIEWPORTS: usize = 4;
+
+/// Region of the scene in fractions of its size, `x` and `y` locate its top left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewportRect {
+    /// The whole scene.
+    pub const FULL: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    /// Gets the region in a target of `size` as `(x, y, width, height)` in pixels.
+    ///
+    /// The region is clamped to the target and covers at least one pixel, as viewports and
+    /// scissor rectangles must.
+    pub fn to_pixels(self, size: PhysicalSize<u32>) -> (u32, u32, u32, u32) {
+        let (width, height) = (size.width.max(1), size.height.max(1));
+        let edge = |fraction: f32, extent: u32| {
+            ((fraction.clamp(0.0, 1.0) * extent as f32).round() as u32).min(extent)
+        };
+        let left = edge(self.x, width).min(width - 1);
+        let top = edge(self.y, height).min(height - 1);
+        let right = edge(self.x + self.width, width).max(left + 1);
+        let bottom = edge(self.y + self.height, height).max(top + 1);
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Gets the size of the region in a target of `size`, which the camera aspect ratio of
+    /// the viewport is fitted to.
+    pub fn size_in(self, size: PhysicalSize<u32>) -> PhysicalSize<u32> {
+        let (_, _, width, height) = self.to_pixels(size);
+        PhysicalSize::new(width, height)
+    }
+}
+
+/// Split of the scene into viewports, each drawn through one of the registered cameras.
+///
+/// The viewports are drawn in order within the same frame, each starting from a cleared depth
+/// buffer so the scene in one does not occlude the scene in another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Viewports(Vec<(ViewportRect, usize)>);
+
+impl Viewports {
+    /// Creates viewports from pairs of a region and the index of the camera drawing it.
+    ///
+    /// Fails with [`PipelineError::ViewportCount`] if there are no viewports or more than
+    /// [`MAX_VIEWPORTS`].
+    pub fn new(viewports: Vec<(ViewportRect, usize)>) -> Result<Self, PipelineError> {
+        if viewports.is_empty() || viewports.len() > MAX_VIEWPORTS {
+            return Err(PipelineError::ViewportCount(viewports.len()));
+        }
+        Ok(Self(viewports))
+    }
+
+    /// Splits the scene into a left and a right half, drawn through the cameras in order, as
+    /// by [`Self::new`].
+    pub fn side_by_side(cameras: [usize; 2]) -> Result<Self, PipelineError> {
+        Self::new(
+            cameras
+                .iter()
+                .enumerate()
+                .map(|(index, &camera)| {
+                    let rect = ViewportRect {
+                        x: index as f32 * 0.5,
+                        width: 0.5,
+                        ..ViewportRect::FULL
+                    };
+                    (rect, camera)
+                })
+                .collect(),
+        )
+    }
+
+    /// Splits the scene into a 2x2 grid, drawn through the cameras in row order from the top
+    /// left, as by [`Self::new`].
+    pub fn grid(cameras: [usize; 4]) -> Result<Self, PipelineError> {
+        Self::new(
+            cameras
+                .iter()
+                .enumerate()
+                .map(|(index, &camera)| {
+                    let rect = ViewportRect {
+                        x: (index % 2) as f32 * 0.5,
+                        y: (index / 2) as f32 * 0.5,
+                        width: 0.5,
+                        height: 0.5,
+                    };
+                    (rect, camera)
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets the regions and camera indices of the viewports, in drawing order.
+    pub fn iter(&self) -> impl Iterator<Item = &(ViewportRect, usize)> {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: PhysicalSize<u32> = PhysicalSize::new(100, 50);
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> ViewportRect {
+        ViewportRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn to_pixels_rounds_to_the_nearest_pixel() {
+        assert_eq!(ViewportRect::FULL.to_pixels(SIZE), (0, 0, 100, 50));
+        assert_eq!(rect(0.5, 0.5, 0.5, 0.5).to_pixels(SIZE), (50, 25, 50, 25));
+        // 33.3 rounds down and 66.6 up, the edges are rounded rather than the size.
+        assert_eq!(
+            rect(0.333, 0.0, 0.333, 1.0).to_pixels(SIZE),
+            (33, 0, 34, 50)
+        );
+    }
+
+    #[test]
+    fn to_pixels_clamps_to_the_target() {
+        assert_eq!(rect(-0.5, -0.5, 1.0, 1.0).to_pixels(SIZE), (0, 0, 50, 25));
+        assert_eq!(rect(0.5, 0.5, 1.0, 1.0).to_pixels(SIZE), (50, 25, 50, 25));
+        // A region past the edge keeps its last pixel.
+        assert_eq!(rect(1.5, 1.5, 0.5, 0.5).to_pixels(SIZE), (99, 49, 1, 1));
+    }
+
+    #[test]
+    fn to_pixels_covers_at_least_one_pixel() {
+        assert_eq!(rect(0.5, 0.5, 0.0, 0.0).to_pixels(SIZE), (50, 25, 1, 1));
+        assert_eq!(rect(0.2, 0.2, -0.1, -0.1).to_pixels(SIZE), (20, 10, 1, 1));
+        // An empty target is treated as a single pixel.
+        let empty = PhysicalSize::new(0, 0);
+        assert_eq!(ViewportRect::FULL.to_pixels(empty), (0, 0, 1, 1));
+    }
+
+    #[test]
+    fn new_rejects_invalid_counts() {
+        assert_eq!(
+            Viewports::new(Vec::new()),
+            Err(PipelineError::ViewportCount(0))
+        );
+        let viewports = vec![(ViewportRect::FULL, 0); MAX_VIEWPORTS + 1];
+        assert_eq!(
+            Viewports::new(viewports),
+            Err(PipelineError::ViewportCount(MAX_VIEWPORTS + 1))
+        );
+        assert!(Viewports::new(vec![(ViewportRect::FULL, 0)]).is_ok());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::pipeline::{PipelineError, PipelineState};
+use std::{fs, path::Path, time::Duration};
+use winit::{dpi::PhysicalSize, event::VirtualKeyCode};
+
+/// A key press replayed once the scripted time reaches `time`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScriptedInput {
+    pub time: Duration,
+    pub key: VirtualKeyCode,
+}
+
+/// Error of a recording.
+#[derive(Debug)]
+pub enum RecordError {
+    /// A frame could not be saved.
+    Image(image::ImageError),
+    /// The frame at the index could not be captured.
+    Capture { frame: u32, error: PipelineError },
+}
+
+impl From<image::ImageError> for RecordError {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+impl From<std::io::Error> for RecordError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Image(error.into())
+    }
+}
+
+/// Drives a [`PipelineState`] with scripted inputs at a fixed timestep and saves every frame.
+///
+/// Frame `n` covers the time `[n * dt, (n + 1) * dt)`, the inputs falling before the end of
+/// that range are applied before it is rendered, so the same script always yields the same
+/// frames and they can be diffed against golden images. The state is updated by `dt` before
+/// every frame.
+#[derive(Clone, Debug)]
+pub struct FrameRecorder {
+    dt: Duration,
+    inputs: Vec<ScriptedInput>,
+    size: Option<PhysicalSize<u32>>,
+}
+
+impl FrameRecorder {
+    /// Creates a recorder, the inputs are replayed in time order whatever their order here.
+    pub fn new(dt: Duration, mut inputs: Vec<ScriptedInput>) -> Self {
+        inputs.sort_by_key(|input| input.time);
+        Self {
+            dt,
+            inputs,
+            size: None,
+        }
+    }
+
+    /// Records the frames at `size` instead of the size of the state.
+    pub fn with_size(mut self, size: PhysicalSize<u32>) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Records `frames` frames into `dir` as `frame_0000.png`, `frame_0001.png`, ...
+    pub async fn record(
+        &self,
+        state: &mut PipelineState,
+        frames: u32,
+        dir: &Path,
+    ) -> Result<(), RecordError> {
+        fs::create_dir_all(dir)?;
+        let mut inputs = self.inputs.iter().peekable();
+        for frame in 0..frames {
+            let frame_end = self.dt * (frame + 1);
+            while let Some(input) = inputs.next_if(|input| input.time < frame_end) {
+                state.handle_key(input.key).await;
+            }
+            state.update(self.dt);
+            let capture = |error| RecordError::Capture { frame, error };
+            let image = match self.size {
+                Some(size) => image::RgbaImage::from_raw(
+                    size.width,
+                    size.height,
+                    state
+                        .capture_at(size.width, size.height)
+                        .await
+                        .map_err(capture)?,
+                )
+                .unwrap(),
+                None => state.capture().await.map_err(capture)?.0,
+            };
+            image.save(dir.join(format!("frame_{:04}.png", frame)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Error of an image comparison.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CompareError {
+    /// An image is `actual` bytes long instead of the `expected` bytes of its RGBA8 size.
+    SizeMismatch { expected: usize, actual: usize },
+}
+
+/// Differences between two RGBA8 images of the same size, see [`compare_images`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageDiff {
+    pub width: u32,
+    pub height: u32,
+    /// Difference of every pixel in row order, the largest over its channels.
+    pub pixel_diffs: Vec<f32>,
+    /// Largest difference of a pixel.
+    pub max: f32,
+    /// Mean difference over the pixels.
+    pub mean: f32,
+    /// Whether each pixel differs by more than the tolerance, in row order.
+    pub mask: Vec<bool>,
+}
+
+impl ImageDiff {
+    /// Gets the number of pixels differing by more than the tolerance.
+    pub fn differing_pixels(&self) -> usize {
+        self.mask.iter().filter(|&&differs| differs).count()
+    }
+
+    /// Whether every pixel is within the tolerance.
+    pub fn matches(&self) -> bool {
+        !self.mask.contains(&true)
+    }
+
+    /// Saves the differences as an image, pixels beyond the tolerance are red and the others
+    /// gray as bright as their difference.
+    pub fn save(&self, path: &Path) -> image::ImageResult<()> {
+        let pixels = self
+            .pixel_diffs
+            .iter()
+            .zip(&self.mask)
+            .flat_map(|(&diff, &differs)| {
+                let value = (diff * 255.0).round() as u8;
+                if differs {
+                    [255, 0, 0, 255]
+                } else {
+                    [value, value, value, 255]
+                }
+            })
+            .collect();
+        image::RgbaImage::from_raw(self.width, self.height, pixels)
+            .unwrap()
+            .save(path)
+    }
+}
+
+/// Compares two sRGB encoded RGBA8 images of `width` by `height`, such as the captures of a
+/// recorded frame and of its golden image.
+///
+/// The colors are decoded to linear before being diffed, so a difference means the same light
+/// in the dark and bright parts of the image. Channels differ by `[0, 1]` and alpha is compared
+/// as is, a pixel differs by its largest channel difference and is marked in the mask beyond
+/// `tolerance`. Fails if an image is not `width` by `height`.
+pub fn compare_images(
+    a: &[u8],
+    b: &[u8],
+    width: u32,
+    height: u32,
+    tolerance: f32,
+) -> Result<ImageDiff, CompareError> {
+    let expected = width as usize * height as usize * 4;
+    if let Some(image) = [a, b].iter().find(|image| image.len() != expected) {
+        return Err(CompareError::SizeMismatch {
+            expected,
+            actual: image.len(),
+        });
+    }
+    let mut linear = [0f32; 256];
+    for (value, decoded) in linear.iter_mut().enumerate() {
+        let srgb = value as f32 / 255.0;
+        *decoded = if srgb <= 0.040_45 {
+            srgb / 12.92
+        } else {
+            ((srgb + 0.055) / 1.055).powf(2.4)
+        };
+    }
+    let pixel_diffs: Vec<f32> = a
+        .chunks_exact(4)
+        .zip(b.chunks_exact(4))
+        .map(|(a, b)| {
+            let color = (0..3)
+                .map(|channel| {
+                    (linear[usize::from(a[channel])] - linear[usize::from(b[channel])]).abs()
+                })
+                .fold(0.0, f32::max);
+            let alpha = (f32::from(a[3]) - f32::from(b[3])).abs() / 255.0;
+            color.max(alpha)
+        })
+        .collect();
+    let max = pixel_diffs.iter().copied().fold(0.0, f32::max);
+    let mean = pixel_diffs.iter().sum::<f32>() / pixel_diffs.len().max(1) as f32;
+    let mask = pixel_diffs.iter().map(|&diff| diff > tolerance).collect();
+    Ok(ImageDiff {
+        width,
+        height,
+        pixel_diffs,
+        max,
+        mean,
+        mask,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets a `width` by `height` image filled with a single RGBA8 color.
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        color.repeat((width * height) as usize)
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = solid(2, 2, [10, 128, 250, 255]);
+        let diff = compare_images(&image, &image, 2, 2, 0.0).unwrap();
+        assert_eq!(diff.max, 0.0);
+        assert_eq!(diff.mean, 0.0);
+        assert_eq!(diff.differing_pixels(), 0);
+        assert!(diff.matches());
+    }
+
+    #[test]
+    fn diff_within_tolerance_matches() {
+        let a = solid(2, 1, [128, 128, 128, 255]);
+        let b = solid(2, 1, [129, 128, 128, 255]);
+        let diff = compare_images(&a, &b, 2, 1, 0.01).unwrap();
+        assert!(diff.max > 0.0 && diff.max <= 0.01);
+        assert!(diff.matches());
+    }
+
+    #[test]
+    fn diff_beyond_tolerance_is_masked() {
+        let a = solid(2, 1, [0, 0, 0, 255]);
+        let mut b = a.clone();
+        b[4..].copy_from_slice(&[255, 255, 255, 255]);
+        let diff = compare_images(&a, &b, 2, 1, 0.01).unwrap();
+        assert_eq!(diff.max, 1.0);
+        assert_eq!(diff.mean, 0.5);
+        assert_eq!(diff.mask, vec![false, true]);
+        assert_eq!(diff.differing_pixels(), 1);
+        assert!(!diff.matches());
+    }
+
+    #[test]
+    fn size_mismatch_is_rejected() {
+        let a = solid(2, 2, [0; 4]);
+        let b = solid(2, 1, [0; 4]);
+        assert_eq!(
+            compare_images(&a, &b, 2, 2, 0.0),
+            Err(CompareError::SizeMismatch {
+                expected: 16,
+                actual: 8,
+            })
+        );
+    }
+}
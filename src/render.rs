@@ -1,4 +1,8 @@
+#![allow(dead_code)]
+
 // mod _render_context;
-mod context;
+pub mod context;
+mod loader;
+mod ping_pong;
 mod render_graph;
 mod resources;
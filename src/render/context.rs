@@ -1,9 +1,48 @@
 use self::{
-    buffer::{BufferDescriptor, BufferId, BufferInitDescriptor},
+    bind_group::{
+        BindGroupDescriptor, BindGroupError, BindGroupId, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindGroupLayoutId, BindingResource, BindingType, TextureSampleType,
+    },
+    buffer::{
+        BufferDescriptor, BufferError, BufferId, BufferInitDescriptor, BufferUsages, DynamicBuffer,
+    },
+    color_target::ColorTargetState,
+    compute_pass::{ComputeCommand, ComputeLimits, ComputePassError},
+    convert::TextureConverter,
+    dump::DumpError,
+    frame_scope::FrameScope,
+    mipmap::{MipmapError, MipmapGenerator, MipmapPath},
+    preset::{DescriptorPresets, Preset},
+    quad::{QuadTarget, Rect, TexturedQuad},
+    render_pass::{
+        sort_transparent, DepthPrepass, Draw, LoadOp, Operations, RenderCommand,
+        RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+        RenderPassError,
+    },
     sampler::{SamplerDescriptor, SamplerId},
-    texture::TextureId,
+    shader::{ShaderError, ShaderModuleId},
+    submission::{SubmissionIndex, SubmissionTracker},
+    surface::{LatencyMode, PresentMode, SurfaceError},
+    texture::{
+        Extent3d, Origin3d, TexelCopyInfo, TextureAspect, TextureDescriptor, TextureDimension,
+        TextureError, TextureFormat, TextureFormatFeatures, TextureId, TextureUsages,
+        TextureViewDescriptor, TextureViewId,
+    },
+    texture_cache::{EvictionCallback, TextureCache},
+    types::Color,
+};
+use glam::Vec3;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    iter,
+    num::{NonZeroU32, NonZeroU64},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
-use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 use wgpu::util::DeviceExt;
 use winit::{
@@ -11,10 +50,74 @@ use winit::{
     window::{Window, WindowId},
 };
 
+/// Declares a resource id, a copyable handle wrapping a [`uuid::Uuid`] from
+/// [`next_resource_uuid`].
+macro_rules! resource_id {
+    ($name:ident) => {
+        ///
+        #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+        pub struct $name(uuid::Uuid);
+
+        impl $name {
+            ///
+            pub fn new() -> Self {
+                Self($crate::render::context::next_resource_uuid())
+            }
+        }
+    };
+}
+
+/// Gets the uuid of a new resource id, random unless the `deterministic-ids` feature is
+/// enabled.
+#[cfg(not(feature = "deterministic-ids"))]
+fn next_resource_uuid() -> uuid::Uuid {
+    uuid::Uuid::new_v4()
+}
+
+/// Gets the uuid of a new resource id from a counter shared by every id type, the first is
+/// `00000000-0000-0000-0000-000000000001`.
+///
+/// A run creating its resources in the same order gets the same ids, so logs and snapshots can
+/// be compared between runs. Resources created from several threads are numbered in whatever
+/// order the threads get there.
+#[cfg(feature = "deterministic-ids")]
+fn next_resource_uuid() -> uuid::Uuid {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    uuid::Uuid::from_u128(u128::from(NEXT_ID.fetch_add(1, Ordering::Relaxed)))
+}
+
+/// Number of attempts at requesting a device, drivers may fail transiently while initializing.
+const DEVICE_REQUEST_ATTEMPTS: u32 = 3;
+/// Delay before the second attempt at requesting a device, doubled after every failure.
+const DEVICE_REQUEST_BACKOFF: Duration = Duration::from_millis(50);
+/// Backends the instance is created with, and the adapters are enumerated from.
+const BACKENDS: wgpu::Backends = wgpu::Backends::PRIMARY;
+
+pub mod bind_group;
 pub mod buffer;
+pub mod color_target;
+pub mod compute_pass;
+mod convert;
+pub mod dump;
+pub mod features;
+pub mod frame_scope;
+pub mod immediate;
+#[cfg(feature = "ktx2")]
+pub mod ktx2;
+pub mod mipmap;
+pub mod preprocess;
+pub mod preset;
+pub mod quad;
+pub mod render_pass;
+pub mod safe_mode;
 pub mod sampler;
+pub mod shader;
+pub mod submission;
+pub mod surface;
 pub mod texture;
+pub mod texture_cache;
 pub mod types;
+pub mod vertex;
 
 ///
 #[derive(Clone)]
@@ -24,110 +127,349 @@ pub struct RenderContext {
 }
 
 impl RenderContext {
-    ///
-    pub async fn new(desc: RenderContextDescriptor<'_>) -> Self {
+    /// Creates a context, failing if no adapter is found, if it misses a required downlevel
+    /// capability or if every device request attempt fails.
+    pub async fn new(desc: RenderContextDescriptor<'_>) -> Result<Self, RenderContextError> {
         // Creates instance.
-        let instance = wgpu::Instance::new(wgpu::Backends::PRIMARY);
+        let instance = wgpu::Instance::new(BACKENDS);
         // Creates surface if window is provided.
-        let surface = match desc.window {
-            Some(window) => Some(unsafe { instance.create_surface(window) }),
-            None => None,
-        };
+        let surface = desc
+            .window
+            .map(|window| unsafe { instance.create_surface(window) });
         // Requesst adapter.
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: match surface {
-                    Some(ref surface) => Some(surface),
-                    None => None,
-                },
-            })
-            .await
-            .unwrap_or_else(|| panic!("Fail to request suitable adapter!"));
-        // Requests device and queue.
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: desc.features,
-                    limits: Default::default(),
-                },
-                None,
-            )
-            .await
-            .unwrap_or_else(|err| panic!("Fail to request device: {}", err));
+        let adapter = if desc.force_fallback_adapter {
+            fallback_adapter(&instance, surface.as_ref()).ok_or(RenderContextError::NoAdapter)?
+        } else {
+            request_adapter(&instance, surface.as_ref())
+                .await
+                .ok_or(RenderContextError::NoAdapter)?
+        };
+        // Checks the downlevel capabilities, compute silently does nothing on GL without them.
+        let missing = desc.downlevel_flags - adapter.get_downlevel_properties().flags;
+        if !missing.is_empty() {
+            return Err(RenderContextError::MissingDownlevelFlags(missing));
+        }
+        // Backend validation layers are toggled by the build profile of wgpu.
+        match desc.validation {
+            ValidationMode::Full if !cfg!(debug_assertions) => {
+                log::warn!("Backend validation layers are only enabled in debug builds.")
+            }
+            ValidationMode::None if cfg!(debug_assertions) => {
+                log::warn!("Backend validation layers are always enabled in debug builds.")
+            }
+            _ => (),
+        }
+        // Adds the optional features the adapter supports, then drops the features known to
+        // break the driver in safe mode.
+        let features = desc.features | (desc.optional_features & adapter.features());
+        let features = if desc.safe_mode {
+            safe_mode::filter_features(&adapter.get_info(), features)
+        } else {
+            features
+        };
+        // Requests device and queue, retrying with a backoff on failure.
+        let device_desc = wgpu::DeviceDescriptor {
+            label: desc.validation.label("CGToy - Device"),
+            features,
+            limits: Default::default(),
+        };
+        #[cfg(feature = "trace")]
+        let trace_path = desc.trace_path.as_deref();
+        #[cfg(not(feature = "trace"))]
+        let trace_path = None;
+        let mut backoff = DEVICE_REQUEST_BACKOFF;
+        let mut attempt = 1;
+        let (device, queue) = loop {
+            match adapter.request_device(&device_desc, trace_path).await {
+                Ok(device_queue) => break device_queue,
+                Err(err) if attempt < DEVICE_REQUEST_ATTEMPTS => {
+                    log::warn!(
+                        "Fail to request device (attempt {}/{}): {}, retrying in {:?}.",
+                        attempt,
+                        DEVICE_REQUEST_ATTEMPTS,
+                        err,
+                        backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(err) => return Err(RenderContextError::FailedToRequestDevice(err)),
+            }
+        };
         // Save context shared data
         let ctx_data = ContextSharedData {
             instance: Arc::new(instance),
             adapter: Arc::new(adapter),
             device: Arc::new(device),
             queue: Arc::new(queue),
+            latency_mode: desc.latency_mode,
+            safe_mode: desc.safe_mode,
+            validation: desc.validation,
+            debug_names: desc.debug_names,
+            label_counter: Default::default(),
+            submissions: Default::default(),
         };
+        Ok(Self::from_shared_data(ctx_data))
+    }
+
+    /// Wraps a wgpu context owned by another application instead of creating one.
+    ///
+    /// The context uses the default latency and validation modes and does not generate debug
+    /// names, the device keeps the features and limits it was requested with.
+    pub fn from_existing(
+        instance: Arc<wgpu::Instance>,
+        adapter: Arc<wgpu::Adapter>,
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+    ) -> Self {
+        Self::from_shared_data(ContextSharedData {
+            instance,
+            adapter,
+            device,
+            queue,
+            latency_mode: Default::default(),
+            safe_mode: false,
+            validation: Default::default(),
+            debug_names: false,
+            label_counter: Default::default(),
+            submissions: Default::default(),
+        })
+    }
 
+    fn from_shared_data(ctx_data: ContextSharedData) -> Self {
         Self {
             ctx_data: ctx_data.clone(),
             resource: ResourceContext {
                 ctx_data,
                 surfaces: Default::default(),
                 samplers: Default::default(),
+                sampler_cache: Default::default(),
                 buffers: Default::default(),
+                textures: Default::default(),
+                texture_views: Default::default(),
+                texture_cache: Default::default(),
+                bind_group_layouts: Default::default(),
+                bind_groups: Default::default(),
+                shader_modules: Default::default(),
+                quad: Default::default(),
+                converter: Default::default(),
+                mipmapper: Default::default(),
+                presets: Default::default(),
             },
         }
     }
+
+    ///
+    pub fn resource(&self) -> &ResourceContext {
+        &self.resource
+    }
+
+    /// Begins a frame, the transient resources created through the returned scope are removed
+    /// when it ends.
+    pub fn begin_frame(&self) -> FrameScope {
+        FrameScope::new(self.resource.clone())
+    }
+
+    /// Gets the device, for code recording its own commands next to the resource context.
+    pub fn device(&self) -> &Arc<wgpu::Device> {
+        &self.ctx_data.device
+    }
+
+    /// Gets the queue commands are submitted to.
+    pub fn queue(&self) -> &Arc<wgpu::Queue> {
+        &self.ctx_data.queue
+    }
+}
+
+/// Requests a high performance adapter able to present to `compatible_surface` if any.
+///
+/// Without a surface to present to, a software adapter is used as a fallback when no hardware
+/// adapter is available, as on CI machines without a GPU.
+pub async fn request_adapter(
+    instance: &wgpu::Instance,
+    compatible_surface: Option<&wgpu::Surface>,
+) -> Option<wgpu::Adapter> {
+    // Requests a hardware adapter first.
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface,
+        })
+        .await;
+    match adapter {
+        None if compatible_surface.is_none() => fallback_adapter(instance, None),
+        adapter => adapter,
+    }
+}
+
+/// Gets a software adapter able to present to `compatible_surface` if any, such as llvmpipe
+/// on Vulkan or WARP on DX12.
+///
+/// wgpu 0.10 can not request one through `RequestAdapterOptions`, so the adapters of the
+/// instance backends are enumerated to find one running on the CPU.
+pub fn fallback_adapter(
+    instance: &wgpu::Instance,
+    compatible_surface: Option<&wgpu::Surface>,
+) -> Option<wgpu::Adapter> {
+    instance.enumerate_adapters(BACKENDS).find(|adapter| {
+        adapter.get_info().device_type == wgpu::DeviceType::Cpu
+            && compatible_surface.is_none_or(|surface| adapter.is_surface_supported(surface))
+    })
 }
 
 ///
 pub struct RenderContextDescriptor<'a> {
     ///
     pub features: wgpu::Features,
+    /// Features requested only if the adapter supports them, check [`wgpu::Device::features`]
+    /// before relying on one.
+    pub optional_features: wgpu::Features,
+    /// Capabilities the adapter must have, GL and WebGL adapters lack some of them such as
+    /// [`wgpu::DownlevelFlags::COMPUTE_SHADERS`].
+    pub downlevel_flags: wgpu::DownlevelFlags,
     ///
     pub window: Option<&'a Window>,
+    /// Present behavior of every surface created by the context.
+    pub latency_mode: LatencyMode,
+    /// Labels of the device and resources, the validation itself follows the build profile.
+    pub validation: ValidationMode,
+    /// Labels every resource created without a label with its type and a counter, e.g. `Buffer#42`.
+    ///
+    /// [`ValidationMode::Full`] implies it.
+    pub debug_names: bool,
+    /// Only uses a software adapter, such as llvmpipe, even if a GPU is available.
+    pub force_fallback_adapter: bool,
+    /// Works around known-bad drivers: features blocklisted for the adapter in
+    /// [`safe_mode::DRIVER_BLOCKLIST`] are not requested, and every surface presents with
+    /// [`PresentMode::Fifo`] whatever the latency mode.
+    pub safe_mode: bool,
+    /// Directory wgpu writes a replayable API trace of the device into, to attach to driver
+    /// bug reports.
+    ///
+    /// Only available with the `trace` feature, which builds wgpu with its own `trace` feature.
+    #[cfg(feature = "trace")]
+    pub trace_path: Option<std::path::PathBuf>,
+}
+
+/// Error of a render context creation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RenderContextError {
+    /// Neither a suitable hardware adapter nor a software one is available.
+    NoAdapter,
+    /// The adapter does not have these required downlevel capabilities.
+    MissingDownlevelFlags(wgpu::DownlevelFlags),
+    /// The adapter failed to create a device on every attempt.
+    FailedToRequestDevice(wgpu::RequestDeviceError),
+}
+
+/// Whether the context labels what it creates for validation errors and captures.
+///
+/// The mode does not change validation itself: wgpu 0.10 has no instance flags, wgpu-core
+/// validates every call whatever the mode, and the backend validation layers and debug markers
+/// are enabled by the build profile of wgpu, in debug builds only. A mismatch between the mode
+/// and the build profile is logged when the context is created.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum ValidationMode {
+    /// Labels the device and every resource so they show up in validation errors and captures,
+    /// resources created without a label get a debug name as with
+    /// [`RenderContextDescriptor::debug_names`].
+    Full,
+    /// Leaves the device label and the labels of unlabeled resources empty.
+    None,
+}
+
+impl ValidationMode {
+    /// Gets the debug label of the device, it is only kept with [`ValidationMode::Full`].
+    fn label(self, label: &'static str) -> Option<&'static str> {
+        match self {
+            ValidationMode::Full => Some(label),
+            ValidationMode::None => None,
+        }
+    }
 }
 
+impl Default for ValidationMode {
+    /// Follows the build profile, as the backend validation layers do.
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Full
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// A bind group layout along the entries it was created with, bind groups are checked against them.
+type BindGroupLayoutWithEntries = (wgpu::BindGroupLayout, Vec<BindGroupLayoutEntry>);
+
+/// A buffer along its requested size and usages, operations are checked against them.
+type BufferWithUsages = (wgpu::Buffer, u64, BufferUsages);
+
+/// A shader module along the layout entries reflected from it, one list per bind group.
+type ShaderModuleWithLayouts = (wgpu::ShaderModule, Vec<Vec<BindGroupLayoutEntry>>);
+
 ///
 #[derive(Clone)]
 pub struct ResourceContext {
     ctx_data: ContextSharedData,
     surfaces: Arc<RwLock<HashMap<WindowId, (wgpu::Surface, wgpu::SurfaceConfiguration)>>>,
-    samplers: Arc<RwLock<HashMap<SamplerId, wgpu::Sampler>>>,
-    buffers: Arc<RwLock<HashMap<BufferId, wgpu::Buffer>>>,
+    samplers: Arc<RwLock<HashMap<SamplerId, (wgpu::Sampler, SamplerDescriptor)>>>,
+    sampler_cache: Arc<RwLock<HashMap<SamplerDescriptor, (SamplerId, usize)>>>,
+    buffers: Arc<RwLock<HashMap<BufferId, BufferWithUsages>>>,
+    textures: Arc<RwLock<HashMap<TextureId, (wgpu::Texture, TextureDescriptor)>>>,
+    texture_views: Arc<RwLock<HashMap<TextureViewId, (wgpu::TextureView, TextureFormat)>>>,
+    texture_cache: Arc<RwLock<TextureCache>>,
+    bind_group_layouts: Arc<RwLock<HashMap<BindGroupLayoutId, BindGroupLayoutWithEntries>>>,
+    bind_groups: Arc<RwLock<HashMap<BindGroupId, wgpu::BindGroup>>>,
+    shader_modules: Arc<RwLock<HashMap<ShaderModuleId, ShaderModuleWithLayouts>>>,
+    quad: Arc<RwLock<Option<TexturedQuad>>>,
+    converter: Arc<RwLock<Option<TextureConverter>>>,
+    mipmapper: Arc<RwLock<Option<MipmapGenerator>>>,
+    presets: Arc<RwLock<DescriptorPresets>>,
 }
 
 impl ResourceContext {
+    /// Creates and configures the surface of `window`, nothing is done if it already has one.
     ///
-    pub async fn create_surface(&self, window: &Window) {
+    /// Fails if the adapter can not present to the window, such as an adapter requested without
+    /// a compatible surface.
+    pub async fn create_surface(&self, window: &Window) -> Result<(), SurfaceError> {
         // Gets the window id.
         let window_id = window.id();
         // Gets the write lock.
         let mut surfaces = self.surfaces.write().await;
         // Checks if the surface has already been created.
-        if !surfaces.contains_key(&window_id) {
+        if let Entry::Vacant(entry) = surfaces.entry(window_id) {
             // Creates a new surface.
             let surface = unsafe { self.ctx_data.instance.create_surface(window) };
             // Checks if the new surface is suit for the adapter.
-            if self.ctx_data.adapter.is_surface_supported(&surface) {
+            let format = if self.ctx_data.adapter.is_surface_supported(&surface) {
                 // Gets preferred format.
-                let format = surface
-                    .get_preferred_format(&self.ctx_data.adapter)
-                    .unwrap();
-                // Gets window size.
-                let size = window.inner_size();
-                // Fills surface config desc.
-                let desc = wgpu::SurfaceConfiguration {
-                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                    format,
-                    width: size.width,
-                    height: size.height,
-                    present_mode: wgpu::PresentMode::Mailbox,
-                };
-                // Configures surface.
-                surface.configure(&self.ctx_data.device, &desc);
-                // Inserts it to the surfaces map.
-                surfaces.insert(window_id, (surface, desc));
+                surface.get_preferred_format(&self.ctx_data.adapter)
             } else {
-                todo!("Throws an error that the adapter not support the surface.");
-            }
+                None
+            };
+            let format = format.ok_or(SurfaceError::UnsupportedSurface(window_id))?;
+            // Gets window size.
+            let size = window.inner_size();
+            // Fills surface config desc.
+            let desc = wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format,
+                width: size.width,
+                height: size.height,
+                present_mode: self
+                    .ctx_data
+                    .present_mode(self.ctx_data.latency_mode.present_mode())
+                    .into(),
+            };
+            // Configures surface.
+            surface.configure(&self.ctx_data.device, &desc);
+            // Inserts it to the surfaces map.
+            entry.insert((surface, desc));
         }
+        Ok(())
     }
 
     ///
@@ -144,8 +486,87 @@ impl ResourceContext {
         }
     }
 
+    /// Changes the present mode of a surface, only its configuration is updated.
+    ///
+    /// wgpu 0.10 can not query the present modes a surface supports, it configures the surface
+    /// with [`PresentMode::Fifo`] and logs a warning if it does not support `mode`. Safe mode
+    /// always uses `Fifo`, which is logged too.
+    pub async fn set_present_mode(
+        &self,
+        id: WindowId,
+        mode: PresentMode,
+    ) -> Result<(), SurfaceError> {
+        // Gets the write lock.
+        let mut surfaces = self.surfaces.write().await;
+        // Gets the target surface from the surfaces.
+        let (surface, desc) = surfaces
+            .get_mut(&id)
+            .ok_or(SurfaceError::SurfaceNotFound(id))?;
+        if mode != PresentMode::Fifo {
+            log::info!(
+                "Presenting with {:?}, wgpu falls back to Fifo if the surface does not support it.",
+                mode
+            );
+        }
+        // Changes present mode in descriptor.
+        desc.present_mode = self.ctx_data.present_mode(mode).into();
+        // Reconfigures surfaces.
+        surface.configure(&self.ctx_data.device, desc);
+        Ok(())
+    }
+
+    /// Changes the format of a surface, e.g. to its srgb variant, only its configuration is
+    /// updated.
+    pub async fn set_surface_format(&self, id: WindowId, format: TextureFormat) {
+        // Gets the write lock.
+        let mut surfaces = self.surfaces.write().await;
+        // Gets the target surface from the surfaces.
+        if let Some((surface, desc)) = surfaces.get_mut(&id) {
+            // Changes format in descriptor.
+            desc.format = format.into();
+            // Reconfigures surfaces.
+            surface.configure(&self.ctx_data.device, desc);
+        }
+    }
+
+    /// Gets a copy of the configuration of a surface.
+    pub async fn surface_config(&self, id: WindowId) -> Option<wgpu::SurfaceConfiguration> {
+        // Gets the read lock.
+        let surfaces = self.surfaces.read().await;
+        // Copies the configuration of the target surface.
+        surfaces.get(&id).map(|(_, desc)| desc.clone())
+    }
+
+    /// Gets the format of the frames of a surface, pipelines drawing into them target it.
+    pub async fn surface_format(&self, id: WindowId) -> Option<TextureFormat> {
+        // Gets the read lock.
+        let surfaces = self.surfaces.read().await;
+        // Converts the format of the target surface.
+        surfaces.get(&id).map(|(_, desc)| desc.format.into())
+    }
+
+    /// Gets the width and height a surface is configured with, which its frames have.
+    ///
+    /// This is the size last passed to [`Self::update_surface`], such as to compute the aspect
+    /// ratio of what is drawn into the frames.
+    pub async fn surface_size(&self, id: WindowId) -> Option<(u32, u32)> {
+        // Gets the read lock.
+        let surfaces = self.surfaces.read().await;
+        // Copies the size of the target surface.
+        surfaces.get(&id).map(|(_, desc)| (desc.width, desc.height))
+    }
+
+    /// Describes a color target drawing into the frames of a surface: it has the surface
+    /// format, replaces the frame and writes every channel.
+    pub async fn color_target_for_surface(&self, id: WindowId) -> Option<ColorTargetState> {
+        self.surface_format(id).await.map(ColorTargetState::from)
+    }
+
+    /// Gets the texture to draw the next frame of a surface into, present it with
+    /// [`surface::present`] once the commands drawing into it are submitted.
     ///
-    pub async fn surface_next_frame(&self, id: WindowId) -> Option<wgpu::SurfaceFrame> {
+    /// A lost surface is reconfigured and `None` returned, the next call gets a frame again.
+    pub async fn surface_current_texture(&self, id: WindowId) -> Option<wgpu::SurfaceTexture> {
         // Gets the read look.
         let surfaces = self.surfaces.read().await;
         // Gets the target surface from the surfaces.
@@ -153,7 +574,7 @@ impl ResourceContext {
             // Gets next frame and deal errors.
             match surface.get_current_frame() {
                 // Success to get next frame.
-                Ok(frame) => Some(frame),
+                Ok(frame) => Some(frame.output),
                 // Fail to get next frame.
                 Err(error) => match error {
                     // Swap Chain has been lost and needs to be recreated.
@@ -174,27 +595,81 @@ impl ResourceContext {
         }
     }
 
+    /// Gets a snapshot of the ids of every live surface.
+    pub async fn surface_ids(&self) -> Vec<WindowId> {
+        // Gets the read lock.
+        let surfaces = self.surfaces.read().await;
+        // Copies the keys of the surfaces map.
+        surfaces.keys().copied().collect()
+    }
+
+    /// Creates a sampler, identical descriptors share the sampler created first.
     ///
+    /// Devices can only hold a limited number of samplers. A shared sampler counts every
+    /// creation and is only removed once [`Self::remove_sampler`] is called as many times.
     pub async fn create_sampler(&self, desc: &SamplerDescriptor) -> SamplerId {
-        // Gets the write lock.
+        // Gets the write locks.
         let mut samplers = self.samplers.write().await;
+        let mut sampler_cache = self.sampler_cache.write().await;
+        // Reuses the sampler of an identical descriptor.
+        if let Some((sampler_id, count)) = sampler_cache.get_mut(desc) {
+            *count += 1;
+            return *sampler_id;
+        }
         // Creates a new sampler id.
         let sampler_id = SamplerId::new();
         // Creates a new sampler.
-        let sampler = self.ctx_data.device.create_sampler(&desc.into());
-        // Inserts it to samplers map.
-        samplers.insert(sampler_id, sampler);
+        let sampler = self
+            .ctx_data
+            .device
+            .create_sampler(&wgpu::SamplerDescriptor {
+                label: self.ctx_data.label("Sampler", desc.label).as_deref(),
+                ..desc.into()
+            });
+        // Inserts it to samplers map and to the cache.
+        samplers.insert(sampler_id, (sampler, desc.clone()));
+        sampler_cache.insert(desc.clone(), (sampler_id, 1));
         sampler_id
     }
 
-    ///
+    /// Releases a sampler, it is removed once released as many times as it was created.
     pub async fn remove_sampler(&self, id: SamplerId) {
-        // Gets the write lock.
+        // Gets the write locks.
         let mut samplers = self.samplers.write().await;
+        let mut sampler_cache = self.sampler_cache.write().await;
+        // Decrements the count of the target sampler.
+        let desc = match samplers.get(&id) {
+            Some((_, desc)) => desc,
+            None => return,
+        };
+        if let Some((_, count)) = sampler_cache.get_mut(desc) {
+            if *count > 1 {
+                *count -= 1;
+                return;
+            }
+            sampler_cache.remove(desc);
+        }
         // Remove target sampler from samplers map.
         samplers.remove(&id);
     }
 
+    /// Gets the level of detail bias a sampler has been created with, to upload it to the
+    /// shaders sampling through it with `textureSampleBias`.
+    pub async fn sampler_lod_bias(&self, id: SamplerId) -> Option<f32> {
+        // Gets the read lock.
+        let samplers = self.samplers.read().await;
+        // Gets the bias from the descriptor of the target sampler.
+        samplers.get(&id).map(|(_, desc)| desc.lod_bias)
+    }
+
+    /// Gets a snapshot of the ids of every live sampler.
+    pub async fn sampler_ids(&self) -> Vec<SamplerId> {
+        // Gets the read lock.
+        let samplers = self.samplers.read().await;
+        // Copies the keys of the samplers map.
+        samplers.keys().copied().collect()
+    }
+
     ///
     pub async fn create_buffer(&self, desc: &BufferDescriptor) -> BufferId {
         // Gets the write lock.
@@ -202,28 +677,162 @@ impl ResourceContext {
         // Creates a new buffer id.
         let buffer_id = BufferId::new();
         // Creates a new buffer.
-        let buffer = self.ctx_data.device.create_buffer(&desc.into());
-        // Inserts it to samplers map.
-        buffers.insert(buffer_id, buffer);
+        let buffer = self.ctx_data.device.create_buffer(&wgpu::BufferDescriptor {
+            label: self.ctx_data.label("Buffer", desc.label).as_deref(),
+            ..desc.into()
+        });
+        // Inserts it to buffers map along its requested size and usages.
+        buffers.insert(buffer_id, (buffer, desc.size, desc.usage));
         buffer_id
     }
 
     ///
-    pub async fn create_buffer_with_data(
-        &self,
-        desc: &BufferInitDescriptor<'_>,
-    ) -> BufferId {
+    pub async fn create_buffer_with_data(&self, desc: &BufferInitDescriptor<'_>) -> BufferId {
         // Gets the write lock.
         let mut buffers = self.buffers.write().await;
         // Creates a new buffer id.
         let buffer_id = BufferId::new();
         // Creates a new buffer.
-        let buffer = self.ctx_data.device.create_buffer_init(&desc.into());
-        // Inserts it to samplers map.
-        buffers.insert(buffer_id, buffer);
+        let buffer = self
+            .ctx_data
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: self.ctx_data.label("Buffer", desc.label).as_deref(),
+                ..desc.into()
+            });
+        // Inserts it to buffers map along its size and usages.
+        buffers.insert(buffer_id, (buffer, desc.contents.len() as u64, desc.usage));
         buffer_id
     }
 
+    /// Creates a buffer with the contents of `data`, taking ownership of it so vertices built
+    /// in the call, e.g. `create_buffer_owned(vec![...], usage)`, need no binding outliving it.
+    pub async fn create_buffer_owned<T: bytemuck::Pod>(
+        &self,
+        data: Vec<T>,
+        usage: BufferUsages,
+    ) -> BufferId {
+        self.create_buffer_with_data(&BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&data),
+            usage,
+        })
+        .await
+    }
+
+    /// Creates a buffer of `size` bytes updated through a [`DynamicBuffer`], it starts zeroed.
+    ///
+    /// [`BufferUsages::COPY_DST`] is added to `usage` and the size is rounded up to the 4 bytes
+    /// alignment of buffer writes.
+    pub async fn create_dynamic_buffer(
+        &self,
+        label: Option<&'static str>,
+        size: u64,
+        usage: BufferUsages,
+    ) -> DynamicBuffer {
+        let size = buffer::align_copy_size(size);
+        let buffer = self
+            .create_buffer(&BufferDescriptor {
+                label,
+                size,
+                usage: usage | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+            .await;
+        DynamicBuffer::new(buffer, vec![0; size as usize])
+    }
+
+    /// Writes `data` into a buffer at `offset` through the queue, the buffer needs
+    /// [`BufferUsages::COPY_DST`].
+    ///
+    /// The offset and the size of `data` must be multiples of 4. Returns the index of the
+    /// submission the write lands in, to wait for it with [`Self::wait_for`].
+    pub async fn write_buffer(
+        &self,
+        id: BufferId,
+        offset: u64,
+        data: &[u8],
+    ) -> Result<SubmissionIndex, BufferError> {
+        // Gets the read lock.
+        let buffers = self.buffers.read().await;
+        // Gets the target buffer and checks the write fits.
+        let (buffer, buffer_size, usage) =
+            buffers.get(&id).ok_or(BufferError::BufferNotFound(id))?;
+        buffer::check_usages(*usage, BufferUsages::COPY_DST)?;
+        buffer::check_copy_alignment("offset", offset)?;
+        buffer::check_copy_alignment("size", data.len() as u64)?;
+        // Writes may cover the padding the allocation is rounded up with.
+        let buffer_size = buffer::align_copy_size(*buffer_size);
+        if !matches!(offset.checked_add(data.len() as u64), Some(end) if end <= buffer_size) {
+            return Err(BufferError::OutOfBounds {
+                offset,
+                size: data.len() as u64,
+                buffer_size,
+            });
+        }
+        // Writes the data.
+        Ok(self
+            .ctx_data
+            .submissions
+            .write(|| self.ctx_data.queue.write_buffer(buffer, offset, data)))
+    }
+
+    /// Records a copy of `size` bytes from `src` at `src_offset` to `dst` at `dst_offset`.
+    ///
+    /// The buffers need [`BufferUsages::COPY_SRC`] and [`BufferUsages::COPY_DST`] respectively.
+    /// The offsets and the size must be multiples of [`wgpu::COPY_BUFFER_ALIGNMENT`] and both
+    /// ranges must fit their buffer.
+    pub async fn copy_buffer_to_buffer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        src: BufferId,
+        src_offset: u64,
+        dst: BufferId,
+        dst_offset: u64,
+        size: u64,
+    ) -> Result<(), BufferError> {
+        // Gets the read lock.
+        let buffers = self.buffers.read().await;
+        let (src_buffer, src_size, src_usage) =
+            buffers.get(&src).ok_or(BufferError::BufferNotFound(src))?;
+        let (dst_buffer, dst_size, dst_usage) =
+            buffers.get(&dst).ok_or(BufferError::BufferNotFound(dst))?;
+        buffer::check_usages(*src_usage, BufferUsages::COPY_SRC)?;
+        buffer::check_usages(*dst_usage, BufferUsages::COPY_DST)?;
+        // Checks the alignment before the bounds, it is the most common mistake.
+        buffer::check_copy_alignment("src_offset", src_offset)?;
+        buffer::check_copy_alignment("dst_offset", dst_offset)?;
+        buffer::check_copy_alignment("size", size)?;
+        // Copies may cover the padding the allocations are rounded up with.
+        for &(offset, buffer_size) in [(src_offset, *src_size), (dst_offset, *dst_size)].iter() {
+            let buffer_size = buffer::align_copy_size(buffer_size);
+            if !matches!(offset.checked_add(size), Some(end) if end <= buffer_size) {
+                return Err(BufferError::OutOfBounds {
+                    offset,
+                    size,
+                    buffer_size,
+                });
+            }
+        }
+        encoder.copy_buffer_to_buffer(src_buffer, src_offset, dst_buffer, dst_offset, size);
+        Ok(())
+    }
+
+    /// Creates a staging buffer for reading back data from the GPU, with exactly the usages
+    /// [`BufferUsages::MAP_READ`] and [`BufferUsages::COPY_DST`].
+    ///
+    /// Without [`wgpu::Features::MAPPABLE_PRIMARY_BUFFERS`] this is the only usage a buffer
+    /// mapped for reading may have, copy into it then map it.
+    pub async fn create_readback_buffer(&self, size: u64) -> BufferId {
+        self.create_buffer(&BufferDescriptor {
+            label: None,
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+        .await
+    }
+
     ///
     pub async fn remove_buffer(&self, id: BufferId) {
         // Gets the write lock.
@@ -231,6 +840,1621 @@ impl ResourceContext {
         // Remove target buffer from buffers map.
         buffers.remove(&id);
     }
+
+    /// Gets a snapshot of the ids of every live buffer.
+    pub async fn buffer_ids(&self) -> Vec<BufferId> {
+        // Gets the read lock.
+        let buffers = self.buffers.read().await;
+        // Copies the keys of the buffers map.
+        buffers.keys().copied().collect()
+    }
+
+    /// Registers a sampler or buffer descriptor under `name`, replacing the preset it had.
+    pub async fn register_preset(&self, name: &str, preset: impl Into<Preset>) -> Option<Preset> {
+        // Gets the write lock.
+        let mut presets = self.presets.write().await;
+        // Inserts it to presets registry.
+        presets.register(name, preset)
+    }
+
+    /// Gets a snapshot of the presets, it can be restored by [`ResourceContext::restore_presets`].
+    pub async fn presets(&self) -> DescriptorPresets {
+        // Gets the read lock.
+        let presets = self.presets.read().await;
+        // Copies the presets registry.
+        presets.clone()
+    }
+
+    /// Replaces every preset with a snapshot, resources already created from them are kept.
+    pub async fn restore_presets(&self, snapshot: DescriptorPresets) {
+        // Gets the write lock.
+        let mut presets = self.presets.write().await;
+        // Replaces the presets registry.
+        *presets = snapshot;
+    }
+
+    /// Creates a sampler from the sampler preset registered under `name`.
+    pub async fn create_sampler_preset(&self, name: &str) -> Option<SamplerId> {
+        // Copies the descriptor so the read lock is released before creating.
+        let desc = self.presets.read().await.sampler(name)?.clone();
+        Some(self.create_sampler(&desc).await)
+    }
+
+    /// Creates a buffer from the buffer preset registered under `name`.
+    pub async fn create_buffer_preset(&self, name: &str) -> Option<BufferId> {
+        // Copies the descriptor so the read lock is released before creating.
+        let desc = self.presets.read().await.buffer(name)?.clone();
+        Some(self.create_buffer(&desc).await)
+    }
+
+    /// Submits command buffers to the queue, returning the index to wait for their completion
+    /// with [`Self::wait_for`].
+    ///
+    /// The copy, dispatch and render pass helpers record into the encoder they are given, so
+    /// their work is submitted here. Writes through [`Self::write_buffer`] and
+    /// [`Self::write_texture`] are staged on the queue and land in the next submission, whose
+    /// index they return.
+    pub fn submit<I: IntoIterator<Item = wgpu::CommandBuffer>>(
+        &self,
+        command_buffers: I,
+    ) -> SubmissionIndex {
+        self.ctx_data
+            .submissions
+            .submit(&self.ctx_data.queue, command_buffers)
+    }
+
+    /// Whether the GPU completed the submission, as of the last device poll.
+    pub fn is_submission_done(&self, index: SubmissionIndex) -> bool {
+        self.ctx_data.submissions.is_done(index)
+    }
+
+    /// Waits until the GPU completed the submission, and every submission before it.
+    ///
+    /// The staged writes are submitted first if `index` is the submission they land in. The
+    /// device is polled on a blocking thread, so other tasks keep running while the GPU works.
+    /// wgpu 0.10 can only wait for every submission at once, so the later ones are waited for
+    /// too unless `index` is already complete.
+    pub async fn wait_for(&self, index: SubmissionIndex) {
+        if !self.ctx_data.submissions.is_submitted(index) {
+            self.submit(iter::empty());
+        }
+        if self.is_submission_done(index) {
+            return;
+        }
+        let device = self.ctx_data.device.clone();
+        tokio::task::spawn_blocking(move || device.poll(wgpu::Maintain::Wait))
+            .await
+            .unwrap();
+    }
+
+    /// Reads back the contents of a buffer created with [`BufferUsages::COPY_SRC`].
+    ///
+    /// The buffer is copied to a staging buffer and the call waits for the GPU to finish, see
+    /// [`Self::wait_for`]. The buffers stay writable meanwhile.
+    ///
+    /// [`BufferUsages::COPY_SRC`]: buffer::BufferUsages::COPY_SRC
+    pub async fn read_buffer(&self, id: BufferId) -> Result<Vec<u8>, BufferError> {
+        // Gets the read lock.
+        let buffers = self.buffers.read().await;
+        let (buffer, size, usage) = buffers.get(&id).ok_or(BufferError::BufferNotFound(id))?;
+        buffer::check_usages(*usage, BufferUsages::COPY_SRC)?;
+        let size = *size;
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        // Copies are made in whole words, buffers created with data are padded accordingly.
+        let copy_size = buffer::align_copy_size(size);
+        let device = &self.ctx_data.device;
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CGToy - Buffer(readback)"),
+            size: copy_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(readback)"),
+        });
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging_buffer, 0, copy_size);
+        let submission = self.submit(std::iter::once(encoder.finish()));
+        // Releases the read lock, the copy no longer needs the buffer.
+        drop(buffers);
+        // Maps the staging buffer and waits for the copy.
+        let slice = staging_buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        self.wait_for(submission).await;
+        mapping.await.map_err(|_| BufferError::MapFailed(id))?;
+        let data = slice.get_mapped_range()[..size as usize].to_vec();
+        staging_buffer.unmap();
+        Ok(data)
+    }
+
+    /// Reads back the contents of a buffer as a vector of `T`.
+    ///
+    /// Fails if the size of the buffer is not a multiple of the size of `T`.
+    pub async fn read_buffer_typed<T: bytemuck::Pod>(
+        &self,
+        id: BufferId,
+    ) -> Result<Vec<T>, BufferError> {
+        let data = self.read_buffer(id).await?;
+        let element_size = std::mem::size_of::<T>();
+        if element_size == 0 || data.len() % element_size != 0 {
+            return Err(BufferError::SizeMismatch {
+                size: data.len() as u64,
+                element_size: element_size as u64,
+            });
+        }
+        // The bytes are copied since the vector of bytes may not be aligned for `T`.
+        let mut typed = vec![T::zeroed(); data.len() / element_size];
+        bytemuck::cast_slice_mut(&mut typed).copy_from_slice(&data);
+        Ok(typed)
+    }
+
+    /// Creates a texture, evicting cached textures first if it does not fit the budget.
+    ///
+    /// A block compressed format whose feature, such as
+    /// [`wgpu::Features::TEXTURE_COMPRESSION_BC`], is not enabled on the device is rejected with
+    /// [`TextureError::UnsupportedFormat`] instead of panicking inside wgpu.
+    pub async fn create_texture(
+        &self,
+        desc: &TextureDescriptor,
+    ) -> Result<TextureId, TextureError> {
+        self.check_format_features(desc.format)?;
+        // Evicts textures until it fits the budget.
+        let size = texture_cache::texture_size(desc);
+        self.make_room_for_texture(size).await;
+        // Gets the write lock.
+        let mut textures = self.textures.write().await;
+        // Creates a new texture id.
+        let texture_id = TextureId::new();
+        // Creates a new texture.
+        let texture = self
+            .ctx_data
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: self.ctx_data.label("Texture", desc.label).as_deref(),
+                ..desc.into()
+            });
+        // Inserts it to textures map and tracks its memory.
+        textures.insert(texture_id, (texture, *desc));
+        self.texture_cache.write().await.insert(texture_id, size);
+        Ok(texture_id)
+    }
+
+    /// Creates a texture holding `data`, every mip level and layer tightly packed from the
+    /// largest level, evicting cached textures first if it does not fit the budget.
+    ///
+    /// Formats whose features are not enabled on the device are rejected with
+    /// [`TextureError::UnsupportedFormat`], as by [`Self::create_texture`].
+    pub async fn create_texture_with_data(
+        &self,
+        desc: &TextureDescriptor,
+        data: &[u8],
+    ) -> Result<TextureId, TextureError> {
+        self.check_format_features(desc.format)?;
+        // Evicts textures until it fits the budget.
+        let size = texture_cache::texture_size(desc);
+        self.make_room_for_texture(size).await;
+        // Gets the write lock.
+        let mut textures = self.textures.write().await;
+        // Creates a new texture id.
+        let texture_id = TextureId::new();
+        // Creates a new texture and uploads all of its mip levels.
+        let texture = self.ctx_data.device.create_texture_with_data(
+            &self.ctx_data.queue,
+            &wgpu::TextureDescriptor {
+                label: self.ctx_data.label("Texture", desc.label).as_deref(),
+                ..desc.into()
+            },
+            data,
+        );
+        // Inserts it to textures map and tracks its memory.
+        textures.insert(texture_id, (texture, *desc));
+        self.texture_cache.write().await.insert(texture_id, size);
+        Ok(texture_id)
+    }
+
+    /// Checks the device has the features of `format`, wgpu panics creating the texture
+    /// otherwise.
+    fn check_format_features(&self, format: TextureFormat) -> Result<(), TextureError> {
+        let missing = format.required_features() - self.ctx_data.device.features();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(TextureError::UnsupportedFormat { format, missing })
+        }
+    }
+
+    /// Loads a 2D texture from a KTX2 file, its mip levels are uploaded without being
+    /// decompressed.
+    ///
+    /// Block compressed formats need their feature, e.g. [`wgpu::Features::TEXTURE_COMPRESSION_BC`],
+    /// to be requested through [`RenderContextDescriptor::features`]. The file is read on the
+    /// calling thread.
+    #[cfg(feature = "ktx2")]
+    pub async fn load_ktx2(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<TextureId, ktx2::Ktx2Error> {
+        let texture = ktx2::parse(&std::fs::read(path)?)?;
+        let desc = TextureDescriptor {
+            label: None,
+            size: texture.size,
+            mip_level_count: texture.mip_level_count,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: texture.format,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        };
+        Ok(self.create_texture_with_data(&desc, &texture.data).await?)
+    }
+
+    /// Creates a [`TextureFormat::R8Unorm`] 2D texture from one byte per texel.
+    pub async fn create_texture_r8(
+        &self,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<TextureId, TextureError> {
+        self.create_single_channel_texture(TextureFormat::R8Unorm, data, width, height)
+            .await
+    }
+
+    /// Creates a [`TextureFormat::R16Uint`] 2D texture from one `u16` per texel.
+    pub async fn create_texture_r16u(
+        &self,
+        data: &[u16],
+        width: u32,
+        height: u32,
+    ) -> Result<TextureId, TextureError> {
+        self.create_single_channel_texture(TextureFormat::R16Uint, data, width, height)
+            .await
+    }
+
+    /// Creates a [`TextureFormat::R32Float`] 2D texture from one `f32` per texel.
+    ///
+    /// `R32Float` is not filterable, read it with `textureLoad` in shaders.
+    pub async fn create_texture_r32f(
+        &self,
+        data: &[f32],
+        width: u32,
+        height: u32,
+    ) -> Result<TextureId, TextureError> {
+        self.create_single_channel_texture(TextureFormat::R32Float, data, width, height)
+            .await
+    }
+
+    /// Creates a sampled 2D texture of a single channel format from tightly packed rows.
+    ///
+    /// Fails with [`TextureError::DataSizeMismatch`] unless `data` holds one value per texel.
+    async fn create_single_channel_texture<T: bytemuck::Pod>(
+        &self,
+        format: TextureFormat,
+        data: &[T],
+        width: u32,
+        height: u32,
+    ) -> Result<TextureId, TextureError> {
+        // Checks the data covers exactly every texel.
+        let texel_size = std::mem::size_of::<T>() as u64;
+        texture::check_image_data(bytemuck::cast_slice(data), texel_size, width, height)?;
+        self.create_texture_with_data(
+            &TextureDescriptor {
+                label: None,
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            },
+            bytemuck::cast_slice(data),
+        )
+        .await
+    }
+
+    ///
+    pub async fn remove_texture(&self, id: TextureId) {
+        // Gets the write lock.
+        let mut textures = self.textures.write().await;
+        // Remove target texture from textures map.
+        textures.remove(&id);
+        // Its views stay valid, they are no longer tracked.
+        self.texture_cache.write().await.remove(id);
+    }
+
+    /// Gets a snapshot of the ids of every live texture.
+    pub async fn texture_ids(&self) -> Vec<TextureId> {
+        // Gets the read lock.
+        let textures = self.textures.read().await;
+        // Copies the keys of the textures map.
+        textures.keys().copied().collect()
+    }
+
+    /// Writes tightly packed texels to a region of a texture, see [`TexelCopyInfo`].
+    ///
+    /// The rows of `data` are rows of blocks for block compressed formats. The texture needs
+    /// [`TextureUsages::COPY_DST`]. Returns the index of the submission the write lands in, to
+    /// wait for it with [`Self::wait_for`].
+    pub async fn write_texture(
+        &self,
+        dst: TexelCopyInfo,
+        data: &[u8],
+        size: Extent3d,
+    ) -> Result<SubmissionIndex, TextureError> {
+        // Gets the read lock.
+        let textures = self.textures.read().await;
+        let (texture, desc) = textures
+            .get(&dst.texture)
+            .ok_or(TextureError::TextureNotFound(dst.texture))?;
+        self.texture_cache.write().await.touch(dst.texture);
+        if !desc.usage.contains(TextureUsages::COPY_DST) {
+            return Err(TextureError::MissingUsages(TextureUsages::COPY_DST));
+        }
+        // Checks the data covers exactly the region.
+        let block_height = wgpu::TextureFormat::from(desc.format)
+            .describe()
+            .block_dimensions
+            .1 as u32;
+        let bytes_per_row = desc.format.unpadded_bytes_per_row(size.width);
+        let rows_per_image = size.height.div_ceil(block_height);
+        let expected =
+            bytes_per_row as u64 * rows_per_image as u64 * size.depth_or_array_layers as u64;
+        if data.len() as u64 != expected {
+            return Err(TextureError::DataSizeMismatch {
+                expected,
+                actual: data.len() as u64,
+            });
+        }
+        Ok(self.ctx_data.submissions.write(|| {
+            self.ctx_data.queue.write_texture(
+                dst.to_wgpu(texture),
+                data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(bytes_per_row),
+                    rows_per_image: NonZeroU32::new(rows_per_image),
+                },
+                size.into(),
+            )
+        }))
+    }
+
+    /// Records a copy of a region from `src` to `dst`.
+    ///
+    /// The textures need [`TextureUsages::COPY_SRC`] and [`TextureUsages::COPY_DST`]
+    /// respectively and compatible formats.
+    pub async fn copy_texture_to_texture(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        src: TexelCopyInfo,
+        dst: TexelCopyInfo,
+        size: Extent3d,
+    ) -> Result<(), TextureError> {
+        // Gets the read lock.
+        let textures = self.textures.read().await;
+        let (src_texture, src_desc) = textures
+            .get(&src.texture)
+            .ok_or(TextureError::TextureNotFound(src.texture))?;
+        let (dst_texture, dst_desc) = textures
+            .get(&dst.texture)
+            .ok_or(TextureError::TextureNotFound(dst.texture))?;
+        {
+            let mut texture_cache = self.texture_cache.write().await;
+            texture_cache.touch(src.texture);
+            texture_cache.touch(dst.texture);
+        }
+        if !src_desc.usage.contains(TextureUsages::COPY_SRC) {
+            return Err(TextureError::MissingUsages(TextureUsages::COPY_SRC));
+        }
+        if !dst_desc.usage.contains(TextureUsages::COPY_DST) {
+            return Err(TextureError::MissingUsages(TextureUsages::COPY_DST));
+        }
+        encoder.copy_texture_to_texture(
+            src.to_wgpu(src_texture),
+            dst.to_wgpu(dst_texture),
+            size.into(),
+        );
+        Ok(())
+    }
+
+    /// Reads back the texel at the origin of `src`, the whole block for block compressed
+    /// formats.
+    ///
+    /// Depth and stencil formats are read one aspect at a time, the stencil aspect is a byte
+    /// per texel. The texel is copied to a staging buffer and the call waits for the GPU to
+    /// finish, see [`Self::wait_for`]. The texture needs [`TextureUsages::COPY_SRC`].
+    pub async fn read_pixel(&self, src: TexelCopyInfo) -> Result<Vec<u8>, TextureError> {
+        // Gets the read lock.
+        let textures = self.textures.read().await;
+        let (texture, desc) = textures
+            .get(&src.texture)
+            .ok_or(TextureError::TextureNotFound(src.texture))?;
+        self.texture_cache.write().await.touch(src.texture);
+        if !desc.usage.contains(TextureUsages::COPY_SRC) {
+            return Err(TextureError::MissingUsages(TextureUsages::COPY_SRC));
+        }
+        // Copies of block compressed formats are made in whole blocks.
+        let info = wgpu::TextureFormat::from(desc.format).describe();
+        let (block_width, block_height) = (
+            info.block_dimensions.0 as u32,
+            info.block_dimensions.1 as u32,
+        );
+        let texel_size = match src.aspect {
+            TextureAspect::StencilOnly => 1,
+            _ => desc.format.unpadded_bytes_per_row(block_width),
+        };
+        let device = &self.ctx_data.device;
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CGToy - Buffer(readback)"),
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(readback)"),
+        });
+        let block = TexelCopyInfo {
+            origin: Origin3d {
+                x: src.origin.x - src.origin.x % block_width,
+                y: src.origin.y - src.origin.y % block_height,
+                z: src.origin.z,
+            },
+            ..src
+        };
+        encoder.copy_texture_to_buffer(
+            block.to_wgpu(texture),
+            wgpu::ImageCopyBuffer {
+                buffer: &staging_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: block_width,
+                height: block_height,
+                depth_or_array_layers: 1,
+            },
+        );
+        let submission = self.submit(iter::once(encoder.finish()));
+        // Releases the read lock, the copy no longer needs the texture.
+        drop(textures);
+        // Maps the staging buffer and waits for the copy.
+        let slice = staging_buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        self.wait_for(submission).await;
+        mapping
+            .await
+            .map_err(|_| TextureError::MapFailed(src.texture))?;
+        let data = slice.get_mapped_range()[..texel_size as usize].to_vec();
+        staging_buffer.unmap();
+        Ok(data)
+    }
+
+    /// Reads back the first mip level and layer of a texture, its rows tightly packed.
+    ///
+    /// The texture is copied to a staging buffer and the call waits for the GPU to finish, see
+    /// [`Self::wait_for`]. The texture needs [`TextureUsages::COPY_SRC`] and a color format,
+    /// depth and stencil are read with [`Self::read_pixel`].
+    pub async fn read_texture(&self, id: TextureId) -> Result<Vec<u8>, TextureError> {
+        // Gets the read lock.
+        let textures = self.textures.read().await;
+        let (texture, desc) = textures.get(&id).ok_or(TextureError::TextureNotFound(id))?;
+        self.texture_cache.write().await.touch(id);
+        if !desc.usage.contains(TextureUsages::COPY_SRC) {
+            return Err(TextureError::MissingUsages(TextureUsages::COPY_SRC));
+        }
+        if desc.format.is_depth() {
+            return Err(TextureError::DepthStencilFormat(desc.format));
+        }
+        // Copies of block compressed formats are made in whole blocks.
+        let block_height = wgpu::TextureFormat::from(desc.format)
+            .describe()
+            .block_dimensions
+            .1 as u32;
+        let (width, height) = (desc.size.width, desc.size.height);
+        let row_pitch = desc.format.row_pitch(width);
+        let rows = height.div_ceil(block_height);
+        let device = &self.ctx_data.device;
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CGToy - Buffer(readback)"),
+            size: u64::from(row_pitch.padded * rows),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(readback)"),
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &staging_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(row_pitch.padded),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        let submission = self.submit(iter::once(encoder.finish()));
+        // Releases the read lock, the copy no longer needs the texture.
+        drop(textures);
+        // Maps the staging buffer and waits for the copy.
+        let slice = staging_buffer.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+        self.wait_for(submission).await;
+        mapping.await.map_err(|_| TextureError::MapFailed(id))?;
+        let data = row_pitch.unpad(&slice.get_mapped_range());
+        staging_buffer.unmap();
+        Ok(data)
+    }
+
+    /// Reads back the contents of a buffer as by [`Self::read_buffer`] and writes them raw to
+    /// `path`, such as a `.bin` file to inspect in a hex editor.
+    pub async fn dump_buffer(
+        &self,
+        id: BufferId,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), DumpError> {
+        let data = self.read_buffer(id).await.map_err(DumpError::Buffer)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Reads back a texture as by [`Self::read_texture`] and saves it to `path`, to inspect what
+    /// a pass wrote in an image viewer.
+    ///
+    /// 8 bits per channel unorm formats are saved as PNG and float formats as OpenEXR, whatever
+    /// the extension of `path`, [`dump::extension`] gets the matching one. The texel values are
+    /// saved as is, without color space conversion.
+    pub async fn dump_texture(
+        &self,
+        id: TextureId,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), DumpError> {
+        // Copies the format and size so the read lock is released before reading back.
+        let (format, size) = {
+            let textures = self.textures.read().await;
+            let (_, desc) = textures
+                .get(&id)
+                .ok_or(DumpError::Texture(TextureError::TextureNotFound(id)))?;
+            (desc.format, desc.size)
+        };
+        if dump::extension(format).is_none() {
+            return Err(DumpError::UnsupportedFormat(format));
+        }
+        let texels = self.read_texture(id).await.map_err(DumpError::Texture)?;
+        dump::save_texels(path.as_ref(), format, size.width, size.height, &texels)
+    }
+
+    /// Sets a budget of `max_bytes` for the memory of every texture, `None` removes it.
+    ///
+    /// Once creating a texture would exceed the budget, the least recently used textures are
+    /// removed along their views and `on_evict` is called with each of them, so they can be
+    /// reloaded when needed again. Creating, viewing, binding, rendering to, copying or writing
+    /// a texture uses it, [`ResourceContext::mark_texture_used`] covers other uses. The memory
+    /// of a texture is freed once the bind groups using it are removed too. Textures over a
+    /// lowered budget are evicted right away.
+    pub async fn set_texture_budget(
+        &self,
+        max_bytes: Option<u64>,
+        on_evict: Option<EvictionCallback>,
+    ) {
+        self.texture_cache
+            .write()
+            .await
+            .set_budget(max_bytes, on_evict);
+        self.make_room_for_texture(0).await;
+    }
+
+    /// Gets the estimated memory of every texture, see [`texture_cache::texture_size`].
+    pub async fn texture_memory(&self) -> u64 {
+        self.texture_cache.read().await.used_bytes()
+    }
+
+    /// Marks a texture as just used, so the texture budget evicts it last.
+    pub async fn mark_texture_used(&self, id: TextureId) {
+        self.texture_cache.write().await.touch(id);
+    }
+
+    /// Evicts the least recently used textures until `size` more bytes fit the texture budget.
+    async fn make_room_for_texture(&self, size: u64) {
+        // Picks the textures under the lock of the cache alone, see `TextureCache`.
+        let (evicted, on_evict) = {
+            let mut texture_cache = self.texture_cache.write().await;
+            (texture_cache.evict_for(size), texture_cache.on_evict())
+        };
+        if evicted.is_empty() {
+            return;
+        }
+        // Removes them along their views.
+        {
+            let mut textures = self.textures.write().await;
+            for (texture, _) in &evicted {
+                textures.remove(texture);
+            }
+        }
+        {
+            let mut texture_views = self.texture_views.write().await;
+            for (_, views) in &evicted {
+                for view in views {
+                    texture_views.remove(view);
+                }
+            }
+        }
+        // Notifies once no lock is held, the callback may create textures again.
+        for (texture, _) in evicted {
+            log::debug!("Texture budget evicts {:?}.", texture);
+            if let Some(on_evict) = &on_evict {
+                on_evict(texture);
+            }
+        }
+    }
+
+    /// Converts the first mip level and layer of `src` into a new 2D texture of `dst_format`.
+    ///
+    /// The source is drawn through a fullscreen pass, so the formats may differ in any way
+    /// a shader can convert, e.g. from an `Rgba16Float` HDR target to `Rgba8Unorm` for saving.
+    /// The source needs [`TextureUsages::TEXTURE_BINDING`], the new texture can be sampled,
+    /// rendered to and copied from. `None` is returned if the source does not exist or is not
+    /// sampled as floats.
+    pub async fn convert_texture(
+        &self,
+        src: TextureId,
+        dst_format: TextureFormat,
+    ) -> Option<TextureId> {
+        // Gets the write lock of the converter and the read lock of textures.
+        let mut converter = self.converter.write().await;
+        let textures = self.textures.read().await;
+        let (src_texture, src_desc) = textures.get(&src)?;
+        self.texture_cache.write().await.touch(src);
+        let src_format = wgpu::TextureFormat::from(src_desc.format);
+        if !matches!(
+            src_format.describe().sample_type,
+            wgpu::TextureSampleType::Float { .. }
+        ) {
+            return None;
+        }
+        let src_view = src_texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            mip_level_count: NonZeroU32::new(1),
+            array_layer_count: NonZeroU32::new(1),
+            ..Default::default()
+        });
+        // Creates the destination texture.
+        let dst_desc = TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                depth_or_array_layers: 1,
+                ..src_desc.size
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: dst_format,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_SRC,
+        };
+        let device = &self.ctx_data.device;
+        let dst_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: self.ctx_data.label("Texture", dst_desc.label).as_deref(),
+            ..(&dst_desc).into()
+        });
+        let dst_view = dst_texture.create_view(&Default::default());
+        // Creates the converter on the first conversion.
+        let converter = converter.get_or_insert_with(|| TextureConverter::new(device));
+        converter.prepare(device, dst_format);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(convert)"),
+            layout: &converter.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&src_view),
+            }],
+        });
+        // Draws the source over the whole destination.
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(convert)"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("CGToy - RenderPass(convert)"),
+                color_attachments: &[wgpu::RenderPassColorAttachment {
+                    view: &dst_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            });
+            render_pass.set_pipeline(converter.pipeline(dst_format));
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        self.submit(iter::once(encoder.finish()));
+        // Inserts the destination to textures map, evicting textures until it fits the budget.
+        drop(textures);
+        let size = texture_cache::texture_size(&dst_desc);
+        self.make_room_for_texture(size).await;
+        let texture_id = TextureId::new();
+        self.textures
+            .write()
+            .await
+            .insert(texture_id, (dst_texture, dst_desc));
+        self.texture_cache.write().await.insert(texture_id, size);
+        Some(texture_id)
+    }
+
+    /// Writes every mip level of `texture` from its first one, each level averaging the 2x2
+    /// texels of the previous one.
+    ///
+    /// Renderable formats are drawn level by level, formats that can only be bound as storage
+    /// textures are written by a compute pass instead, see [`MipmapPath::select`]. The texture
+    /// needs [`TextureUsages::TEXTURE_BINDING`] along the usage of its path.
+    pub async fn generate_mipmaps(&self, texture: TextureId) -> Result<MipmapPath, MipmapError> {
+        // Gets the write lock of the generator and the read lock of textures.
+        let mut mipmapper = self.mipmapper.write().await;
+        let textures = self.textures.read().await;
+        self.texture_cache.write().await.touch(texture);
+        let (texture, desc) = textures
+            .get(&texture)
+            .ok_or(MipmapError::TextureNotFound(texture))?;
+        // Picks the path from what the format supports.
+        if desc.dimension != TextureDimension::D2 {
+            return Err(MipmapError::UnsupportedDimension(desc.dimension));
+        }
+        let path = MipmapPath::select(desc.format, self.ctx_data.format_features(desc.format))?;
+        let missing = path.required_usages() - desc.usage;
+        if !missing.is_empty() {
+            return Err(MipmapError::MissingUsages(missing));
+        }
+        // Creates the generator on the first generation.
+        let device = &self.ctx_data.device;
+        let mipmapper = mipmapper.get_or_insert_with(|| MipmapGenerator::new(device));
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("CGToy - Encoder(mipmap)"),
+        });
+        mipmapper.generate(device, &mut encoder, texture, desc, path);
+        self.submit(iter::once(encoder.finish()));
+        Ok(path)
+    }
+
+    /// Creates a view of a texture, `None` is returned if the texture does not exist.
+    pub async fn create_texture_view(
+        &self,
+        texture: TextureId,
+        desc: &TextureViewDescriptor,
+    ) -> Option<TextureViewId> {
+        // Gets the read lock of textures and the write lock of texture views.
+        let textures = self.textures.read().await;
+        let mut texture_views = self.texture_views.write().await;
+        // Creates a new texture view id.
+        let texture_view_id = TextureViewId::new();
+        // Creates a new texture view.
+        let (wgpu_texture, texture_desc) = textures.get(&texture)?;
+        let texture_view = wgpu_texture.create_view(&wgpu::TextureViewDescriptor {
+            label: self.ctx_data.label("TextureView", desc.label).as_deref(),
+            ..desc.into()
+        });
+        // Inserts it to texture views map along its format.
+        let format = desc.format.unwrap_or(texture_desc.format);
+        texture_views.insert(texture_view_id, (texture_view, format));
+        self.texture_cache
+            .write()
+            .await
+            .insert_view(texture_view_id, texture);
+        Some(texture_view_id)
+    }
+
+    ///
+    pub async fn remove_texture_view(&self, id: TextureViewId) {
+        // Gets the write lock.
+        let mut texture_views = self.texture_views.write().await;
+        // Remove target texture view from texture views map.
+        texture_views.remove(&id);
+        self.texture_cache.write().await.remove_view(id);
+    }
+
+    /// Gets a snapshot of the ids of every live texture view.
+    pub async fn texture_view_ids(&self) -> Vec<TextureViewId> {
+        // Gets the read lock.
+        let texture_views = self.texture_views.read().await;
+        // Copies the keys of the texture views map.
+        texture_views.keys().copied().collect()
+    }
+
+    /// Picks the highest sample count up to `requested` supported by every attachment format.
+    ///
+    /// Falls back to 1, which every format supports.
+    pub fn sample_count(&self, formats: &[TextureFormat], requested: u32) -> u32 {
+        formats
+            .iter()
+            .map(|format| format.supported_sample_counts(&self.ctx_data.adapter))
+            .fold(vec![1, 2, 4, 8, 16, 32], |counts, supported| {
+                counts
+                    .into_iter()
+                    .filter(|count| supported.contains(count))
+                    .collect()
+            })
+            .into_iter()
+            .filter(|&count| count <= requested)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Picks the format of depth attachments, `requested` if the device can render to it.
+    ///
+    /// Falls back with a warning to `Depth32Float`, or to `Depth24PlusStencil8` if `requested`
+    /// has a stencil aspect, which every device can render to. wgpu 0.10 has no
+    /// `Depth32FloatStencil8` to fall back to.
+    pub fn depth_format(&self, requested: TextureFormat) -> TextureFormat {
+        if requested.is_depth() && self.ctx_data.format_features(requested).renderable() {
+            return requested;
+        }
+        let fallback = match requested {
+            TextureFormat::Depth24PlusStencil8 => TextureFormat::Depth24PlusStencil8,
+            _ => TextureFormat::Depth32Float,
+        };
+        log::warn!(
+            "Depth format {:?} is not renderable, falling back to {:?}.",
+            requested,
+            fallback
+        );
+        fallback
+    }
+
+    ///
+    pub async fn create_bind_group_layout(
+        &self,
+        desc: &BindGroupLayoutDescriptor<'_>,
+    ) -> BindGroupLayoutId {
+        // Gets the write lock.
+        let mut bind_group_layouts = self.bind_group_layouts.write().await;
+        // Creates a new bind group layout id.
+        let bind_group_layout_id = BindGroupLayoutId::new();
+        // Creates a new bind group layout.
+        let entries: Vec<wgpu::BindGroupLayoutEntry> =
+            desc.entries.iter().map(|entry| entry.into()).collect();
+        let bind_group_layout =
+            self.ctx_data
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: self
+                        .ctx_data
+                        .label("BindGroupLayout", desc.label)
+                        .as_deref(),
+                    entries: &entries,
+                });
+        // Inserts it to bind group layouts map.
+        bind_group_layouts.insert(
+            bind_group_layout_id,
+            (bind_group_layout, desc.entries.to_vec()),
+        );
+        bind_group_layout_id
+    }
+
+    ///
+    pub async fn remove_bind_group_layout(&self, id: BindGroupLayoutId) {
+        // Gets the write lock.
+        let mut bind_group_layouts = self.bind_group_layouts.write().await;
+        // Remove target bind group layout from bind group layouts map.
+        bind_group_layouts.remove(&id);
+    }
+
+    /// Gets a snapshot of the ids of every live bind group layout.
+    pub async fn bind_group_layout_ids(&self) -> Vec<BindGroupLayoutId> {
+        // Gets the read lock.
+        let bind_group_layouts = self.bind_group_layouts.read().await;
+        // Copies the keys of the bind group layouts map.
+        bind_group_layouts.keys().copied().collect()
+    }
+
+    ///
+    pub async fn create_bind_group(
+        &self,
+        desc: &BindGroupDescriptor<'_>,
+    ) -> Result<BindGroupId, BindGroupError> {
+        // Gets the read locks of every bindable resource.
+        let bind_group_layouts = self.bind_group_layouts.read().await;
+        let buffers = self.buffers.read().await;
+        let samplers = self.samplers.read().await;
+        let textures = self.textures.read().await;
+        let texture_views = self.texture_views.read().await;
+        // Gets the layout of the bind group.
+        let (layout, layout_entries) = bind_group_layouts
+            .get(&desc.layout)
+            .ok_or(BindGroupError::InvalidLayout(desc.layout))?;
+        // Binding a texture uses it.
+        {
+            let mut texture_cache = self.texture_cache.write().await;
+            for entry in desc.entries {
+                match entry.resource {
+                    BindingResource::Texture(id) => texture_cache.touch(id),
+                    BindingResource::TextureView(id) => texture_cache.touch_view(id),
+                    _ => (),
+                }
+            }
+        }
+        // Creates the texture views first, the entries borrow them.
+        let mut views = HashMap::new();
+        for entry in desc.entries {
+            if let BindingResource::Texture(id) = entry.resource {
+                let texture = textures
+                    .get(&id)
+                    .ok_or(BindGroupError::ResourceNotFound(entry.resource))?;
+                views.insert(id, texture.0.create_view(&Default::default()));
+            }
+        }
+        // Resolves the resource of every entry.
+        let mut entries = Vec::with_capacity(desc.entries.len());
+        for entry in desc.entries {
+            // Buffers, samplers and textures are checked against the binding they are bound to.
+            let binding_type = layout_entries
+                .iter()
+                .find(|layout_entry| layout_entry.binding == entry.binding)
+                .map(|layout_entry| layout_entry.ty);
+            let check_texture = |format: TextureFormat| match binding_type {
+                Some(BindingType::Texture { sample_type, .. })
+                    if (sample_type == TextureSampleType::Depth) != format.is_depth() =>
+                {
+                    Err(BindGroupError::TextureMismatch {
+                        binding: entry.binding,
+                        format,
+                    })
+                }
+                _ => Ok(()),
+            };
+            let check_buffer = |size: u64| match binding_type {
+                Some(BindingType::Buffer {
+                    min_binding_size: Some(min_binding_size),
+                    ..
+                }) if size < min_binding_size.get() => Err(BindGroupError::BufferTooSmall {
+                    binding: entry.binding,
+                    size,
+                    min_binding_size,
+                }),
+                _ => Ok(()),
+            };
+            let resource = match entry.resource {
+                BindingResource::Buffer(id) => {
+                    let (buffer, size, _) = buffers
+                        .get(&id)
+                        .ok_or(BindGroupError::ResourceNotFound(entry.resource))?;
+                    check_buffer(*size)?;
+                    buffer.as_entire_binding()
+                }
+                BindingResource::BufferRange(binding) => {
+                    let (buffer, size, _) = buffers
+                        .get(&binding.buffer)
+                        .ok_or(BindGroupError::ResourceNotFound(entry.resource))?;
+                    // Checks the range is aligned and holds at least a byte of the buffer.
+                    let in_bounds = match binding.size {
+                        Some(range) => matches!(
+                            binding.offset.checked_add(range.get()),
+                            Some(end) if end <= *size
+                        ),
+                        None => binding.offset < *size,
+                    };
+                    if binding.offset % wgpu::BIND_BUFFER_ALIGNMENT != 0 || !in_bounds {
+                        return Err(BindGroupError::InvalidBufferRange {
+                            binding,
+                            size: *size,
+                        });
+                    }
+                    check_buffer(binding.size.map_or(*size - binding.offset, NonZeroU64::get))?;
+                    wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer,
+                        offset: binding.offset,
+                        size: binding.size,
+                    })
+                }
+                BindingResource::Sampler(id) => {
+                    let (sampler, sampler_desc) = samplers
+                        .get(&id)
+                        .ok_or(BindGroupError::ResourceNotFound(entry.resource))?;
+                    let comparison = sampler_desc.compare.is_some();
+                    if let Some(BindingType::Sampler {
+                        comparison: expected,
+                        ..
+                    }) = binding_type
+                    {
+                        if expected != comparison {
+                            return Err(BindGroupError::SamplerMismatch {
+                                binding: entry.binding,
+                                comparison,
+                            });
+                        }
+                    }
+                    wgpu::BindingResource::Sampler(sampler)
+                }
+                BindingResource::Texture(id) => {
+                    check_texture(textures[&id].1.format)?;
+                    wgpu::BindingResource::TextureView(&views[&id])
+                }
+                BindingResource::TextureView(id) => {
+                    let (view, format) = texture_views
+                        .get(&id)
+                        .ok_or(BindGroupError::ResourceNotFound(entry.resource))?;
+                    check_texture(*format)?;
+                    wgpu::BindingResource::TextureView(view)
+                }
+            };
+            entries.push(wgpu::BindGroupEntry {
+                binding: entry.binding,
+                resource,
+            });
+        }
+        // Creates a new bind group id.
+        let bind_group_id = BindGroupId::new();
+        // Creates a new bind group.
+        let bind_group = self
+            .ctx_data
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: self.ctx_data.label("BindGroup", desc.label).as_deref(),
+                layout,
+                entries: &entries,
+            });
+        // Inserts it to bind groups map.
+        self.bind_groups
+            .write()
+            .await
+            .insert(bind_group_id, bind_group);
+        Ok(bind_group_id)
+    }
+
+    ///
+    pub async fn remove_bind_group(&self, id: BindGroupId) {
+        // Gets the write lock.
+        let mut bind_groups = self.bind_groups.write().await;
+        // Remove target bind group from bind groups map.
+        bind_groups.remove(&id);
+    }
+
+    /// Gets a snapshot of the ids of every live bind group.
+    pub async fn bind_group_ids(&self) -> Vec<BindGroupId> {
+        // Gets the read lock.
+        let bind_groups = self.bind_groups.read().await;
+        // Copies the keys of the bind groups map.
+        bind_groups.keys().copied().collect()
+    }
+
+    /// Creates a shader module from WGSL, reflecting the resources it binds for
+    /// [`Self::auto_layout`].
+    pub async fn create_shader_module(
+        &self,
+        label: Option<&'static str>,
+        source: &str,
+    ) -> Result<ShaderModuleId, ShaderError> {
+        // Parses and validates the module for its reflection.
+        let module = naga::front::wgsl::parse_str(source)
+            .map_err(|error| ShaderError::Parse(error.emit_to_string(source)))?;
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|error| ShaderError::Validation(error.to_string()))?;
+        let layouts = shader::reflect_layouts(&module, &info)?;
+        // Gets the write lock.
+        let mut shader_modules = self.shader_modules.write().await;
+        // Creates a new shader module id.
+        let shader_module_id = ShaderModuleId::new();
+        // Creates a new shader module.
+        let shader_module =
+            self.ctx_data
+                .device
+                .create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: self.ctx_data.label("ShaderModule", label).as_deref(),
+                    source: wgpu::ShaderSource::Wgsl(source.into()),
+                });
+        // Inserts it to shader modules map along its layouts.
+        shader_modules.insert(shader_module_id, (shader_module, layouts));
+        Ok(shader_module_id)
+    }
+
+    /// Creates a shader module from the WGSL file at `path`, resolving its include directives
+    /// with [`preprocess::preprocess_file`].
+    ///
+    /// The files are read on the calling thread.
+    pub async fn create_shader_module_from_file(
+        &self,
+        label: Option<&'static str>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ShaderModuleId, ShaderError> {
+        let source = preprocess::preprocess_file(path.as_ref())?;
+        self.create_shader_module(label, &source).await
+    }
+
+    ///
+    pub async fn remove_shader_module(&self, id: ShaderModuleId) {
+        // Gets the write lock.
+        let mut shader_modules = self.shader_modules.write().await;
+        // Remove target shader module from shader modules map.
+        shader_modules.remove(&id);
+    }
+
+    /// Gets a snapshot of the ids of every live shader module.
+    pub async fn shader_module_ids(&self) -> Vec<ShaderModuleId> {
+        // Gets the read lock.
+        let shader_modules = self.shader_modules.read().await;
+        // Copies the keys of the shader modules map.
+        shader_modules.keys().copied().collect()
+    }
+
+    /// Creates the bind group layouts a shader module binds its resources with, the layout of
+    /// group `i` is at index `i`.
+    ///
+    /// The layouts are derived from the module by [`shader::reflect_layouts`], so they can not
+    /// drift from the shader. Layouts are created anew on every call.
+    pub async fn auto_layout(
+        &self,
+        shader: ShaderModuleId,
+    ) -> Result<Vec<BindGroupLayoutId>, ShaderError> {
+        // Copies the reflected entries out of the read lock.
+        let layouts = self
+            .shader_modules
+            .read()
+            .await
+            .get(&shader)
+            .map(|(_, layouts)| layouts.clone())
+            .ok_or(ShaderError::ShaderModuleNotFound(shader))?;
+        // Creates a bind group layout per group.
+        let mut ids = Vec::with_capacity(layouts.len());
+        for entries in &layouts {
+            let id = self
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: None,
+                    entries,
+                })
+                .await;
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Creates a compute pipeline running `entry_point` of `shader`, the bind group layout of
+    /// group `i` is `layouts[i]`, e.g. the layouts of [`Self::auto_layout`].
+    ///
+    /// Nothing is presented, so it works as well with a context created without a window.
+    pub async fn create_compute_pipeline(
+        &self,
+        label: Option<&'static str>,
+        shader: ShaderModuleId,
+        entry_point: &str,
+        layouts: &[BindGroupLayoutId],
+    ) -> Result<wgpu::ComputePipeline, ShaderError> {
+        // Gets the read locks.
+        let shader_modules = self.shader_modules.read().await;
+        let bind_group_layouts = self.bind_group_layouts.read().await;
+        let (module, _) = shader_modules
+            .get(&shader)
+            .ok_or(ShaderError::ShaderModuleNotFound(shader))?;
+        let layouts = layouts
+            .iter()
+            .map(|id| {
+                bind_group_layouts
+                    .get(id)
+                    .map(|(layout, _)| layout)
+                    .ok_or(ShaderError::BindGroupLayoutNotFound(*id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Creates the pipeline layout and the pipeline.
+        let device = &self.ctx_data.device;
+        let label = self.ctx_data.label("ComputePipeline", label);
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: label.as_deref(),
+            bind_group_layouts: &layouts,
+            push_constant_ranges: &[],
+        });
+        Ok(
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: label.as_deref(),
+                layout: Some(&layout),
+                module,
+                entry_point,
+            }),
+        )
+    }
+
+    /// Records a render pass into `encoder`, resolving the ids of its attachments and commands.
+    pub async fn record_render_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        desc: &RenderPassDescriptor<'_>,
+        commands: &[RenderCommand<'_>],
+    ) -> Result<(), RenderPassError> {
+        // Gets the read locks of every resource used by the pass.
+        let texture_views = self.texture_views.read().await;
+        let bind_groups = self.bind_groups.read().await;
+        let buffers = self.buffers.read().await;
+        // Rendering to a texture uses it.
+        {
+            let mut texture_cache = self.texture_cache.write().await;
+            for attachment in desc.color_attachments {
+                texture_cache.touch_view(attachment.view);
+                if let Some(resolve_target) = attachment.resolve_target {
+                    texture_cache.touch_view(resolve_target);
+                }
+            }
+            if let Some(attachment) = &desc.depth_stencil_attachment {
+                texture_cache.touch_view(attachment.view);
+            }
+        }
+        // Resolves the attachments.
+        let view = |id| {
+            texture_views
+                .get(&id)
+                .map(|(view, _)| view)
+                .ok_or(RenderPassError::InvalidAttachment(id))
+        };
+        let mut color_attachments = Vec::with_capacity(desc.color_attachments.len());
+        for attachment in desc.color_attachments {
+            color_attachments.push(wgpu::RenderPassColorAttachment {
+                view: view(attachment.view)?,
+                resolve_target: attachment.resolve_target.map(view).transpose()?,
+                ops: attachment.ops.into(),
+            });
+        }
+        let depth_stencil_attachment = match &desc.depth_stencil_attachment {
+            Some(attachment) => Some(wgpu::RenderPassDepthStencilAttachment {
+                view: view(attachment.view)?,
+                depth_ops: attachment.depth_ops.map(Into::into),
+                stencil_ops: attachment.stencil_ops.map(Into::into),
+            }),
+            None => None,
+        };
+        // Checks every command refers to live resources and debug groups are balanced before
+        // beginning the pass.
+        let mut debug_groups = 0usize;
+        for command in commands {
+            match command {
+                RenderCommand::PushDebugGroup(_) => debug_groups += 1,
+                RenderCommand::PopDebugGroup => {
+                    debug_groups = debug_groups
+                        .checked_sub(1)
+                        .ok_or(RenderPassError::UnbalancedDebugGroups)?;
+                }
+                RenderCommand::SetBindGroup { bind_group, .. }
+                    if !bind_groups.contains_key(bind_group) =>
+                {
+                    return Err(RenderPassError::BindGroupNotFound(*bind_group));
+                }
+                RenderCommand::SetVertexBuffer { buffer, .. }
+                | RenderCommand::SetIndexBuffer { buffer, .. }
+                    if !buffers.contains_key(buffer) =>
+                {
+                    return Err(RenderPassError::BufferNotFound(*buffer));
+                }
+                _ => (),
+            }
+        }
+        if debug_groups != 0 {
+            return Err(RenderPassError::UnbalancedDebugGroups);
+        }
+        // Begins the pass and records the commands.
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: desc.label,
+            color_attachments: &color_attachments,
+            depth_stencil_attachment,
+        });
+        for command in commands {
+            match command {
+                RenderCommand::SetPipeline(pipeline) => render_pass.set_pipeline(pipeline),
+                RenderCommand::SetBindGroup { index, bind_group } => {
+                    render_pass.set_bind_group(*index, &bind_groups[bind_group], &[])
+                }
+                RenderCommand::SetVertexBuffer { slot, buffer } => {
+                    render_pass.set_vertex_buffer(*slot, buffers[buffer].0.slice(..))
+                }
+                RenderCommand::Draw {
+                    vertices,
+                    instances,
+                } => render_pass.draw(vertices.clone(), instances.clone()),
+                RenderCommand::SetIndexBuffer { buffer, format } => {
+                    render_pass.set_index_buffer(buffers[buffer].0.slice(..), (*format).into())
+                }
+                RenderCommand::DrawIndexed {
+                    indices,
+                    base_vertex,
+                    instances,
+                } => render_pass.draw_indexed(indices.clone(), *base_vertex, instances.clone()),
+                RenderCommand::PushDebugGroup(label) => render_pass.push_debug_group(label),
+                RenderCommand::PopDebugGroup => render_pass.pop_debug_group(),
+                RenderCommand::InsertDebugMarker(label) => render_pass.insert_debug_marker(label),
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a render pass of transparent `draws`, sorted back-to-front from `camera_pos` by
+    /// [`sort_transparent`] first.
+    ///
+    /// The draws should blend and test the depth of the opaque objects without writing it.
+    pub async fn record_transparent_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        desc: &RenderPassDescriptor<'_>,
+        draws: &mut Vec<Draw<'_>>,
+        camera_pos: Vec3,
+    ) -> Result<(), RenderPassError> {
+        sort_transparent(draws, camera_pos);
+        let commands: Vec<_> = draws
+            .iter()
+            .flat_map(|draw| draw.commands.iter().cloned())
+            .collect();
+        self.record_render_pass(encoder, desc, &commands).await
+    }
+
+    /// Records a pass clearing the depth of `target` to `value`, nothing else is attached so
+    /// the color of a previous pass is preserved.
+    pub async fn clear_depth_only(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: TextureViewId,
+        value: f32,
+    ) -> Result<(), RenderPassError> {
+        let desc = RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(clear depth)"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment::clear_depth(
+                target, value,
+            )),
+        };
+        self.record_render_pass(encoder, &desc, &[]).await
+    }
+
+    /// Records a depth prepass of `commands` into `depth`, then their color pass into
+    /// `color_attachments` reading that depth read-only.
+    ///
+    /// The depth is cleared to `depth_clear` first. The pipelines of `prepass` are set before
+    /// `commands` in each pass, so the commands should only bind resources and draw.
+    pub async fn record_depth_prepass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_attachments: &[RenderPassColorAttachment],
+        depth: TextureViewId,
+        depth_clear: f32,
+        prepass: DepthPrepass<'_>,
+        commands: &[RenderCommand<'_>],
+    ) -> Result<(), RenderPassError> {
+        let with_pipeline = |pipeline| {
+            iter::once(RenderCommand::SetPipeline(pipeline))
+                .chain(commands.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+        // Writes the depth of the opaque geometry.
+        let depth_desc = RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(depth prepass)"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment::clear_depth(
+                depth,
+                depth_clear,
+            )),
+        };
+        self.record_render_pass(encoder, &depth_desc, &with_pipeline(prepass.depth_pipeline))
+            .await?;
+        // Shades the fragments left visible by the prepass.
+        let color_desc = RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(depth prepass color)"),
+            color_attachments,
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment::read_only(depth)),
+        };
+        self.record_render_pass(encoder, &color_desc, &with_pipeline(prepass.color_pipeline))
+            .await
+    }
+
+    /// Records a pass clearing the color of `target` to `color`, no depth is attached so the
+    /// depth of a previous pass is preserved.
+    pub async fn clear_color_only(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: TextureViewId,
+        color: Color,
+    ) -> Result<(), RenderPassError> {
+        let desc = RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(clear color)"),
+            color_attachments: &[RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(color),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        };
+        self.record_render_pass(encoder, &desc, &[]).await
+    }
+
+    /// Gets the compute limits dispatches are checked against.
+    pub fn compute_limits(&self) -> ComputeLimits {
+        ComputeLimits::default()
+    }
+
+    /// Records a compute pass into `encoder`, resolving the ids of its commands.
+    ///
+    /// Every dispatch is checked against [`ResourceContext::compute_limits`] before the pass
+    /// begins, instead of failing wgpu validation.
+    pub async fn record_compute_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        label: Option<&'static str>,
+        commands: &[ComputeCommand<'_>],
+    ) -> Result<(), ComputePassError> {
+        // Gets the read locks of every resource used by the pass.
+        let bind_groups = self.bind_groups.read().await;
+        let buffers = self.buffers.read().await;
+        // Checks every command before beginning the pass.
+        let limits = self.compute_limits();
+        let mut debug_groups = 0usize;
+        for command in commands {
+            match *command {
+                ComputeCommand::PushDebugGroup(_) => debug_groups += 1,
+                ComputeCommand::PopDebugGroup => {
+                    debug_groups = debug_groups
+                        .checked_sub(1)
+                        .ok_or(ComputePassError::UnbalancedDebugGroups)?;
+                }
+                ComputeCommand::SetBindGroup { bind_group, .. }
+                    if !bind_groups.contains_key(&bind_group) =>
+                {
+                    return Err(ComputePassError::BindGroupNotFound(bind_group));
+                }
+                ComputeCommand::Dispatch { x, y, z } => limits.check_dispatch([x, y, z])?,
+                ComputeCommand::DispatchIndirect { buffer, offset } => {
+                    let (_, size, _) = buffers
+                        .get(&buffer)
+                        .ok_or(ComputePassError::BufferNotFound(buffer))?;
+                    // The three workgroup counts are u32.
+                    if offset % 4 != 0 || offset.saturating_add(12) > *size {
+                        return Err(ComputePassError::InvalidIndirectOffset {
+                            offset,
+                            size: *size,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        if debug_groups != 0 {
+            return Err(ComputePassError::UnbalancedDebugGroups);
+        }
+        // Begins the pass and records the commands.
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label });
+        for command in commands {
+            match command {
+                ComputeCommand::SetPipeline(pipeline) => compute_pass.set_pipeline(pipeline),
+                ComputeCommand::SetBindGroup { index, bind_group } => {
+                    compute_pass.set_bind_group(*index, &bind_groups[bind_group], &[])
+                }
+                ComputeCommand::Dispatch { x, y, z } => compute_pass.dispatch(*x, *y, *z),
+                ComputeCommand::DispatchIndirect { buffer, offset } => {
+                    compute_pass.dispatch_indirect(&buffers[buffer].0, *offset)
+                }
+                ComputeCommand::PushDebugGroup(label) => compute_pass.push_debug_group(label),
+                ComputeCommand::PopDebugGroup => compute_pass.pop_debug_group(),
+                ComputeCommand::InsertDebugMarker(label) => compute_pass.insert_debug_marker(label),
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a compute pass dispatching `pipeline` once with workgroup counts read from
+    /// `buffer` at `offset`, `bind_groups` are set from index 0.
+    pub async fn dispatch_indirect(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        bind_groups: &[BindGroupId],
+        buffer: BufferId,
+        offset: u64,
+    ) -> Result<(), ComputePassError> {
+        let commands: Vec<_> = iter::once(ComputeCommand::SetPipeline(pipeline))
+            .chain(bind_groups.iter().enumerate().map(|(index, &bind_group)| {
+                ComputeCommand::SetBindGroup {
+                    index: index as u32,
+                    bind_group,
+                }
+            }))
+            .chain(iter::once(ComputeCommand::DispatchIndirect {
+                buffer,
+                offset,
+            }))
+            .collect();
+        self.record_compute_pass(encoder, Some("CGToy - ComputePass(indirect)"), &commands)
+            .await
+    }
+
+    /// Draws `texture` stretched over `dest_rect` of the target with `sampler`.
+    ///
+    /// The quad and its pipelines are built-in resources created on first use, the texture is
+    /// alpha blended over the previous content of the target.
+    pub async fn draw_texture(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &QuadTarget,
+        texture: TextureViewId,
+        dest_rect: Rect,
+        sampler: SamplerId,
+    ) -> Result<(), RenderPassError> {
+        // Gets the write lock of the quad and the read locks of the sampled resources.
+        let mut quad = self.quad.write().await;
+        let texture_views = self.texture_views.read().await;
+        let samplers = self.samplers.read().await;
+        // Resolves the views and the sampler.
+        let (target_view, _) = texture_views
+            .get(&target.view)
+            .ok_or(RenderPassError::InvalidAttachment(target.view))?;
+        let (texture_view, _) = texture_views
+            .get(&texture)
+            .ok_or(RenderPassError::TextureViewNotFound(texture))?;
+        let (sampler, _) = samplers
+            .get(&sampler)
+            .ok_or(RenderPassError::SamplerNotFound(sampler))?;
+        // Creates the quad on the first draw.
+        let device = &self.ctx_data.device;
+        let quad = quad.get_or_insert_with(|| TexturedQuad::new(device));
+        // Every draw gets its own transform, so several draws can share one submission.
+        let transform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(quad transform)"),
+            contents: bytemuck::bytes_of(
+                &dest_rect
+                    .transform(target.width, target.height, target.scale_factor)
+                    .to_cols_array_2d(),
+            ),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(quad)"),
+            layout: &quad.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: transform.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+        quad.prepare(device, target.format);
+        // Draws the quad over the target.
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(quad)"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(quad.pipeline(target.format));
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, quad.vertex_buffer.slice(..));
+        render_pass.draw(0..4, 0..1);
+        Ok(())
+    }
 }
 
 ///
@@ -240,4 +2464,53 @@ struct ContextSharedData {
     pub adapter: Arc<wgpu::Adapter>,
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
+    pub latency_mode: LatencyMode,
+    pub safe_mode: bool,
+    pub validation: ValidationMode,
+    pub debug_names: bool,
+    pub label_counter: Arc<AtomicU64>,
+    pub submissions: Arc<SubmissionTracker>,
+}
+
+impl ContextSharedData {
+    /// Gets the present mode surfaces are configured with, only `Fifo` is used in safe mode.
+    fn present_mode(&self, mode: PresentMode) -> PresentMode {
+        if self.safe_mode && mode != PresentMode::Fifo {
+            log::warn!("Safe mode presents with Fifo instead of {:?}.", mode);
+            PresentMode::Fifo
+        } else {
+            mode
+        }
+    }
+
+    /// Gets the features of a format the device can use, the adapter ones are only allowed
+    /// with `Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`.
+    fn format_features(&self, format: TextureFormat) -> TextureFormatFeatures {
+        if self
+            .device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            self.adapter
+                .get_texture_format_features(format.into())
+                .into()
+        } else {
+            format.guaranteed_format_features()
+        }
+    }
+
+    /// Gets the debug label of a new resource.
+    ///
+    /// Explicit labels are always kept, otherwise a label is only generated with `debug_names`
+    /// or [`ValidationMode::Full`].
+    fn label(&self, kind: &str, label: Option<&'static str>) -> Option<Cow<'static, str>> {
+        match label {
+            Some(label) => Some(Cow::Borrowed(label)),
+            None if self.debug_names || self.validation == ValidationMode::Full => {
+                let index = self.label_counter.fetch_add(1, Ordering::Relaxed);
+                Some(Cow::Owned(format!("{}#{}", kind, index)))
+            }
+            None => None,
+        }
+    }
 }
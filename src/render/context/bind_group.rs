@@ -0,0 +1,326 @@
+use super::{
+    buffer::BufferId,
+    sampler::SamplerId,
+    texture::{TextureFormat, TextureId, TextureViewDimension, TextureViewId},
+    types::ShaderStages,
+};
+use std::num::{NonZeroU32, NonZeroU64};
+
+resource_id!(BindGroupLayoutId);
+
+resource_id!(BindGroupId);
+
+/// Describes a BindGroupLayout.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BindGroupLayoutDescriptor<'a> {
+    /// Debug label of the bind group layout. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
+    /// Array of entries in this BindGroupLayout.
+    pub entries: &'a [BindGroupLayoutEntry],
+}
+
+/// Describes a single binding inside a bind group.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BindGroupLayoutEntry {
+    /// Binding index. Must match shader index and be unique inside a BindGroupLayout. A binding
+    /// of index 1, would be described as `[[group(0), binding(1)]]` in shaders.
+    pub binding: u32,
+    /// Which shader stages can see this binding.
+    pub visibility: ShaderStages,
+    /// The type of the binding.
+    pub ty: BindingType,
+    /// If this value is Some, indicates this entry is an array. Array size must be 1 or greater.
+    ///
+    /// If this value is Some and `ty` is `BindingType::Texture`, [`Features::TEXTURE_BINDING_ARRAY`] must be supported.
+    ///
+    /// If this value is Some and `ty` is any other variant, bind group creation will fail.
+    pub count: Option<NonZeroU32>,
+}
+
+impl From<&BindGroupLayoutEntry> for wgpu::BindGroupLayoutEntry {
+    fn from(entry: &BindGroupLayoutEntry) -> Self {
+        Self {
+            binding: entry.binding,
+            visibility: entry.visibility.into(),
+            ty: entry.ty.into(),
+            count: entry.count,
+        }
+    }
+}
+
+/// Specific type of a binding.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum BindingType {
+    /// A buffer binding.
+    Buffer {
+        /// Sub-type of the buffer binding.
+        ty: BufferBindingType,
+        /// Indicates that the binding has a dynamic offset.
+        /// One offset must be passed to [`RenderPass::set_bind_group`] for each dynamic binding in increasing order of binding number.
+        has_dynamic_offset: bool,
+        /// Minimum size of the bound buffer range, which must hold the structure the shader
+        /// declares at this binding. `None` defers the check to every draw or dispatch.
+        ///
+        /// Binding a smaller range fails the bind group creation with
+        /// [`BindGroupError::BufferTooSmall`], and a structure larger than it fails the pipeline
+        /// creation.
+        min_binding_size: Option<NonZeroU64>,
+    },
+    /// A sampler that can be used to sample a texture.
+    Sampler {
+        /// The sampling result is produced based on more than a single color sample from a texture,
+        /// e.g. when bilinear interpolation is enabled.
+        ///
+        /// A filtering sampler can only be used with a filterable texture.
+        filtering: bool,
+        /// Use as a comparison sampler instead of a normal sampler.
+        comparison: bool,
+    },
+    /// A texture binding.
+    Texture {
+        /// Sample type of the texture binding.
+        sample_type: TextureSampleType,
+        /// Dimension of the texture view that is going to be sampled.
+        view_dimension: TextureViewDimension,
+        /// True if the texture has a sample count greater than 1. If this is true,
+        /// the texture must be read from shaders with `texture_multisampled_2d`.
+        multisampled: bool,
+    },
+    /// A storage texture.
+    ///
+    /// Note that the texture format must be specified in the shader as well.
+    StorageTexture {
+        /// Allowed access to this texture.
+        access: StorageTextureAccess,
+        /// Format of the texture.
+        format: TextureFormat,
+        /// Dimension of the texture view that is going to be sampled.
+        view_dimension: TextureViewDimension,
+    },
+}
+
+impl From<BindingType> for wgpu::BindingType {
+    fn from(ty: BindingType) -> Self {
+        match ty {
+            BindingType::Buffer {
+                ty,
+                has_dynamic_offset,
+                min_binding_size,
+            } => Self::Buffer {
+                ty: ty.into(),
+                has_dynamic_offset,
+                min_binding_size,
+            },
+            BindingType::Sampler {
+                filtering,
+                comparison,
+            } => Self::Sampler {
+                filtering,
+                comparison,
+            },
+            BindingType::Texture {
+                sample_type,
+                view_dimension,
+                multisampled,
+            } => Self::Texture {
+                sample_type: sample_type.into(),
+                view_dimension: view_dimension.into(),
+                multisampled,
+            },
+            BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension,
+            } => Self::StorageTexture {
+                access: access.into(),
+                format: format.into(),
+                view_dimension: view_dimension.into(),
+            },
+        }
+    }
+}
+
+/// Specific type of a buffer binding.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum BufferBindingType {
+    /// A buffer for uniform values.
+    #[default]
+    Uniform,
+    /// A storage buffer.
+    Storage {
+        /// If `true`, the buffer can only be read in the shader,
+        /// and it must be annotated with `read` access.
+        read_only: bool,
+    },
+}
+
+impl From<BufferBindingType> for wgpu::BufferBindingType {
+    fn from(ty: BufferBindingType) -> Self {
+        match ty {
+            BufferBindingType::Uniform => Self::Uniform,
+            BufferBindingType::Storage { read_only } => Self::Storage { read_only },
+        }
+    }
+}
+
+/// Specific type of a sample in a texture binding.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum TextureSampleType {
+    /// Sampling returns floats.
+    Float {
+        /// If `filterable` is false, the texture can't be sampled with
+        /// a filtering sampler.
+        filterable: bool,
+    },
+    /// Sampling does the depth reference comparison.
+    Depth,
+    /// Sampling returns signed integers.
+    Sint,
+    /// Sampling returns unsigned integers.
+    Uint,
+}
+
+impl Default for TextureSampleType {
+    fn default() -> Self {
+        Self::Float { filterable: true }
+    }
+}
+
+impl From<TextureSampleType> for wgpu::TextureSampleType {
+    fn from(ty: TextureSampleType) -> Self {
+        match ty {
+            TextureSampleType::Float { filterable } => Self::Float { filterable },
+            TextureSampleType::Depth => Self::Depth,
+            TextureSampleType::Sint => Self::Sint,
+            TextureSampleType::Uint => Self::Uint,
+        }
+    }
+}
+
+/// Specific type of a sample in a texture binding.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum StorageTextureAccess {
+    /// The texture can only be written in the shader and it must be annotated with `write`.
+    WriteOnly,
+    /// The texture can only be read in the shader and it must be annotated with `read`.
+    /// [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] must be enabled to use this access mode.
+    ReadOnly,
+    /// The texture can be both read and written in the shader.
+    /// [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] must be enabled to use this access mode.
+    ReadWrite,
+}
+
+impl From<StorageTextureAccess> for wgpu::StorageTextureAccess {
+    fn from(access: StorageTextureAccess) -> Self {
+        match access {
+            StorageTextureAccess::WriteOnly => Self::WriteOnly,
+            StorageTextureAccess::ReadOnly => Self::ReadOnly,
+            StorageTextureAccess::ReadWrite => Self::ReadWrite,
+        }
+    }
+}
+
+/// Describes a group of bindings and the resources to be bound.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BindGroupDescriptor<'a> {
+    /// Debug label of the bind group. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
+    /// The [`BindGroupLayoutId`] that corresponds to this bind group.
+    pub layout: BindGroupLayoutId,
+    /// The resources to bind to this bind group.
+    pub entries: &'a [BindGroupEntry],
+}
+
+/// An element of a [`BindGroupDescriptor`], consisting of a bindable resource
+/// and the slot to bind it to.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BindGroupEntry {
+    /// Slot for which binding provides resource. Corresponds to an entry of the same
+    /// binding index in the [`BindGroupLayoutDescriptor`].
+    pub binding: u32,
+    /// Resource to attach to the binding.
+    pub resource: BindingResource,
+}
+
+/// A range of a buffer bound to a binding, several bindings may share the same buffer.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BufferBinding {
+    /// The buffer to bind.
+    pub buffer: BufferId,
+    /// Base offset of the buffer, it must be a multiple of 256.
+    pub offset: u64,
+    /// Size of the binding, `None` binds the rest of the buffer after `offset`.
+    pub size: Option<NonZeroU64>,
+}
+
+/// Resource that can be bound to a pipeline.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum BindingResource {
+    /// The whole buffer is bound.
+    Buffer(BufferId),
+    /// A range of the buffer is bound.
+    BufferRange(BufferBinding),
+    ///
+    Sampler(SamplerId),
+    /// A default view of the whole texture is bound.
+    Texture(TextureId),
+    /// The texture view is bound as is.
+    TextureView(TextureViewId),
+}
+
+impl From<BufferId> for BindingResource {
+    fn from(id: BufferId) -> Self {
+        Self::Buffer(id)
+    }
+}
+
+impl From<BufferBinding> for BindingResource {
+    fn from(binding: BufferBinding) -> Self {
+        Self::BufferRange(binding)
+    }
+}
+
+impl From<SamplerId> for BindingResource {
+    fn from(id: SamplerId) -> Self {
+        Self::Sampler(id)
+    }
+}
+
+impl From<TextureId> for BindingResource {
+    fn from(id: TextureId) -> Self {
+        Self::Texture(id)
+    }
+}
+
+impl From<TextureViewId> for BindingResource {
+    fn from(id: TextureViewId) -> Self {
+        Self::TextureView(id)
+    }
+}
+
+/// Error of a bind group creation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BindGroupError {
+    /// The layout id does not belong to a live bind group layout.
+    InvalidLayout(BindGroupLayoutId),
+    /// An entry refers to a resource that does not exist.
+    ResourceNotFound(BindingResource),
+    /// A buffer range is unaligned or past the end of its buffer of `size` bytes.
+    InvalidBufferRange { binding: BufferBinding, size: u64 },
+    /// A sampler is bound to a sampler binding of the other kind, `comparison` tells whether
+    /// the sampler is a comparison sampler.
+    ///
+    /// Comparison samplers are declared `sampler_comparison` in shaders and need a
+    /// [`BindingType::Sampler`] with `comparison` set.
+    SamplerMismatch { binding: u32, comparison: bool },
+    /// A texture of `format` is bound to a texture binding of the other kind, depth formats need
+    /// [`TextureSampleType::Depth`] and color formats any other sample type.
+    TextureMismatch { binding: u32, format: TextureFormat },
+    /// A buffer range of `size` bytes is bound to a buffer binding declaring a larger
+    /// `min_binding_size`.
+    BufferTooSmall {
+        binding: u32,
+        size: u64,
+        min_binding_size: NonZeroU64,
+    },
+}
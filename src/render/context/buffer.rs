@@ -1,21 +1,19 @@
+use super::ResourceContext;
 use bitflags::bitflags;
-use uuid::Uuid;
+use std::{future::Future, ops::Range};
 
-///
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-pub struct BufferId(Uuid);
-
-impl BufferId {
-    ///
-    pub fn new() -> Self {
-        Self(Uuid::new_v4())
-    }
-}
+resource_id!(BufferId);
 
 /// Describes a Buffer.
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct BufferDescriptor {
+    /// Debug label of the buffer. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
     /// Size of a buffer.
+    ///
+    /// The allocation is rounded up to a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`], the
+    /// alignment copies and mapping work in, so any size works. Readbacks and bindings still
+    /// see the requested size, copies and writes may cover the padding.
     pub size: u64,
     /// Usages of a buffer. If the buffer is used in any way that isn’t specified here, the operation will panic.
     pub usage: BufferUsages,
@@ -28,18 +26,183 @@ pub struct BufferDescriptor {
 impl From<&BufferDescriptor> for wgpu::BufferDescriptor<'_> {
     fn from(desc: &BufferDescriptor) -> Self {
         Self {
-            label: None,
-            size: desc.size,
+            label: desc.label,
+            size: align_copy_size(desc.size),
             usage: desc.usage.into(),
             mapped_at_creation: false,
         }
     }
 }
 
+/// Rounds `size` up to a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`].
+pub fn align_copy_size(size: u64) -> u64 {
+    size.div_ceil(wgpu::COPY_BUFFER_ALIGNMENT) * wgpu::COPY_BUFFER_ALIGNMENT
+}
+
+/// Error of a buffer operation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BufferError {
+    /// The buffer does not exist.
+    BufferNotFound(BufferId),
+    /// The buffer could not be mapped for reading.
+    MapFailed(BufferId),
+    /// The buffer size is not a multiple of the size of the requested element type.
+    SizeMismatch { size: u64, element_size: u64 },
+    /// A write of `size` bytes at `offset` goes past the end of a buffer of `buffer_size`.
+    OutOfBounds {
+        offset: u64,
+        size: u64,
+        buffer_size: u64,
+    },
+    /// An offset or size of a copy or write is not a multiple of
+    /// [`wgpu::COPY_BUFFER_ALIGNMENT`], `name` tells which one.
+    Unaligned { name: &'static str, value: u64 },
+    /// The buffer lacks usages the operation requires.
+    MissingUsages(BufferUsages),
+}
+
+/// Checks a buffer created with `usage` has the `required` usages, wgpu panics otherwise.
+pub fn check_usages(usage: BufferUsages, required: BufferUsages) -> Result<(), BufferError> {
+    if usage.contains(required) {
+        Ok(())
+    } else {
+        Err(BufferError::MissingUsages(required - usage))
+    }
+}
+
+/// Checks an offset or size of a buffer copy or write is a multiple of
+/// [`wgpu::COPY_BUFFER_ALIGNMENT`], wgpu panics otherwise.
+pub fn check_copy_alignment(name: &'static str, value: u64) -> Result<(), BufferError> {
+    if value.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+        Ok(())
+    } else {
+        Err(BufferError::Unaligned { name, value })
+    }
+}
+
+/// Buffer updated by scattered writes, which are uploaded together once per frame.
+///
+/// Writes are applied to a CPU copy of the buffer and only mark their range dirty.
+/// [`DynamicBuffer::flush`] then uploads each run of overlapping or adjacent dirty ranges with
+/// a single `write_buffer`, instead of one per write. Ranges are widened to the 4 bytes
+/// alignment of buffer writes, the CPU copy provides the bytes around them.
+#[derive(Clone, Debug)]
+pub struct DynamicBuffer {
+    buffer: BufferId,
+    data: Vec<u8>,
+    /// Sorted ranges that do not overlap nor touch.
+    dirty: Vec<Range<u64>>,
+}
+
+impl DynamicBuffer {
+    /// Wraps a buffer created with [`BufferUsages::COPY_DST`] whose current content is `data`.
+    pub(super) fn new(buffer: BufferId, data: Vec<u8>) -> Self {
+        Self {
+            buffer,
+            data,
+            dirty: Vec::new(),
+        }
+    }
+
+    /// Gets the id of the buffer, to bind it.
+    pub fn id(&self) -> BufferId {
+        self.buffer
+    }
+
+    /// Gets the content of the buffer once flushed.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Writes `bytes` at `offset` into the CPU copy and marks the range dirty.
+    pub fn write(&mut self, offset: u64, bytes: &[u8]) -> Result<(), BufferError> {
+        let buffer_size = self.data.len() as u64;
+        let end = offset
+            .checked_add(bytes.len() as u64)
+            .filter(|&end| end <= buffer_size)
+            .ok_or(BufferError::OutOfBounds {
+                offset,
+                size: bytes.len() as u64,
+                buffer_size,
+            })?;
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.data[offset as usize..end as usize].copy_from_slice(bytes);
+        mark_dirty(&mut self.dirty, offset..end);
+        Ok(())
+    }
+
+    /// Gets the ranges the next flush uploads, sorted by offset.
+    pub fn dirty_ranges(&self) -> &[Range<u64>] {
+        &self.dirty
+    }
+
+    /// Uploads the dirty ranges, returning the number of `write_buffer` calls made.
+    ///
+    /// A range stays dirty until its write succeeds, so the next flush retries the ranges a
+    /// failed one did not upload.
+    pub async fn flush(&mut self, resource: &ResourceContext) -> Result<usize, BufferError> {
+        let (buffer, data) = (self.buffer, &self.data);
+        flush_ranges(&mut self.dirty, |range| {
+            resource.write_buffer(
+                buffer,
+                range.start,
+                &data[range.start as usize..range.end as usize],
+            )
+        })
+        .await
+    }
+}
+
+/// Marks `range` dirty in the sorted `dirty` ranges, widened to the 4 bytes alignment of buffer
+/// writes and merged with every range it overlaps or touches.
+fn mark_dirty(dirty: &mut Vec<Range<u64>>, range: Range<u64>) {
+    // Widens the range to the write alignment, the buffer size is aligned.
+    let alignment = wgpu::COPY_BUFFER_ALIGNMENT;
+    let mut range = range.start / alignment * alignment..range.end.div_ceil(alignment) * alignment;
+    // Merges every dirty range overlapping or touching the new one.
+    let first = dirty.partition_point(|dirty| dirty.end < range.start);
+    let last = dirty.partition_point(|dirty| dirty.start <= range.end);
+    if first < last {
+        range.start = range.start.min(dirty[first].start);
+        range.end = range.end.max(dirty[last - 1].end);
+    }
+    dirty.splice(first..last, std::iter::once(range));
+}
+
+/// Writes the `dirty` ranges in order through `write`, returning the number of writes.
+///
+/// A range is removed once its write succeeds, the range of a failed write and the ones after
+/// it stay dirty.
+async fn flush_ranges<T, F, W>(
+    dirty: &mut Vec<Range<u64>>,
+    mut write: W,
+) -> Result<usize, BufferError>
+where
+    F: Future<Output = Result<T, BufferError>>,
+    W: FnMut(Range<u64>) -> F,
+{
+    let writes = dirty.len();
+    for written in 0..writes {
+        if let Err(error) = write(dirty[written].clone()).await {
+            dirty.drain(..written);
+            return Err(error);
+        }
+    }
+    dirty.clear();
+    Ok(writes)
+}
+
 /// Describes a Buffer when allocating.
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct BufferInitDescriptor<'a> {
+    /// Debug label of the buffer. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
     /// Contents of a buffer on creation.
+    ///
+    /// They are padded with zeros to [`wgpu::COPY_BUFFER_ALIGNMENT`] on creation, so any length
+    /// works. Copies of the whole buffer still need a length that is a multiple of it.
     pub contents: &'a [u8],
     /// Usages of a buffer. If the buffer is used in any way that isn’t specified here, the operation will panic.
     pub usage: BufferUsages,
@@ -48,7 +211,7 @@ pub struct BufferInitDescriptor<'a> {
 impl<'a> From<&BufferInitDescriptor<'a>> for wgpu::util::BufferInitDescriptor<'a> {
     fn from(desc: &BufferInitDescriptor<'a>) -> Self {
         Self {
-            label: None,
+            label: desc.label,
             contents: desc.contents,
             usage: desc.usage.into(),
         }
@@ -97,3 +260,99 @@ impl From<BufferUsages> for wgpu::BufferUsages {
         Self::from_bits_truncate(usages.bits)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn align_copy_size_rounds_up_to_the_copy_alignment() {
+        assert_eq!(align_copy_size(0), 0);
+        assert_eq!(align_copy_size(1), 4);
+        assert_eq!(align_copy_size(3), 4);
+        assert_eq!(align_copy_size(4), 4);
+        assert_eq!(align_copy_size(5), 8);
+    }
+
+    #[test]
+    fn check_copy_alignment_rejects_unaligned_values() {
+        assert_eq!(check_copy_alignment("size", 0), Ok(()));
+        assert_eq!(check_copy_alignment("size", 8), Ok(()));
+        assert_eq!(
+            check_copy_alignment("offset", 6),
+            Err(BufferError::Unaligned {
+                name: "offset",
+                value: 6
+            })
+        );
+    }
+
+    /// Marks each range of bounds dirty in order, returning the bounds of the dirty ranges.
+    fn dirty_after(ranges: &[(u64, u64)]) -> Vec<(u64, u64)> {
+        let mut dirty = Vec::new();
+        for &(start, end) in ranges {
+            mark_dirty(&mut dirty, start..end);
+        }
+        dirty.iter().map(|range| (range.start, range.end)).collect()
+    }
+
+    #[test]
+    fn mark_dirty_merges_overlapping_and_adjacent_ranges() {
+        assert_eq!(dirty_after(&[(0, 8), (4, 12)]), [(0, 12)]);
+        assert_eq!(dirty_after(&[(0, 8), (8, 16)]), [(0, 16)]);
+        assert_eq!(dirty_after(&[(0, 4), (12, 16), (4, 12)]), [(0, 16)]);
+    }
+
+    #[test]
+    fn mark_dirty_keeps_disjoint_ranges_sorted() {
+        assert_eq!(
+            dirty_after(&[(16, 20), (0, 4), (8, 12)]),
+            [(0, 4), (8, 12), (16, 20)]
+        );
+        assert_eq!(
+            dirty_after(&[(24, 28), (0, 4), (12, 16), (4, 8)]),
+            [(0, 8), (12, 16), (24, 28)]
+        );
+    }
+
+    #[test]
+    fn mark_dirty_widens_ranges_to_the_write_alignment() {
+        assert_eq!(dirty_after(&[(1, 2)]), [(0, 4)]);
+        assert_eq!(dirty_after(&[(3, 9)]), [(0, 12)]);
+        // Widened ranges touch, so they merge.
+        assert_eq!(dirty_after(&[(1, 3), (6, 7)]), [(0, 8)]);
+    }
+
+    #[tokio::test]
+    async fn flush_ranges_keeps_the_ranges_of_a_failed_write() {
+        let mut dirty = vec![0..4, 8..12, 16..20];
+        let mut written = Vec::new();
+        let result = flush_ranges(&mut dirty, |range| {
+            let failed = range.start == 8;
+            written.push(range);
+            std::future::ready(if failed {
+                Err(BufferError::MapFailed(BufferId::new()))
+            } else {
+                Ok(())
+            })
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(written, [0..4, 8..12]);
+        assert_eq!(dirty, [8..12, 16..20]);
+        // The next flush retries them.
+        let result = flush_ranges(&mut dirty, |_| std::future::ready(Ok(()))).await;
+        assert_eq!(result, Ok(2));
+        assert!(dirty.is_empty());
+    }
+
+    #[test]
+    fn check_usages_reports_the_missing_usages() {
+        let usage = BufferUsages::COPY_SRC | BufferUsages::UNIFORM;
+        assert_eq!(check_usages(usage, BufferUsages::COPY_SRC), Ok(()));
+        assert_eq!(
+            check_usages(usage, BufferUsages::COPY_SRC | BufferUsages::COPY_DST),
+            Err(BufferError::MissingUsages(BufferUsages::COPY_DST))
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use super::texture::TextureFormat;
+use bitflags::bitflags;
+
+/// Alpha blend factor.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum BlendFactor {
+    /// 0.0
+    Zero,
+    /// 1.0
+    One,
+    /// S.component
+    Src,
+    /// 1.0 - S.component
+    OneMinusSrc,
+    /// S.alpha
+    SrcAlpha,
+    /// 1.0 - S.alpha
+    OneMinusSrcAlpha,
+    /// D.component
+    Dst,
+    /// 1.0 - D.component
+    OneMinusDst,
+    /// D.alpha
+    DstAlpha,
+    /// 1.0 - D.alpha
+    OneMinusDstAlpha,
+    /// min(S.alpha, 1.0 - D.alpha)
+    SrcAlphaSaturated,
+    /// Constant
+    Constant,
+    /// 1.0 - Constant
+    OneMinusConstant,
+}
+
+impl From<BlendFactor> for wgpu::BlendFactor {
+    fn from(factor: BlendFactor) -> Self {
+        match factor {
+            BlendFactor::Zero => Self::Zero,
+            BlendFactor::One => Self::One,
+            BlendFactor::Src => Self::Src,
+            BlendFactor::OneMinusSrc => Self::OneMinusSrc,
+            BlendFactor::SrcAlpha => Self::SrcAlpha,
+            BlendFactor::OneMinusSrcAlpha => Self::OneMinusSrcAlpha,
+            BlendFactor::Dst => Self::Dst,
+            BlendFactor::OneMinusDst => Self::OneMinusDst,
+            BlendFactor::DstAlpha => Self::DstAlpha,
+            BlendFactor::OneMinusDstAlpha => Self::OneMinusDstAlpha,
+            BlendFactor::SrcAlphaSaturated => Self::SrcAlphaSaturated,
+            BlendFactor::Constant => Self::Constant,
+            BlendFactor::OneMinusConstant => Self::OneMinusConstant,
+        }
+    }
+}
+
+/// Alpha blend operation.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum BlendOperation {
+    /// Src + Dst
+    #[default]
+    Add,
+    /// Src - Dst
+    Subtract,
+    /// Dst - Src
+    ReverseSubtract,
+    /// min(Src, Dst)
+    Min,
+    /// max(Src, Dst)
+    Max,
+}
+
+impl From<BlendOperation> for wgpu::BlendOperation {
+    fn from(operation: BlendOperation) -> Self {
+        match operation {
+            BlendOperation::Add => Self::Add,
+            BlendOperation::Subtract => Self::Subtract,
+            BlendOperation::ReverseSubtract => Self::ReverseSubtract,
+            BlendOperation::Min => Self::Min,
+            BlendOperation::Max => Self::Max,
+        }
+    }
+}
+
+/// Describes the blend component of a pipeline.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BlendComponent {
+    /// Multiplier for the source, which is produced by the fragment shader.
+    pub src_factor: BlendFactor,
+    /// Multiplier for the destination, which is stored in the target.
+    pub dst_factor: BlendFactor,
+    /// The binary operation applied to the source and destination, multiplied by their
+    /// respective factors.
+    pub operation: BlendOperation,
+}
+
+impl BlendComponent {
+    /// Default blending state that replaces destination with the source.
+    pub const REPLACE: Self = Self {
+        src_factor: BlendFactor::One,
+        dst_factor: BlendFactor::Zero,
+        operation: BlendOperation::Add,
+    };
+
+    /// Blend state of (1 * src) + ((1 - src_alpha) * dst)
+    pub const OVER: Self = Self {
+        src_factor: BlendFactor::One,
+        dst_factor: BlendFactor::OneMinusSrcAlpha,
+        operation: BlendOperation::Add,
+    };
+}
+
+impl From<BlendComponent> for wgpu::BlendComponent {
+    fn from(component: BlendComponent) -> Self {
+        Self {
+            src_factor: component.src_factor.into(),
+            dst_factor: component.dst_factor.into(),
+            operation: component.operation.into(),
+        }
+    }
+}
+
+/// Describe the blend state of a render pipeline.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct BlendState {
+    /// Color equation.
+    pub color: BlendComponent,
+    /// Alpha equation.
+    pub alpha: BlendComponent,
+}
+
+impl BlendState {
+    /// Blend mode that does no color blending, just overwrites the output with the contents of the shader.
+    pub const REPLACE: Self = Self {
+        color: BlendComponent::REPLACE,
+        alpha: BlendComponent::REPLACE,
+    };
+
+    /// Blend mode that does standard alpha blending with non-premultiplied alpha.
+    pub const ALPHA_BLENDING: Self = Self {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation: BlendOperation::Add,
+        },
+        alpha: BlendComponent::OVER,
+    };
+
+    /// Blend mode that does standard alpha blending with premultiplied alpha.
+    pub const PREMULTIPLIED_ALPHA_BLENDING: Self = Self {
+        color: BlendComponent::OVER,
+        alpha: BlendComponent::OVER,
+    };
+}
+
+impl From<BlendState> for wgpu::BlendState {
+    fn from(state: BlendState) -> Self {
+        Self {
+            color: state.color.into(),
+            alpha: state.alpha.into(),
+        }
+    }
+}
+
+bitflags! {
+    /// Color write mask. Disabled color channels will not be written to.
+    pub struct ColorWrites: u32 {
+        /// Enable red channel writes
+        const RED = 1 << 0;
+        /// Enable green channel writes
+        const GREEN = 1 << 1;
+        /// Enable blue channel writes
+        const BLUE = 1 << 2;
+        /// Enable alpha channel writes
+        const ALPHA = 1 << 3;
+        /// Enable red, green, and blue channel writes
+        const COLOR = Self::RED.bits | Self::GREEN.bits | Self::BLUE.bits;
+        /// Enable writes to all channels.
+        const ALL = Self::RED.bits | Self::GREEN.bits | Self::BLUE.bits | Self::ALPHA.bits;
+    }
+}
+
+impl Default for ColorWrites {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl From<ColorWrites> for wgpu::ColorWrites {
+    fn from(writes: ColorWrites) -> Self {
+        Self::from_bits_truncate(writes.bits)
+    }
+}
+
+/// Describes the color state of a render pipeline, one per `[[location]]` of the fragment
+/// output.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct ColorTargetState {
+    /// The texture format of the image that this pipeline will render to.
+    pub format: TextureFormat,
+    /// The blending that is used for this pipeline, `None` replaces the target.
+    pub blend: Option<BlendState>,
+    /// Mask which enables/disables writes to different color/alpha channel.
+    pub write_mask: ColorWrites,
+}
+
+impl From<TextureFormat> for ColorTargetState {
+    fn from(format: TextureFormat) -> Self {
+        Self {
+            format,
+            blend: Some(BlendState::REPLACE),
+            write_mask: ColorWrites::ALL,
+        }
+    }
+}
+
+impl From<ColorTargetState> for wgpu::ColorTargetState {
+    fn from(state: ColorTargetState) -> Self {
+        Self {
+            format: state.format.into(),
+            blend: state.blend.map(Into::into),
+            write_mask: state.write_mask.into(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use super::{bind_group::BindGroupId, buffer::BufferId};
+
+/// Compute limits of the device, dispatches are checked against them before being recorded.
+///
+/// wgpu does not report these limits yet, [`ComputeLimits::default`] holds the WebGPU defaults
+/// which every adapter supports.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct ComputeLimits {
+    /// Maximum number of workgroups dispatched in each dimension.
+    pub max_compute_workgroups_per_dimension: u32,
+    /// Maximum `x` of the `workgroup_size` of a compute entry point.
+    pub max_compute_workgroup_size_x: u32,
+    /// Maximum `y` of the `workgroup_size` of a compute entry point.
+    pub max_compute_workgroup_size_y: u32,
+    /// Maximum `z` of the `workgroup_size` of a compute entry point.
+    pub max_compute_workgroup_size_z: u32,
+    /// Maximum product of the `workgroup_size` of a compute entry point.
+    pub max_compute_invocations_per_workgroup: u32,
+}
+
+impl Default for ComputeLimits {
+    fn default() -> Self {
+        Self {
+            max_compute_workgroups_per_dimension: 65535,
+            max_compute_workgroup_size_x: 256,
+            max_compute_workgroup_size_y: 256,
+            max_compute_workgroup_size_z: 64,
+            max_compute_invocations_per_workgroup: 256,
+        }
+    }
+}
+
+impl ComputeLimits {
+    /// Checks the number of workgroups of a dispatch.
+    pub fn check_dispatch(&self, workgroups: [u32; 3]) -> Result<(), ComputePassError> {
+        for (dimension, requested) in ["x", "y", "z"]
+            .iter()
+            .copied()
+            .zip(workgroups.iter().copied())
+        {
+            if requested > self.max_compute_workgroups_per_dimension {
+                return Err(ComputePassError::WorkgroupCountExceeded {
+                    limit: "max_compute_workgroups_per_dimension",
+                    dimension,
+                    requested,
+                    max: self.max_compute_workgroups_per_dimension,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the `workgroup_size` a compute entry point is declared with.
+    pub fn check_workgroup_size(&self, size: [u32; 3]) -> Result<(), ComputePassError> {
+        let limits = [
+            (
+                "max_compute_workgroup_size_x",
+                self.max_compute_workgroup_size_x,
+            ),
+            (
+                "max_compute_workgroup_size_y",
+                self.max_compute_workgroup_size_y,
+            ),
+            (
+                "max_compute_workgroup_size_z",
+                self.max_compute_workgroup_size_z,
+            ),
+        ];
+        for ((limit, max), requested) in limits.iter().copied().zip(size.iter().copied()) {
+            if requested > max {
+                return Err(ComputePassError::WorkgroupSizeExceeded {
+                    limit,
+                    requested,
+                    max,
+                });
+            }
+        }
+        let invocations = size.iter().map(|&size| size as u64).product::<u64>();
+        if invocations > self.max_compute_invocations_per_workgroup as u64 {
+            return Err(ComputePassError::WorkgroupSizeExceeded {
+                limit: "max_compute_invocations_per_workgroup",
+                requested: invocations.min(u32::MAX as u64) as u32,
+                max: self.max_compute_invocations_per_workgroup,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Command recorded into a compute pass, resources are referred to by their ids.
+#[derive(Clone, Debug)]
+pub enum ComputeCommand<'a> {
+    /// Sets the active compute pipeline.
+    SetPipeline(&'a wgpu::ComputePipeline),
+    /// Sets the active bind group for a given bind group index.
+    SetBindGroup { index: u32, bind_group: BindGroupId },
+    /// Dispatches `x * y * z` workgroups of the active pipeline.
+    Dispatch { x: u32, y: u32, z: u32 },
+    /// Dispatches the workgroups counted by three `u32` in `buffer` at `offset`.
+    ///
+    /// The counts are read on the GPU, so an earlier pass can compute them without a round
+    /// trip to the CPU. The buffer needs [`BufferUsages::INDIRECT`] and the counts are not
+    /// checked against the compute limits.
+    ///
+    /// [`BufferUsages::INDIRECT`]: super::buffer::BufferUsages::INDIRECT
+    DispatchIndirect { buffer: BufferId, offset: u64 },
+    /// Opens a group of the following commands, shown nested under `label` by graphics
+    /// debuggers such as RenderDoc and PIX.
+    PushDebugGroup(&'a str),
+    /// Closes the last opened debug group.
+    PopDebugGroup,
+    /// Marks the point between two commands with `label` in graphics debuggers.
+    InsertDebugMarker(&'a str),
+}
+
+/// Error of a compute pass recording.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ComputePassError {
+    /// A command refers to a bind group that does not exist.
+    BindGroupNotFound(BindGroupId),
+    /// A command refers to a buffer that does not exist.
+    BufferNotFound(BufferId),
+    /// The workgroup counts of an indirect dispatch are unaligned or past the end of the buffer.
+    ///
+    /// The offset must be a multiple of 4 and leave 12 bytes in the buffer.
+    InvalidIndirectOffset { offset: u64, size: u64 },
+    /// A dispatch requests more workgroups along `dimension` than `limit` allows.
+    WorkgroupCountExceeded {
+        limit: &'static str,
+        dimension: &'static str,
+        requested: u32,
+        max: u32,
+    },
+    /// A workgroup size is larger than `limit` allows.
+    WorkgroupSizeExceeded {
+        limit: &'static str,
+        requested: u32,
+        max: u32,
+    },
+    /// A debug group is popped without being pushed, or left open at the end of the pass.
+    UnbalancedDebugGroups,
+}
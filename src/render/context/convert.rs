@@ -0,0 +1,79 @@
+use super::texture::TextureFormat;
+use std::collections::HashMap;
+
+/// Built-in resources of [`ResourceContext::convert_texture`], created on the first conversion.
+///
+/// [`ResourceContext::convert_texture`]: super::ResourceContext::convert_texture
+pub(super) struct TextureConverter {
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    shader: wgpu::ShaderModule,
+    pipelines: HashMap<TextureFormat, wgpu::RenderPipeline>,
+}
+
+impl TextureConverter {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("CGToy - BindGroupLayout(convert)"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("CGToy - PipelineLayout(convert)"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(convert)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/convert.wgsl").into()),
+        });
+        Self {
+            bind_group_layout,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Creates the pipeline writing `format` unless it is already cached.
+    pub fn prepare(&mut self, device: &wgpu::Device, format: TextureFormat) {
+        let Self {
+            pipeline_layout,
+            shader,
+            pipelines,
+            ..
+        } = self;
+        pipelines.entry(format).or_insert_with(|| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("CGToy - Pipeline(convert)"),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: "main",
+                    buffers: &[],
+                },
+                primitive: Default::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: "main",
+                    targets: &[wgpu::TextureFormat::from(format).into()],
+                }),
+            })
+        });
+    }
+
+    /// Gets the pipeline writing `format`, it must have been prepared.
+    pub fn pipeline(&self, format: TextureFormat) -> &wgpu::RenderPipeline {
+        &self.pipelines[&format]
+    }
+}
@@ -0,0 +1,245 @@
+use super::{
+    buffer::BufferError,
+    texture::{TextureError, TextureFormat},
+};
+use std::{fs, io, path::Path};
+
+/// Identifier every OpenEXR file starts with.
+const EXR_MAGIC: [u8; 4] = [0x76, 0x2F, 0x31, 0x01];
+/// Version 2 of a single part scanline file, with short attribute names.
+const EXR_VERSION: [u8; 4] = [2, 0, 0, 0];
+
+/// Error of a buffer or texture dump.
+#[derive(Debug)]
+pub enum DumpError {
+    Io(io::Error),
+    /// The PNG could not be encoded.
+    Image(image::ImageError),
+    /// The buffer could not be read back.
+    Buffer(BufferError),
+    /// The texture could not be read back.
+    Texture(TextureError),
+    /// The format has no image encoding, see [`extension`].
+    UnsupportedFormat(TextureFormat),
+}
+
+impl From<io::Error> for DumpError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// How the texels of a format are saved.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Encoding {
+    /// 8 bits per channel unorm texels, saved as PNG.
+    Png,
+    /// Float texels of `channels` samples of `sample_size` bytes each, saved as OpenEXR.
+    Exr { channels: usize, sample_size: usize },
+}
+
+fn encoding(format: TextureFormat) -> Option<Encoding> {
+    let exr = |channels, sample_size| {
+        Some(Encoding::Exr {
+            channels,
+            sample_size,
+        })
+    };
+    match format {
+        TextureFormat::R8Unorm
+        | TextureFormat::Rg8Unorm
+        | TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => Some(Encoding::Png),
+        TextureFormat::R16Float => exr(1, 2),
+        TextureFormat::Rg16Float => exr(2, 2),
+        TextureFormat::Rgba16Float => exr(4, 2),
+        TextureFormat::R32Float => exr(1, 4),
+        TextureFormat::Rg32Float => exr(2, 4),
+        TextureFormat::Rgba32Float => exr(4, 4),
+        _ => None,
+    }
+}
+
+/// Gets the extension of the file a texture of `format` is dumped to, `None` if the format
+/// can not be dumped.
+///
+/// 8 bits per channel unorm formats are saved as `png`, 16 and 32 bits float formats as `exr`.
+pub fn extension(format: TextureFormat) -> Option<&'static str> {
+    encoding(format).map(|encoding| match encoding {
+        Encoding::Png => "png",
+        Encoding::Exr { .. } => "exr",
+    })
+}
+
+/// Saves tightly packed texels of `format` to `path`, in the encoding [`extension`] tells
+/// whatever the extension of `path`.
+pub(super) fn save_texels(
+    path: &Path,
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    texels: &[u8],
+) -> Result<(), DumpError> {
+    match encoding(format).ok_or(DumpError::UnsupportedFormat(format))? {
+        Encoding::Png => {
+            // Expands the texels to RGBA, missing channels are zero and alpha is opaque.
+            let rgba: Vec<u8> = match format {
+                TextureFormat::R8Unorm => texels.iter().flat_map(|&r| [r, 0, 0, 255]).collect(),
+                TextureFormat::Rg8Unorm => texels
+                    .chunks_exact(2)
+                    .flat_map(|rg| [rg[0], rg[1], 0, 255])
+                    .collect(),
+                TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => texels
+                    .chunks_exact(4)
+                    .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+                    .collect(),
+                _ => texels.to_vec(),
+            };
+            image::save_buffer_with_format(
+                path,
+                &rgba,
+                width,
+                height,
+                image::ColorType::Rgba8,
+                image::ImageFormat::Png,
+            )
+            .map_err(DumpError::Image)
+        }
+        Encoding::Exr {
+            channels,
+            sample_size,
+        } => {
+            let exr = encode_exr(width, height, channels, sample_size, texels);
+            fs::write(path, exr).map_err(DumpError::Io)
+        }
+    }
+}
+
+/// Encodes texels of `channels` float samples as an uncompressed OpenEXR image, each sample is
+/// a `half` of 2 bytes or a `float` of 4 bytes.
+///
+/// The samples are stored little endian as the GPU reads them back, which is the byte order of
+/// OpenEXR too. Channels are named `R`, `G`, `B` and `A` in order.
+fn encode_exr(
+    width: u32,
+    height: u32,
+    channels: usize,
+    sample_size: usize,
+    texels: &[u8],
+) -> Vec<u8> {
+    // Channels are listed and stored in alphabetical order of their names.
+    let mut names: Vec<(&str, usize)> = ["R", "G", "B", "A"][..channels]
+        .iter()
+        .copied()
+        .zip(0..)
+        .collect();
+    names.sort_unstable();
+    let pixel_type: i32 = if sample_size == 2 { 1 } else { 2 };
+    let mut channel_list = Vec::new();
+    for &(name, _) in &names {
+        channel_list.extend_from_slice(name.as_bytes());
+        channel_list.push(0);
+        channel_list.extend_from_slice(&pixel_type.to_le_bytes());
+        // Perceptually linear flag and reserved bytes, then the x and y sampling.
+        channel_list.extend_from_slice(&[0; 4]);
+        channel_list.extend_from_slice(&1i32.to_le_bytes());
+        channel_list.extend_from_slice(&1i32.to_le_bytes());
+    }
+    channel_list.push(0);
+    let window: Vec<u8> = [0, 0, width as i32 - 1, height as i32 - 1]
+        .iter()
+        .flat_map(|value: &i32| value.to_le_bytes())
+        .collect();
+    // Writes the header, every attribute is its name, its type, its size and its value.
+    let mut exr = Vec::new();
+    exr.extend_from_slice(&EXR_MAGIC);
+    exr.extend_from_slice(&EXR_VERSION);
+    let mut attribute = |name: &str, ty: &str, value: &[u8]| {
+        for text in [name, ty] {
+            exr.extend_from_slice(text.as_bytes());
+            exr.push(0);
+        }
+        exr.extend_from_slice(&(value.len() as i32).to_le_bytes());
+        exr.extend_from_slice(value);
+    };
+    attribute("channels", "chlist", &channel_list);
+    attribute("compression", "compression", &[0]);
+    attribute("dataWindow", "box2i", &window);
+    attribute("displayWindow", "box2i", &window);
+    attribute("lineOrder", "lineOrder", &[0]);
+    attribute("pixelAspectRatio", "float", &1f32.to_le_bytes());
+    attribute("screenWindowCenter", "v2f", &[0; 8]);
+    attribute("screenWindowWidth", "float", &1f32.to_le_bytes());
+    exr.push(0);
+    // Writes the offset of every scanline, then the scanlines with one channel after another.
+    let texel_size = channels * sample_size;
+    let line_size = width as usize * texel_size;
+    let lines_offset = exr.len() + height as usize * 8;
+    for y in 0..height as usize {
+        let offset = (lines_offset + y * (8 + line_size)) as u64;
+        exr.extend_from_slice(&offset.to_le_bytes());
+    }
+    for (y, line) in texels.chunks_exact(line_size).enumerate() {
+        exr.extend_from_slice(&(y as i32).to_le_bytes());
+        exr.extend_from_slice(&(line_size as i32).to_le_bytes());
+        for &(_, channel) in &names {
+            for texel in line.chunks_exact(texel_size) {
+                let sample = channel * sample_size;
+                exr.extend_from_slice(&texel[sample..sample + sample_size]);
+            }
+        }
+    }
+    exr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exr_header_and_offsets() {
+        let texels: Vec<u8> = [1f32, 2f32].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let exr = encode_exr(1, 2, 1, 4, &texels);
+        let header: &[&[u8]] = &[
+            b"\x76\x2f\x31\x01\x02\x00\x00\x00",
+            b"channels\0chlist\0\x13\0\0\0R\0\x02\0\0\0\0\0\0\0\x01\0\0\0\x01\0\0\0\0",
+            b"compression\0compression\0\x01\0\0\0\0",
+            b"dataWindow\0box2i\0\x10\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x01\0\0\0",
+            b"displayWindow\0box2i\0\x10\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\x01\0\0\0",
+            b"lineOrder\0lineOrder\0\x01\0\0\0\0",
+            b"pixelAspectRatio\0float\0\x04\0\0\0\0\0\x80\x3f",
+            b"screenWindowCenter\0v2f\0\x08\0\0\0\0\0\0\0\0\0\0\0",
+            b"screenWindowWidth\0float\0\x04\0\0\0\0\0\x80\x3f",
+            b"\0",
+        ];
+        let header = header.concat();
+        assert_eq!(header.len(), 277);
+        assert_eq!(&exr[..277], &header[..]);
+        // The scanlines start after the offset table, each is its y, its size and its samples.
+        assert_eq!(&exr[277..285], &293u64.to_le_bytes());
+        assert_eq!(&exr[285..293], &305u64.to_le_bytes());
+        assert_eq!(&exr[293..305], b"\0\0\0\0\x04\0\0\0\0\0\x80\x3f");
+        assert_eq!(&exr[305..], b"\x01\0\0\0\x04\0\0\0\0\0\0\x40");
+    }
+
+    #[test]
+    fn exr_channels_are_stored_in_name_order() {
+        // Two half texels with their red samples before their green ones.
+        let texels = [1, 2, 3, 4, 5, 6, 7, 8];
+        let exr = encode_exr(2, 1, 2, 2, &texels);
+        let channels = b"channels\0chlist\0\x25\0\0\0G\0\x01\0\0\0\0\0\0\0\x01\0\0\0\x01\0\0\0R\0";
+        assert_eq!(&exr[8..8 + channels.len()], &channels[..]);
+        // The green samples of the scanline come first.
+        let line = &exr[exr.len() - 16..];
+        assert_eq!(line, b"\0\0\0\0\x08\0\0\0\x03\x04\x07\x08\x01\x02\x05\x06");
+    }
+
+    #[test]
+    fn extension_of_formats() {
+        assert_eq!(extension(TextureFormat::Rgba8UnormSrgb), Some("png"));
+        assert_eq!(extension(TextureFormat::Rgba16Float), Some("exr"));
+        assert_eq!(extension(TextureFormat::Depth32Float), None);
+    }
+}
@@ -0,0 +1,148 @@
+/// Names of the wgpu features, spelled as their constants.
+const FEATURE_NAMES: &[(&str, wgpu::Features)] = &[
+    ("DEPTH_CLAMPING", wgpu::Features::DEPTH_CLAMPING),
+    (
+        "TEXTURE_COMPRESSION_BC",
+        wgpu::Features::TEXTURE_COMPRESSION_BC,
+    ),
+    ("TIMESTAMP_QUERY", wgpu::Features::TIMESTAMP_QUERY),
+    (
+        "PIPELINE_STATISTICS_QUERY",
+        wgpu::Features::PIPELINE_STATISTICS_QUERY,
+    ),
+    (
+        "MAPPABLE_PRIMARY_BUFFERS",
+        wgpu::Features::MAPPABLE_PRIMARY_BUFFERS,
+    ),
+    (
+        "TEXTURE_BINDING_ARRAY",
+        wgpu::Features::TEXTURE_BINDING_ARRAY,
+    ),
+    ("BUFFER_BINDING_ARRAY", wgpu::Features::BUFFER_BINDING_ARRAY),
+    (
+        "STORAGE_RESOURCE_BINDING_ARRAY",
+        wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY,
+    ),
+    (
+        "SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING",
+        wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+    ),
+    (
+        "UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING",
+        wgpu::Features::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+    ),
+    (
+        "UNSIZED_BINDING_ARRAY",
+        wgpu::Features::UNSIZED_BINDING_ARRAY,
+    ),
+    ("MULTI_DRAW_INDIRECT", wgpu::Features::MULTI_DRAW_INDIRECT),
+    (
+        "MULTI_DRAW_INDIRECT_COUNT",
+        wgpu::Features::MULTI_DRAW_INDIRECT_COUNT,
+    ),
+    ("PUSH_CONSTANTS", wgpu::Features::PUSH_CONSTANTS),
+    (
+        "ADDRESS_MODE_CLAMP_TO_BORDER",
+        wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER,
+    ),
+    (
+        "NON_FILL_POLYGON_MODE",
+        wgpu::Features::NON_FILL_POLYGON_MODE,
+    ),
+    (
+        "TEXTURE_COMPRESSION_ETC2",
+        wgpu::Features::TEXTURE_COMPRESSION_ETC2,
+    ),
+    (
+        "TEXTURE_COMPRESSION_ASTC_LDR",
+        wgpu::Features::TEXTURE_COMPRESSION_ASTC_LDR,
+    ),
+    (
+        "TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES",
+        wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+    ),
+    ("SHADER_FLOAT64", wgpu::Features::SHADER_FLOAT64),
+    (
+        "VERTEX_ATTRIBUTE_64BIT",
+        wgpu::Features::VERTEX_ATTRIBUTE_64BIT,
+    ),
+    (
+        "CONSERVATIVE_RASTERIZATION",
+        wgpu::Features::CONSERVATIVE_RASTERIZATION,
+    ),
+    (
+        "VERTEX_WRITABLE_STORAGE",
+        wgpu::Features::VERTEX_WRITABLE_STORAGE,
+    ),
+    ("CLEAR_COMMANDS", wgpu::Features::CLEAR_COMMANDS),
+    (
+        "SPIRV_SHADER_PASSTHROUGH",
+        wgpu::Features::SPIRV_SHADER_PASSTHROUGH,
+    ),
+    (
+        "SHADER_PRIMITIVE_INDEX",
+        wgpu::Features::SHADER_PRIMITIVE_INDEX,
+    ),
+];
+
+/// Set of wgpu features, convertible to and from the names of their constants so they can be
+/// picked from a command line or a config file.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Features(pub wgpu::Features);
+
+impl Features {
+    /// Parses feature names such as `PUSH_CONSTANTS`, ignoring case and surrounding whitespace.
+    ///
+    /// Empty names are skipped, so a trailing comma in a list is harmless.
+    pub fn from_names(names: &[&str]) -> Result<wgpu::Features, UnknownFeature> {
+        names
+            .iter()
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .try_fold(wgpu::Features::empty(), |features, name| {
+                FEATURE_NAMES
+                    .iter()
+                    .find(|(feature_name, _)| feature_name.eq_ignore_ascii_case(name))
+                    .map(|&(_, feature)| features | feature)
+                    .ok_or_else(|| UnknownFeature {
+                        name: name.to_owned(),
+                        valid: Self::valid_names(),
+                    })
+            })
+    }
+
+    /// Gets the names of the features in the set, in bit order.
+    pub fn names(self) -> Vec<&'static str> {
+        FEATURE_NAMES
+            .iter()
+            .filter(|(_, feature)| self.0.contains(*feature))
+            .map(|&(name, _)| name)
+            .collect()
+    }
+
+    /// Gets the name of every feature [`Self::from_names`] accepts.
+    pub fn valid_names() -> Vec<&'static str> {
+        FEATURE_NAMES.iter().map(|&(name, _)| name).collect()
+    }
+}
+
+impl From<wgpu::Features> for Features {
+    fn from(features: wgpu::Features) -> Self {
+        Self(features)
+    }
+}
+
+impl From<Features> for wgpu::Features {
+    fn from(features: Features) -> Self {
+        features.0
+    }
+}
+
+/// Error of [`Features::from_names`], no feature has the name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownFeature {
+    /// Name that failed to parse.
+    pub name: String,
+    /// Names of the known features.
+    pub valid: Vec<&'static str>,
+}
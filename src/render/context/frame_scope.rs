@@ -0,0 +1,95 @@
+use super::{
+    buffer::{BufferDescriptor, BufferId},
+    texture::{TextureDescriptor, TextureError, TextureId},
+    ResourceContext,
+};
+
+/// Scope of the transient resources of a frame, created by [`RenderContext::begin_frame`].
+///
+/// Textures and buffers created through the scope are removed from the resource context when
+/// it ends, either by [`FrameScope::end`] or when it is dropped. Dropping it removes them from
+/// a task spawned on the current tokio runtime, await [`FrameScope::end`] to have them removed
+/// before going on.
+///
+/// [`RenderContext::begin_frame`]: super::RenderContext::begin_frame
+pub struct FrameScope {
+    resource: ResourceContext,
+    textures: Vec<TextureId>,
+    buffers: Vec<BufferId>,
+}
+
+impl FrameScope {
+    pub(super) fn new(resource: ResourceContext) -> Self {
+        Self {
+            resource,
+            textures: Vec::new(),
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Creates a texture removed at the end of the frame.
+    pub async fn create_transient_texture(
+        &mut self,
+        desc: &TextureDescriptor,
+    ) -> Result<TextureId, TextureError> {
+        let texture = self.resource.create_texture(desc).await?;
+        self.textures.push(texture);
+        Ok(texture)
+    }
+
+    /// Creates a buffer removed at the end of the frame.
+    pub async fn create_transient_buffer(&mut self, desc: &BufferDescriptor) -> BufferId {
+        let buffer = self.resource.create_buffer(desc).await;
+        self.buffers.push(buffer);
+        buffer
+    }
+
+    /// Gets the resource context the transient resources are created in.
+    pub fn resource(&self) -> &ResourceContext {
+        &self.resource
+    }
+
+    /// Ends the frame, removing every transient resource.
+    pub async fn end(mut self) {
+        remove_transients(
+            &self.resource,
+            std::mem::take(&mut self.textures),
+            std::mem::take(&mut self.buffers),
+        )
+        .await;
+    }
+}
+
+impl Drop for FrameScope {
+    fn drop(&mut self) {
+        if self.textures.is_empty() && self.buffers.is_empty() {
+            return;
+        }
+        let resource = self.resource.clone();
+        let textures = std::mem::take(&mut self.textures);
+        let buffers = std::mem::take(&mut self.buffers);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move { remove_transients(&resource, textures, buffers).await });
+            }
+            Err(_) => log::warn!(
+                "FrameScope dropped outside of a tokio runtime, leaking {} textures and {} buffers.",
+                textures.len(),
+                buffers.len()
+            ),
+        }
+    }
+}
+
+async fn remove_transients(
+    resource: &ResourceContext,
+    textures: Vec<TextureId>,
+    buffers: Vec<BufferId>,
+) {
+    for texture in textures {
+        resource.remove_texture(texture).await;
+    }
+    for buffer in buffers {
+        resource.remove_buffer(buffer).await;
+    }
+}
@@ -0,0 +1,329 @@
+use super::{
+    buffer::{BufferError, BufferUsages, DynamicBuffer},
+    quad::QuadTarget,
+    render_pass::RenderPassError,
+    texture::TextureFormat,
+    ResourceContext,
+};
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use std::{collections::HashMap, mem, ops::Range};
+use wgpu::util::DeviceExt;
+
+/// Number of vertices the buffer of an [`Immediate`] holds before growing.
+const INITIAL_CAPACITY: u64 = 1024;
+
+/// Size of a vertex in the vertex buffer.
+const VERTEX_SIZE: u64 = mem::size_of::<ImmediateVertex>() as u64;
+
+/// How the vertices of a batch are assembled into primitives.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Primitive {
+    /// Every 3 vertices make a triangle.
+    Triangles,
+    /// Every 2 vertices make a line.
+    Lines,
+    /// Every vertex is a point.
+    Points,
+}
+
+impl From<Primitive> for wgpu::PrimitiveTopology {
+    fn from(primitive: Primitive) -> Self {
+        match primitive {
+            Primitive::Triangles => Self::TriangleList,
+            Primitive::Lines => Self::LineList,
+            Primitive::Points => Self::PointList,
+        }
+    }
+}
+
+/// Vertex of the immediate shader, mirrors the vertex input of `immediate.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ImmediateVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+/// Error of an immediate-mode drawing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ImmediateError {
+    /// The vertices could not be written to the vertex buffer.
+    Buffer(BufferError),
+    /// The draws could not be recorded.
+    RenderPass(RenderPassError),
+}
+
+/// Immediate-mode drawing of colored vertices, in the spirit of `glBegin` and `glEnd`.
+///
+/// The vertices given between [`Self::begin`] and [`Self::end`] make a batch, which
+/// [`Self::end`] uploads to a vertex buffer growing as needed. [`Self::draw`] then draws the
+/// batches ended since the previous draw in one render pass. Every batch of a frame keeps its
+/// own region of the buffer, as the uploads only land when the frame is submitted, and
+/// [`Self::finish_frame`] starts the next frame from an empty buffer once it is. A single
+/// shader is used, with one pipeline per primitive and target format.
+pub struct Immediate {
+    resource: ResourceContext,
+    vertex_buffer: DynamicBuffer,
+    /// Number of vertices the vertex buffer holds.
+    capacity: u64,
+    /// Number of vertices uploaded this frame.
+    len: u64,
+    /// Batch opened by [`Self::begin`] and the vertices given since.
+    open: Option<(Primitive, Vec<ImmediateVertex>)>,
+    /// Batches uploaded since the previous draw, with their range in the vertex buffer.
+    batches: Vec<(Primitive, Range<u32>)>,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    shader: wgpu::ShaderModule,
+    pipelines: HashMap<(Primitive, TextureFormat), wgpu::RenderPipeline>,
+}
+
+impl Immediate {
+    /// Creates the helper, its vertex buffer is created in `resource`.
+    pub async fn new(resource: &ResourceContext) -> Self {
+        let vertex_buffer = resource
+            .create_dynamic_buffer(
+                Some("immediate"),
+                INITIAL_CAPACITY * VERTEX_SIZE,
+                BufferUsages::VERTEX,
+            )
+            .await;
+        let device = &resource.ctx_data.device;
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("CGToy - BindGroupLayout(immediate)"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("CGToy - PipelineLayout(immediate)"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(immediate)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/immediate.wgsl").into()),
+        });
+        Self {
+            resource: resource.clone(),
+            vertex_buffer,
+            capacity: INITIAL_CAPACITY,
+            len: 0,
+            open: None,
+            batches: Vec::new(),
+            bind_group_layout,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Opens a batch of `primitive`, the vertices given until [`Self::end`] are assembled into
+    /// it.
+    ///
+    /// Panics if a batch is already open.
+    pub fn begin(&mut self, primitive: Primitive) {
+        assert!(self.open.is_none(), "Immediate::begin called twice!");
+        self.open = Some((primitive, Vec::new()));
+    }
+
+    /// Adds a vertex at `position` of RGBA `color` to the open batch.
+    ///
+    /// Panics if no batch is open.
+    pub fn vertex(&mut self, position: Vec3, color: [f32; 4]) {
+        let (_, vertices) = self
+            .open
+            .as_mut()
+            .expect("Immediate::vertex called outside of a batch!");
+        vertices.push(ImmediateVertex {
+            position: position.to_array(),
+            color,
+        });
+    }
+
+    /// Closes the open batch and uploads its vertices, growing the vertex buffer if they do
+    /// not fit.
+    ///
+    /// Panics if no batch is open.
+    pub async fn end(&mut self) -> Result<(), BufferError> {
+        let (primitive, vertices) = self
+            .open
+            .take()
+            .expect("Immediate::end called outside of a batch!");
+        if vertices.is_empty() {
+            return Ok(());
+        }
+        let start = self.len;
+        let end = start + vertices.len() as u64;
+        // Grows the buffer, keeping the vertices uploaded this frame.
+        if end > self.capacity {
+            let capacity = end.max(self.capacity * 2);
+            let mut vertex_buffer = self
+                .resource
+                .create_dynamic_buffer(
+                    Some("immediate"),
+                    capacity * VERTEX_SIZE,
+                    BufferUsages::VERTEX,
+                )
+                .await;
+            vertex_buffer.write(
+                0,
+                &self.vertex_buffer.data()[..(start * VERTEX_SIZE) as usize],
+            )?;
+            let old = mem::replace(&mut self.vertex_buffer, vertex_buffer);
+            self.resource.remove_buffer(old.id()).await;
+            self.capacity = capacity;
+        }
+        self.vertex_buffer
+            .write(start * VERTEX_SIZE, bytemuck::cast_slice(&vertices))?;
+        self.vertex_buffer.flush(&self.resource).await?;
+        self.len = end;
+        self.batches.push((primitive, start as u32..end as u32));
+        Ok(())
+    }
+
+    /// Draws the batches ended since the previous draw over `target`, with their positions
+    /// transformed by `transform` into clip space.
+    ///
+    /// A batch still open is ended first. The vertex colors are alpha blended over the previous
+    /// content of the target. Several draws can be recorded before the frame is submitted, the
+    /// vertex buffer is only reused after [`Self::finish_frame`].
+    pub async fn draw(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &QuadTarget,
+        transform: Mat4,
+    ) -> Result<(), ImmediateError> {
+        if self.open.is_some() {
+            self.end().await.map_err(ImmediateError::Buffer)?;
+        }
+        // The batches are drawn once whether the draws can be recorded or not.
+        let batches = mem::take(&mut self.batches);
+        if batches.is_empty() {
+            return Ok(());
+        }
+        // Gets the read locks of the vertex buffer and the target.
+        let buffers = self.resource.buffers.read().await;
+        let texture_views = self.resource.texture_views.read().await;
+        let (vertex_buffer, _, _) =
+            buffers
+                .get(&self.vertex_buffer.id())
+                .ok_or(ImmediateError::RenderPass(RenderPassError::BufferNotFound(
+                    self.vertex_buffer.id(),
+                )))?;
+        let (target_view, _) =
+            texture_views
+                .get(&target.view)
+                .ok_or(ImmediateError::RenderPass(
+                    RenderPassError::InvalidAttachment(target.view),
+                ))?;
+        // Every draw gets its own transform, so several draws can share one submission.
+        let device = &self.resource.ctx_data.device;
+        let transform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(immediate transform)"),
+            contents: bytemuck::bytes_of(&transform.to_cols_array_2d()),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("CGToy - BindGroup(immediate)"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: transform.as_entire_binding(),
+            }],
+        });
+        // Creates the pipelines of the primitives drawn onto the target format.
+        for &(primitive, _) in &batches {
+            let Self {
+                pipeline_layout,
+                shader,
+                pipelines,
+                ..
+            } = self;
+            pipelines
+                .entry((primitive, target.format))
+                .or_insert_with(|| {
+                    create_pipeline(device, pipeline_layout, shader, primitive, target.format)
+                });
+        }
+        // Draws the batches over the target.
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("CGToy - RenderPass(immediate)"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        for (primitive, vertices) in batches {
+            render_pass.set_pipeline(&self.pipelines[&(primitive, target.format)]);
+            render_pass.draw(vertices, 0..1);
+        }
+        Ok(())
+    }
+
+    /// Starts the next frame from an empty vertex buffer, to call once the commands of every
+    /// draw of the frame are submitted.
+    ///
+    /// Batches ended but not drawn yet are dropped. Panics if a batch is open.
+    pub fn finish_frame(&mut self) {
+        assert!(
+            self.open.is_none(),
+            "Immediate::finish_frame called inside of a batch!"
+        );
+        self.len = 0;
+        self.batches.clear();
+    }
+}
+
+/// Creates the pipeline drawing `primitive` onto `format`.
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    primitive: Primitive,
+    format: TextureFormat,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("CGToy - Pipeline(immediate)"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: VERTEX_SIZE,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+            }],
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: primitive.into(),
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "main",
+            targets: &[wgpu::ColorTargetState {
+                format: format.into(),
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            }],
+        }),
+    })
+}
@@ -0,0 +1,181 @@
+use super::texture::{Extent3d, TextureError, TextureFormat};
+use std::{convert::TryInto, io};
+
+/// Identifier every KTX2 file starts with.
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+/// Size of the identifier, the header and the index preceding the level index.
+const LEVEL_INDEX_OFFSET: usize = 80;
+/// Size of a level index entry: byte offset, byte length and uncompressed byte length.
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+/// Texture read from a KTX2 container, its mip levels are kept compressed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ktx2Texture {
+    pub format: TextureFormat,
+    pub size: Extent3d,
+    pub mip_level_count: u32,
+    /// Every mip level from the largest one, tightly packed.
+    pub data: Vec<u8>,
+}
+
+/// Error of a KTX2 texture loading.
+#[derive(Debug)]
+pub enum Ktx2Error {
+    Io(io::Error),
+    /// The file is not a KTX2 container or is truncated.
+    InvalidContainer,
+    /// The `vkFormat` of the container has no matching [`TextureFormat`].
+    UnsupportedFormat(u32),
+    /// The container uses a layout that can not be loaded yet, such as cube maps, arrays or
+    /// supercompression.
+    UnsupportedLayout(&'static str),
+    /// The texture could not be created, such as when the device lacks the features of the
+    /// format.
+    Texture(TextureError),
+}
+
+impl From<io::Error> for Ktx2Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<TextureError> for Ktx2Error {
+    fn from(error: TextureError) -> Self {
+        Self::Texture(error)
+    }
+}
+
+/// Parses a KTX2 container holding a single 2D texture, faces, layers and supercompression
+/// are rejected.
+pub fn parse(bytes: &[u8]) -> Result<Ktx2Texture, Ktx2Error> {
+    if bytes.len() < LEVEL_INDEX_OFFSET || bytes[..IDENTIFIER.len()] != IDENTIFIER {
+        return Err(Ktx2Error::InvalidContainer);
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let vk_format = u32_at(12);
+    let width = u32_at(20);
+    let height = u32_at(24);
+    let depth = u32_at(28);
+    let layer_count = u32_at(32);
+    let face_count = u32_at(36);
+    let level_count = u32_at(40).max(1);
+    let supercompression = u32_at(44);
+    if depth > 1 || height == 0 {
+        return Err(Ktx2Error::UnsupportedLayout(
+            "only 2D textures are supported",
+        ));
+    }
+    if layer_count > 1 || face_count != 1 {
+        return Err(Ktx2Error::UnsupportedLayout(
+            "arrays and cube maps are not supported",
+        ));
+    }
+    if supercompression != 0 {
+        return Err(Ktx2Error::UnsupportedLayout(
+            "supercompression is not supported",
+        ));
+    }
+    let format =
+        vk_format_to_texture_format(vk_format).ok_or(Ktx2Error::UnsupportedFormat(vk_format))?;
+    // Levels are listed from the largest one, the data is stored from the smallest one.
+    let mut data = Vec::new();
+    for level in 0..level_count as usize {
+        let entry = LEVEL_INDEX_OFFSET + level * LEVEL_INDEX_ENTRY_SIZE;
+        let entry = bytes
+            .get(entry..entry + 16)
+            .ok_or(Ktx2Error::InvalidContainer)?;
+        let offset = u64::from_le_bytes(entry[..8].try_into().unwrap()) as usize;
+        let length = u64::from_le_bytes(entry[8..].try_into().unwrap()) as usize;
+        let level_data = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or(Ktx2Error::InvalidContainer)?;
+        data.extend_from_slice(level_data);
+    }
+    Ok(Ktx2Texture {
+        format,
+        size: Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: level_count,
+        data,
+    })
+}
+
+/// Maps a `VkFormat` to a texture format, formats wgpu does not support are `None`.
+fn vk_format_to_texture_format(vk_format: u32) -> Option<TextureFormat> {
+    Some(match vk_format {
+        9 => TextureFormat::R8Unorm,
+        10 => TextureFormat::R8Snorm,
+        16 => TextureFormat::Rg8Unorm,
+        17 => TextureFormat::Rg8Snorm,
+        37 => TextureFormat::Rgba8Unorm,
+        38 => TextureFormat::Rgba8Snorm,
+        43 => TextureFormat::Rgba8UnormSrgb,
+        44 => TextureFormat::Bgra8Unorm,
+        50 => TextureFormat::Bgra8UnormSrgb,
+        76 => TextureFormat::R16Float,
+        83 => TextureFormat::Rg16Float,
+        97 => TextureFormat::Rgba16Float,
+        100 => TextureFormat::R32Float,
+        103 => TextureFormat::Rg32Float,
+        109 => TextureFormat::Rgba32Float,
+        // BC1 without alpha decodes with an opaque alpha.
+        131 | 133 => TextureFormat::Bc1RgbaUnorm,
+        132 | 134 => TextureFormat::Bc1RgbaUnormSrgb,
+        135 => TextureFormat::Bc2RgbaUnorm,
+        136 => TextureFormat::Bc2RgbaUnormSrgb,
+        137 => TextureFormat::Bc3RgbaUnorm,
+        138 => TextureFormat::Bc3RgbaUnormSrgb,
+        139 => TextureFormat::Bc4RUnorm,
+        140 => TextureFormat::Bc4RSnorm,
+        141 => TextureFormat::Bc5RgUnorm,
+        142 => TextureFormat::Bc5RgSnorm,
+        143 => TextureFormat::Bc6hRgbUfloat,
+        144 => TextureFormat::Bc6hRgbSfloat,
+        145 => TextureFormat::Bc7RgbaUnorm,
+        146 => TextureFormat::Bc7RgbaUnormSrgb,
+        147 => TextureFormat::Etc2RgbUnorm,
+        148 => TextureFormat::Etc2RgbUnormSrgb,
+        149 => TextureFormat::Etc2RgbA1Unorm,
+        150 => TextureFormat::Etc2RgbA1UnormSrgb,
+        153 => TextureFormat::EacRUnorm,
+        154 => TextureFormat::EacRSnorm,
+        155 => TextureFormat::EacRgUnorm,
+        156 => TextureFormat::EacRgSnorm,
+        157 => TextureFormat::Astc4x4RgbaUnorm,
+        158 => TextureFormat::Astc4x4RgbaUnormSrgb,
+        159 => TextureFormat::Astc5x4RgbaUnorm,
+        160 => TextureFormat::Astc5x4RgbaUnormSrgb,
+        161 => TextureFormat::Astc5x5RgbaUnorm,
+        162 => TextureFormat::Astc5x5RgbaUnormSrgb,
+        163 => TextureFormat::Astc6x5RgbaUnorm,
+        164 => TextureFormat::Astc6x5RgbaUnormSrgb,
+        165 => TextureFormat::Astc6x6RgbaUnorm,
+        166 => TextureFormat::Astc6x6RgbaUnormSrgb,
+        167 => TextureFormat::Astc8x5RgbaUnorm,
+        168 => TextureFormat::Astc8x5RgbaUnormSrgb,
+        169 => TextureFormat::Astc8x6RgbaUnorm,
+        170 => TextureFormat::Astc8x6RgbaUnormSrgb,
+        171 => TextureFormat::Astc8x8RgbaUnorm,
+        172 => TextureFormat::Astc8x8RgbaUnormSrgb,
+        173 => TextureFormat::Astc10x5RgbaUnorm,
+        174 => TextureFormat::Astc10x5RgbaUnormSrgb,
+        175 => TextureFormat::Astc10x6RgbaUnorm,
+        176 => TextureFormat::Astc10x6RgbaUnormSrgb,
+        177 => TextureFormat::Astc10x8RgbaUnorm,
+        178 => TextureFormat::Astc10x8RgbaUnormSrgb,
+        179 => TextureFormat::Astc10x10RgbaUnorm,
+        180 => TextureFormat::Astc10x10RgbaUnormSrgb,
+        181 => TextureFormat::Astc12x10RgbaUnorm,
+        182 => TextureFormat::Astc12x10RgbaUnormSrgb,
+        183 => TextureFormat::Astc12x12RgbaUnorm,
+        184 => TextureFormat::Astc12x12RgbaUnormSrgb,
+        _ => return None,
+    })
+}
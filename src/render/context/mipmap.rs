@@ -0,0 +1,301 @@
+use super::texture::{
+    TextureDescriptor, TextureDimension, TextureFormat, TextureFormatFeatures, TextureId,
+    TextureUsages,
+};
+use std::collections::HashMap;
+
+/// Side of the square workgroups of the compute downsampling, see `mipmap_compute.wgsl`.
+const WORKGROUP_SIZE: u32 = 8;
+
+/// How [`ResourceContext::generate_mipmaps`] writes the mip levels of a texture.
+///
+/// [`ResourceContext::generate_mipmaps`]: super::ResourceContext::generate_mipmaps
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum MipmapPath {
+    /// Each level is drawn by a fullscreen pass reading the previous one, the format must be
+    /// renderable.
+    Render,
+    /// Each level is bound as a storage texture and written by a compute pass reading the
+    /// previous one, for formats that can be stored to but not rendered to.
+    Compute,
+}
+
+impl MipmapPath {
+    /// Picks the path writing `format` from its features, rendering is preferred.
+    pub fn select(
+        format: TextureFormat,
+        features: TextureFormatFeatures,
+    ) -> Result<Self, MipmapError> {
+        let float = matches!(
+            wgpu::TextureFormat::from(format).describe().sample_type,
+            wgpu::TextureSampleType::Float { .. }
+        );
+        if float && features.renderable() {
+            Ok(Self::Render)
+        } else if float && features.storage() && storage_format_name(format).is_some() {
+            Ok(Self::Compute)
+        } else {
+            Err(MipmapError::UnsupportedFormat(format))
+        }
+    }
+
+    /// Gets the usages a texture needs to be mipmapped through this path.
+    pub fn required_usages(self) -> TextureUsages {
+        match self {
+            Self::Render => TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+            Self::Compute => TextureUsages::TEXTURE_BINDING | TextureUsages::STORAGE_BINDING,
+        }
+    }
+}
+
+/// Error of a mipmap generation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MipmapError {
+    /// The texture does not exist.
+    TextureNotFound(TextureId),
+    /// Only 2D textures and 2D texture arrays can be mipmapped.
+    UnsupportedDimension(TextureDimension),
+    /// The format is neither renderable nor storable as floats.
+    UnsupportedFormat(TextureFormat),
+    /// The texture lacks the usages of the path its format allows, along the texture binding.
+    MissingUsages(TextureUsages),
+}
+
+/// Built-in resources of [`ResourceContext::generate_mipmaps`], created on the first
+/// generation.
+///
+/// [`ResourceContext::generate_mipmaps`]: super::ResourceContext::generate_mipmaps
+pub(super) struct MipmapGenerator {
+    render_bind_group_layout: wgpu::BindGroupLayout,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    render_shader: wgpu::ShaderModule,
+    render_pipelines: HashMap<TextureFormat, wgpu::RenderPipeline>,
+    compute_bind_group_layouts: HashMap<TextureFormat, wgpu::BindGroupLayout>,
+    compute_pipelines: HashMap<TextureFormat, wgpu::ComputePipeline>,
+}
+
+impl MipmapGenerator {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let render_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("CGToy - BindGroupLayout(mipmap)"),
+                entries: &[source_entry(wgpu::ShaderStages::FRAGMENT)],
+            });
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("CGToy - PipelineLayout(mipmap)"),
+                bind_group_layouts: &[&render_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let render_shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(mipmap)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/mipmap.wgsl").into()),
+        });
+        Self {
+            render_bind_group_layout,
+            render_pipeline_layout,
+            render_shader,
+            render_pipelines: HashMap::new(),
+            compute_bind_group_layouts: HashMap::new(),
+            compute_pipelines: HashMap::new(),
+        }
+    }
+
+    /// Writes every mip level of every layer of `texture` from its first level.
+    ///
+    /// The passes are recorded into `encoder`, `path` must have been selected for the format.
+    pub fn generate(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        desc: &TextureDescriptor,
+        path: MipmapPath,
+    ) {
+        let format = desc.format;
+        self.prepare(device, format, path);
+        for layer in 0..desc.size.depth_or_array_layers {
+            for level in 1..desc.mip_level_count {
+                let source = level_view(texture, layer, level - 1);
+                let target = level_view(texture, layer, level);
+                match path {
+                    MipmapPath::Render => {
+                        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            label: Some("CGToy - BindGroup(mipmap)"),
+                            layout: &self.render_bind_group_layout,
+                            entries: &[wgpu::BindGroupEntry {
+                                binding: 0,
+                                resource: wgpu::BindingResource::TextureView(&source),
+                            }],
+                        });
+                        let mut render_pass =
+                            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                                label: Some("CGToy - RenderPass(mipmap)"),
+                                color_attachments: &[wgpu::RenderPassColorAttachment {
+                                    view: &target,
+                                    resolve_target: None,
+                                    ops: wgpu::Operations {
+                                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                                        store: true,
+                                    },
+                                }],
+                                depth_stencil_attachment: None,
+                            });
+                        render_pass.set_pipeline(&self.render_pipelines[&format]);
+                        render_pass.set_bind_group(0, &bind_group, &[]);
+                        render_pass.draw(0..3, 0..1);
+                    }
+                    MipmapPath::Compute => {
+                        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            label: Some("CGToy - BindGroup(mipmap compute)"),
+                            layout: &self.compute_bind_group_layouts[&format],
+                            entries: &[
+                                wgpu::BindGroupEntry {
+                                    binding: 0,
+                                    resource: wgpu::BindingResource::TextureView(&source),
+                                },
+                                wgpu::BindGroupEntry {
+                                    binding: 1,
+                                    resource: wgpu::BindingResource::TextureView(&target),
+                                },
+                            ],
+                        });
+                        let mut compute_pass =
+                            encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                                label: Some("CGToy - ComputePass(mipmap)"),
+                            });
+                        compute_pass.set_pipeline(&self.compute_pipelines[&format]);
+                        compute_pass.set_bind_group(0, &bind_group, &[]);
+                        let size = desc.size.mip_level_size(level);
+                        compute_pass.dispatch(
+                            size.width.div_ceil(WORKGROUP_SIZE),
+                            size.height.div_ceil(WORKGROUP_SIZE),
+                            1,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Creates the pipeline writing `format` through `path` unless it is already cached.
+    fn prepare(&mut self, device: &wgpu::Device, format: TextureFormat, path: MipmapPath) {
+        match path {
+            MipmapPath::Render => {
+                let Self {
+                    render_pipeline_layout,
+                    render_shader,
+                    render_pipelines,
+                    ..
+                } = self;
+                render_pipelines.entry(format).or_insert_with(|| {
+                    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                        label: Some("CGToy - Pipeline(mipmap)"),
+                        layout: Some(render_pipeline_layout),
+                        vertex: wgpu::VertexState {
+                            module: render_shader,
+                            entry_point: "main",
+                            buffers: &[],
+                        },
+                        primitive: Default::default(),
+                        depth_stencil: None,
+                        multisample: wgpu::MultisampleState::default(),
+                        fragment: Some(wgpu::FragmentState {
+                            module: render_shader,
+                            entry_point: "main",
+                            targets: &[wgpu::TextureFormat::from(format).into()],
+                        }),
+                    })
+                });
+            }
+            MipmapPath::Compute => {
+                if self.compute_pipelines.contains_key(&format) {
+                    return;
+                }
+                // Storage textures are declared with their format, so every format gets its
+                // own shader and layout.
+                let bind_group_layout =
+                    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                        label: Some("CGToy - BindGroupLayout(mipmap compute)"),
+                        entries: &[
+                            source_entry(wgpu::ShaderStages::COMPUTE),
+                            wgpu::BindGroupLayoutEntry {
+                                binding: 1,
+                                visibility: wgpu::ShaderStages::COMPUTE,
+                                ty: wgpu::BindingType::StorageTexture {
+                                    access: wgpu::StorageTextureAccess::WriteOnly,
+                                    format: format.into(),
+                                    view_dimension: wgpu::TextureViewDimension::D2,
+                                },
+                                count: None,
+                            },
+                        ],
+                    });
+                let pipeline_layout =
+                    device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("CGToy - PipelineLayout(mipmap compute)"),
+                        bind_group_layouts: &[&bind_group_layout],
+                        push_constant_ranges: &[],
+                    });
+                let source = include_str!("../../shaders/mipmap_compute.wgsl").replace(
+                    "{format}",
+                    storage_format_name(format).expect("format is selected for storage"),
+                );
+                let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                    label: Some("CGToy - Shader(mipmap compute)"),
+                    source: wgpu::ShaderSource::Wgsl(source.into()),
+                });
+                let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some("CGToy - Pipeline(mipmap compute)"),
+                    layout: Some(&pipeline_layout),
+                    module: &shader,
+                    entry_point: "main",
+                });
+                self.compute_bind_group_layouts
+                    .insert(format, bind_group_layout);
+                self.compute_pipelines.insert(format, pipeline);
+            }
+        }
+    }
+}
+
+/// Binding of the previous level, it is loaded texel by texel so any float format works.
+fn source_entry(visibility: wgpu::ShaderStages) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+/// Gets a view of a single mip level of a single layer.
+fn level_view(texture: &wgpu::Texture, layer: u32, level: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("CGToy - TextureView(mipmap)"),
+        dimension: Some(wgpu::TextureViewDimension::D2),
+        base_mip_level: level,
+        mip_level_count: std::num::NonZeroU32::new(1),
+        base_array_layer: layer,
+        array_layer_count: std::num::NonZeroU32::new(1),
+        ..Default::default()
+    })
+}
+
+/// Gets the WGSL name of a float storage format, `None` for formats that can not be stored to
+/// as floats.
+fn storage_format_name(format: TextureFormat) -> Option<&'static str> {
+    Some(match format {
+        TextureFormat::Rgba8Unorm => "rgba8unorm",
+        TextureFormat::Rgba8Snorm => "rgba8snorm",
+        TextureFormat::Rgba16Float => "rgba16float",
+        TextureFormat::R32Float => "r32float",
+        TextureFormat::Rg32Float => "rg32float",
+        TextureFormat::Rgba32Float => "rgba32float",
+        _ => return None,
+    })
+}
@@ -0,0 +1,94 @@
+use super::shader::ShaderError;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Directive pasting another WGSL file in place, as `//!include "path"` on its own line.
+///
+/// Being a comment, a shader with includes still parses as is.
+pub const INCLUDE_DIRECTIVE: &str = "//!include";
+
+/// Reads the WGSL file at `path` and resolves its include directives, see [`preprocess`].
+pub fn preprocess_file(path: &Path) -> Result<String, ShaderError> {
+    let mut preprocessor = Preprocessor::default();
+    preprocessor.include(path, None)?;
+    Ok(preprocessor.output)
+}
+
+/// Resolves the include directives of WGSL `source`, paths are relative to `dir`.
+///
+/// An included file resolves its own includes relative to its directory. Every file is pasted
+/// once, later includes of it are dropped so files can share a common dependency. A file
+/// including itself, directly or not, is an error. Line numbers of parse errors are the ones of
+/// the assembled source.
+pub fn preprocess(source: &str, dir: &Path) -> Result<String, ShaderError> {
+    let mut preprocessor = Preprocessor::default();
+    preprocessor.expand(source, None, dir)?;
+    Ok(preprocessor.output)
+}
+
+#[derive(Default)]
+struct Preprocessor {
+    /// Files being expanded, the innermost last.
+    stack: Vec<PathBuf>,
+    /// Files already pasted.
+    included: HashSet<PathBuf>,
+    output: String,
+}
+
+impl Preprocessor {
+    /// Pastes the file at `path`, `from` is the file and line of the directive.
+    fn include(&mut self, path: &Path, from: Option<(&Path, usize)>) -> Result<(), ShaderError> {
+        let io_error = |error: std::io::Error| ShaderError::Include {
+            path: path.to_owned(),
+            from: from.map(|(file, line)| (file.to_owned(), line)),
+            message: error.to_string(),
+        };
+        // Tells files apart by their canonical path, however they are reached.
+        let canonical = fs::canonicalize(path).map_err(io_error)?;
+        if let Some(start) = self.stack.iter().position(|file| *file == canonical) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(canonical);
+            return Err(ShaderError::IncludeCycle(cycle));
+        }
+        if !self.included.insert(canonical.clone()) {
+            return Ok(());
+        }
+        let source = fs::read_to_string(&canonical).map_err(io_error)?;
+        let dir = canonical
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .to_owned();
+        self.stack.push(canonical.clone());
+        self.expand(&source, Some(&canonical), &dir)?;
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Pastes `source` of `file`, resolving its includes relative to `dir`.
+    fn expand(&mut self, source: &str, file: Option<&Path>, dir: &Path) -> Result<(), ShaderError> {
+        for (index, line) in source.lines().enumerate() {
+            let directive = match line.trim().strip_prefix(INCLUDE_DIRECTIVE) {
+                Some(directive) => directive.trim(),
+                None => {
+                    self.output.push_str(line);
+                    self.output.push('\n');
+                    continue;
+                }
+            };
+            let include = directive
+                .strip_prefix('"')
+                .and_then(|directive| directive.strip_suffix('"'))
+                .filter(|include| !include.is_empty() && !include.contains('"'))
+                .ok_or_else(|| ShaderError::InvalidInclude {
+                    file: file.map(Path::to_owned),
+                    line: index + 1,
+                })?;
+            let from = file.map(|file| (file, index + 1));
+            self.include(&dir.join(include), from)?;
+        }
+        Ok(())
+    }
+}
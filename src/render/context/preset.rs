@@ -0,0 +1,125 @@
+use super::{
+    buffer::BufferDescriptor,
+    sampler::{AddressMode, FilterMode, SamplerDescriptor},
+    types::CompareFunction,
+};
+use std::collections::HashMap;
+
+/// Descriptor stored under a name in [`DescriptorPresets`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Preset {
+    Sampler(SamplerDescriptor),
+    Buffer(BufferDescriptor),
+}
+
+impl From<SamplerDescriptor> for Preset {
+    fn from(desc: SamplerDescriptor) -> Self {
+        Self::Sampler(desc)
+    }
+}
+
+impl From<BufferDescriptor> for Preset {
+    fn from(desc: BufferDescriptor) -> Self {
+        Self::Buffer(desc)
+    }
+}
+
+/// Registry of named descriptors, resources are created from them by
+/// [`ResourceContext::create_sampler_preset`] and [`ResourceContext::create_buffer_preset`].
+///
+/// [`DescriptorPresets::default`] holds the built-in presets:
+/// - `"pixel-art"`: nearest sampler clamped to the edge, for upscaling without blurring texels.
+/// - `"smooth"`: trilinear sampler repeating the texture.
+/// - `"shadow"`: comparison sampler passing if the reference is less than or equal to the depth.
+///
+/// [`ResourceContext::create_sampler_preset`]: super::ResourceContext::create_sampler_preset
+/// [`ResourceContext::create_buffer_preset`]: super::ResourceContext::create_buffer_preset
+#[derive(Clone, Debug, PartialEq)]
+pub struct DescriptorPresets {
+    presets: HashMap<String, Preset>,
+}
+
+impl Default for DescriptorPresets {
+    fn default() -> Self {
+        let mut presets = Self::empty();
+        presets.register(
+            "pixel-art",
+            SamplerDescriptor {
+                label: Some("pixel-art"),
+                mag_filter: FilterMode::Nearest,
+                min_filter: FilterMode::Nearest,
+                mipmap_filter: FilterMode::Nearest,
+                ..Default::default()
+            },
+        );
+        presets.register(
+            "smooth",
+            SamplerDescriptor {
+                label: Some("smooth"),
+                address_mode_u: AddressMode::Repeat,
+                address_mode_v: AddressMode::Repeat,
+                address_mode_w: AddressMode::Repeat,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                mipmap_filter: FilterMode::Linear,
+                ..Default::default()
+            },
+        );
+        presets.register(
+            "shadow",
+            SamplerDescriptor {
+                label: Some("shadow"),
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                compare: Some(CompareFunction::LessEqual),
+                ..Default::default()
+            },
+        );
+        presets
+    }
+}
+
+impl DescriptorPresets {
+    /// Creates a registry without the built-in presets.
+    pub fn empty() -> Self {
+        Self {
+            presets: HashMap::new(),
+        }
+    }
+
+    /// Registers `preset` under `name`, returning the preset it replaces if any.
+    pub fn register(&mut self, name: &str, preset: impl Into<Preset>) -> Option<Preset> {
+        self.presets.insert(name.to_owned(), preset.into())
+    }
+
+    /// Removes the preset registered under `name`.
+    pub fn unregister(&mut self, name: &str) -> Option<Preset> {
+        self.presets.remove(name)
+    }
+
+    /// Gets the preset registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.get(name)
+    }
+
+    /// Gets the sampler preset registered under `name`, `None` if it is a buffer preset.
+    pub fn sampler(&self, name: &str) -> Option<&SamplerDescriptor> {
+        match self.get(name) {
+            Some(Preset::Sampler(desc)) => Some(desc),
+            _ => None,
+        }
+    }
+
+    /// Gets the buffer preset registered under `name`, `None` if it is a sampler preset.
+    pub fn buffer(&self, name: &str) -> Option<&BufferDescriptor> {
+        match self.get(name) {
+            Some(Preset::Buffer(desc)) => Some(desc),
+            _ => None,
+        }
+    }
+
+    /// Gets the names of every registered preset.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.keys().map(String::as_str)
+    }
+}
@@ -0,0 +1,162 @@
+use super::texture::{TextureFormat, TextureViewId};
+use glam::{Mat4, Vec3};
+use std::collections::HashMap;
+use wgpu::util::DeviceExt;
+
+/// Corners of the unit quad drawn as a triangle strip, they double as texture coordinates.
+const QUAD_CORNERS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]];
+
+/// Axis aligned rectangle in logical pixels, `x` and `y` locate its top left corner.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// Gets the transform mapping the unit quad onto the rectangle in a target of `width` by
+    /// `height` physical pixels, through an orthographic projection with the origin at the top
+    /// left.
+    ///
+    /// The rectangle is scaled by `scale_factor` physical pixels per logical pixel.
+    pub fn transform(&self, width: u32, height: u32, scale_factor: f64) -> Mat4 {
+        let scale_factor = scale_factor as f32;
+        Mat4::orthographic_rh(0.0, width as f32, height as f32, 0.0, -1.0, 1.0)
+            * Mat4::from_scale(Vec3::new(scale_factor, scale_factor, 1.0))
+            * Mat4::from_translation(Vec3::new(self.x, self.y, 0.0))
+            * Mat4::from_scale(Vec3::new(self.width, self.height, 1.0))
+    }
+}
+
+/// Color attachment a texture is drawn onto, its previous content is kept.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct QuadTarget {
+    /// The view to draw onto.
+    pub view: TextureViewId,
+    /// The format of the view, a pipeline is cached for every format drawn onto.
+    pub format: TextureFormat,
+    /// The width of the view in physical pixels.
+    pub width: u32,
+    /// The height of the view in physical pixels.
+    pub height: u32,
+    /// Physical pixels per logical pixel of the view, see `PipelineState::scale_factor`.
+    pub scale_factor: f64,
+}
+
+/// Built-in resources of [`ResourceContext::draw_texture`], created on the first draw.
+///
+/// [`ResourceContext::draw_texture`]: super::ResourceContext::draw_texture
+pub(super) struct TexturedQuad {
+    pub vertex_buffer: wgpu::Buffer,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    shader: wgpu::ShaderModule,
+    pipelines: HashMap<TextureFormat, wgpu::RenderPipeline>,
+}
+
+impl TexturedQuad {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CGToy - Buffer(quad)"),
+            contents: bytemuck::cast_slice(&QUAD_CORNERS),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("CGToy - BindGroupLayout(quad)"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        filtering: true,
+                        comparison: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("CGToy - PipelineLayout(quad)"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("CGToy - Shader(quad)"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/quad.wgsl").into()),
+        });
+        Self {
+            vertex_buffer,
+            bind_group_layout,
+            pipeline_layout,
+            shader,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Creates the pipeline drawing onto `format` unless it is already cached.
+    pub fn prepare(&mut self, device: &wgpu::Device, format: TextureFormat) {
+        let Self {
+            pipeline_layout,
+            shader,
+            pipelines,
+            ..
+        } = self;
+        pipelines.entry(format).or_insert_with(|| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("CGToy - Pipeline(quad)"),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: "main",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: "main",
+                    targets: &[wgpu::ColorTargetState {
+                        format: format.into(),
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }],
+                }),
+            })
+        });
+    }
+
+    /// Gets the pipeline drawing onto `format`, it must have been prepared.
+    pub fn pipeline(&self, format: TextureFormat) -> &wgpu::RenderPipeline {
+        &self.pipelines[&format]
+    }
+}
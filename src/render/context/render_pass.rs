@@ -0,0 +1,251 @@
+use super::{
+    bind_group::BindGroupId,
+    buffer::BufferId,
+    sampler::SamplerId,
+    texture::{TextureFormat, TextureViewId},
+    types::{Color, CompareFunction},
+    vertex::IndexFormat,
+};
+use glam::{Mat4, Vec3};
+use std::ops::Range;
+
+/// Operation to perform to the output attachment at the start of a renderpass.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum LoadOp<V> {
+    /// Clear with a specified value.
+    Clear(V),
+    /// Load from memory.
+    Load,
+}
+
+impl<V: Into<W>, W> From<LoadOp<V>> for wgpu::LoadOp<W> {
+    fn from(load: LoadOp<V>) -> Self {
+        match load {
+            LoadOp::Clear(value) => Self::Clear(value.into()),
+            LoadOp::Load => Self::Load,
+        }
+    }
+}
+
+/// Pair of load and store operations for an attachment aspect.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Operations<V> {
+    /// How data should be read through this attachment.
+    pub load: LoadOp<V>,
+    /// Whether data will be written to through this attachment.
+    pub store: bool,
+}
+
+impl<V: Into<W>, W> From<Operations<V>> for wgpu::Operations<W> {
+    fn from(ops: Operations<V>) -> Self {
+        Self {
+            load: ops.load.into(),
+            store: ops.store,
+        }
+    }
+}
+
+/// Describes a color attachment to a render pass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderPassColorAttachment {
+    /// The view to use as an attachment.
+    ///
+    /// It must cover a single mip level and array layer, see [`TextureViewDescriptor::attachment`].
+    ///
+    /// [`TextureViewDescriptor::attachment`]: super::texture::TextureViewDescriptor::attachment
+    pub view: TextureViewId,
+    /// The view that will receive the resolved output if multisampling is used.
+    pub resolve_target: Option<TextureViewId>,
+    /// What operations will be performed on this color attachment.
+    pub ops: Operations<Color>,
+}
+
+/// Describes a depth/stencil attachment to a render pass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderPassDepthStencilAttachment {
+    /// The view to use as an attachment.
+    pub view: TextureViewId,
+    /// What operations will be performed on the depth part of the attachment.
+    ///
+    /// `None` makes the depth read-only: depth tests still run but nothing is written, which
+    /// lets the pass sample the same texture through a [`TextureViewDescriptor::depth_only`] view.
+    ///
+    /// [`TextureViewDescriptor::depth_only`]: super::texture::TextureViewDescriptor::depth_only
+    pub depth_ops: Option<Operations<f32>>,
+    /// What operations will be performed on the stencil part of the attachment.
+    ///
+    /// `None` makes the stencil read-only.
+    pub stencil_ops: Option<Operations<u32>>,
+}
+
+impl RenderPassDepthStencilAttachment {
+    /// Describes an attachment whose depth is cleared to `depth_clear` and stored.
+    ///
+    /// Standard depth clears to 1.0, reverse-Z clears to 0.0 and compares with `Greater`.
+    pub fn clear_depth(view: TextureViewId, depth_clear: f32) -> Self {
+        Self {
+            view,
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(depth_clear),
+                store: true,
+            }),
+            stencil_ops: None,
+        }
+    }
+
+    /// Describes an attachment whose depth and stencil are both read-only.
+    pub fn read_only(view: TextureViewId) -> Self {
+        Self {
+            view,
+            depth_ops: None,
+            stencil_ops: None,
+        }
+    }
+
+    /// Whether the depth of the attachment is never written by the pass.
+    pub fn is_depth_read_only(&self) -> bool {
+        self.depth_ops.is_none()
+    }
+}
+
+/// Describes the attachments of a render pass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderPassDescriptor<'a> {
+    /// Debug label of the render pass. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
+    /// The color attachments of the render pass.
+    pub color_attachments: &'a [RenderPassColorAttachment],
+    /// The depth and stencil attachment of the render pass, if any.
+    pub depth_stencil_attachment: Option<RenderPassDepthStencilAttachment>,
+}
+
+/// Pipelines of a depth prepass, recorded by [`ResourceContext::record_depth_prepass`].
+///
+/// The opaque geometry is first drawn depth-only, then drawn again with its color where the
+/// depth is equal, so heavy fragment shaders only run once per pixel. Both pipelines must draw
+/// the same vertices, create their depth states with [`DepthPrepass::depth_state`] and
+/// [`DepthPrepass::color_depth_state`].
+///
+/// [`ResourceContext::record_depth_prepass`]: super::ResourceContext::record_depth_prepass
+#[derive(Copy, Clone, Debug)]
+pub struct DepthPrepass<'a> {
+    /// Pipeline writing the depth only, it has no fragment stage nor color target.
+    pub depth_pipeline: &'a wgpu::RenderPipeline,
+    /// Pipeline shading the color, it tests the depth for equality without writing it.
+    pub color_pipeline: &'a wgpu::RenderPipeline,
+}
+
+impl DepthPrepass<'_> {
+    /// Gets the depth state of the depth pipeline, writing the depth of the nearest surface.
+    ///
+    /// Standard depth compares with `Less`, reverse-Z with `Greater`.
+    pub fn depth_state(format: TextureFormat, compare: CompareFunction) -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: format.into(),
+            depth_write_enabled: true,
+            depth_compare: compare.into(),
+            stencil: Default::default(),
+            bias: Default::default(),
+        }
+    }
+
+    /// Gets the depth state of the color pipeline, only passing fragments at the depth of the
+    /// prepass and never writing it, so the attachment can be read-only.
+    pub fn color_depth_state(format: TextureFormat) -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: format.into(),
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Equal,
+            stencil: Default::default(),
+            bias: Default::default(),
+        }
+    }
+}
+
+/// Command recorded into a render pass, resources are referred to by their ids.
+#[derive(Clone, Debug)]
+pub enum RenderCommand<'a> {
+    /// Sets the active render pipeline.
+    SetPipeline(&'a wgpu::RenderPipeline),
+    /// Sets the active bind group for a given bind group index.
+    SetBindGroup { index: u32, bind_group: BindGroupId },
+    /// Assigns a whole buffer to a vertex buffer slot.
+    ///
+    /// Every slot keeps its buffer until it is set again, the slot matches the index of the
+    /// buffer layout in the pipeline which decides whether it steps per vertex or per instance.
+    SetVertexBuffer { slot: u32, buffer: BufferId },
+    /// Assigns a whole buffer as the active index buffer.
+    SetIndexBuffer {
+        buffer: BufferId,
+        format: IndexFormat,
+    },
+    /// Draws primitives from the active vertex buffers.
+    Draw {
+        vertices: Range<u32>,
+        instances: Range<u32>,
+    },
+    /// Draws indexed primitives from the active index and vertex buffers.
+    ///
+    /// `base_vertex` is added to every index, per-instance buffers are read over `instances`.
+    DrawIndexed {
+        indices: Range<u32>,
+        base_vertex: i32,
+        instances: Range<u32>,
+    },
+    /// Opens a group of the following commands, shown nested under `label` by graphics
+    /// debuggers such as RenderDoc and PIX.
+    PushDebugGroup(&'a str),
+    /// Closes the last opened debug group.
+    PopDebugGroup,
+    /// Marks the point between two commands with `label` in graphics debuggers.
+    InsertDebugMarker(&'a str),
+}
+
+/// Commands drawing one object, placed by the center of its bounds for sorting.
+#[derive(Clone, Debug)]
+pub struct Draw<'a> {
+    /// Transform from the object space to the world space.
+    pub transform: Mat4,
+    /// Center of the bounds of the object in object space.
+    pub center: Vec3,
+    /// Commands binding the resources of the object and drawing it.
+    pub commands: Vec<RenderCommand<'a>>,
+}
+
+impl Draw<'_> {
+    /// Gets the center of the bounds of the object in world space.
+    pub fn world_center(&self) -> Vec3 {
+        self.transform.transform_point3(self.center)
+    }
+}
+
+/// Sorts transparent draws back-to-front from `camera_pos`, by the distance to the world space
+/// center of their bounds, so blending composes the farthest objects first.
+///
+/// Draws at the same distance keep their order, and draws with a NaN center still get a
+/// consistent place. Objects whose bounds overlap can still blend in the wrong order, only
+/// their centers are compared.
+pub fn sort_transparent(draws: &mut Vec<Draw<'_>>, camera_pos: Vec3) {
+    draws.sort_by(|a, b| {
+        let a = a.world_center().distance_squared(camera_pos);
+        let b = b.world_center().distance_squared(camera_pos);
+        b.total_cmp(&a)
+    });
+}
+
+/// Error of a render pass recording.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RenderPassError {
+    /// An attachment refers to a texture view that does not exist.
+    InvalidAttachment(TextureViewId),
+    /// A command refers to a bind group that does not exist.
+    BindGroupNotFound(BindGroupId),
+    /// A command refers to a buffer that does not exist.
+    BufferNotFound(BufferId),
+    /// A sampled texture view does not exist.
+    TextureViewNotFound(TextureViewId),
+    /// A sampler does not exist.
+    SamplerNotFound(SamplerId),
+    /// A debug group is popped without being pushed, or left open at the end of the pass.
+    UnbalancedDebugGroups,
+}
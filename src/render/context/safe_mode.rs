@@ -0,0 +1,62 @@
+/// Features a group of drivers is known to crash or misbehave with.
+///
+/// An entry matches an adapter if every criterion it sets matches. wgpu 0.10 does
+/// not report driver versions, so drivers are told apart by vendor, backend, device type and
+/// name only.
+#[derive(Copy, Clone, Debug)]
+pub struct BlocklistEntry {
+    /// PCI vendor id of the adapter.
+    pub vendor: Option<usize>,
+    /// Backend the driver is used through.
+    pub backend: Option<wgpu::Backend>,
+    /// Type of the device, to single out software rasterizers.
+    pub device_type: Option<wgpu::DeviceType>,
+    /// Case insensitive substring of the adapter name.
+    pub name: Option<&'static str>,
+    /// Features disabled on matching adapters.
+    pub features: wgpu::Features,
+    /// Why the features are disabled with a link to the tracked bug, logged when the entry
+    /// matches.
+    pub reason: &'static str,
+}
+
+impl BlocklistEntry {
+    /// Checks if an adapter is covered by the entry.
+    pub fn matches(&self, info: &wgpu::AdapterInfo) -> bool {
+        self.vendor.is_none_or(|vendor| vendor == info.vendor)
+            && self.backend.is_none_or(|backend| backend == info.backend)
+            && self
+                .device_type
+                .is_none_or(|device_type| device_type == info.device_type)
+            && self
+                .name
+                .is_none_or(|name| info.name.to_lowercase().contains(&name.to_lowercase()))
+    }
+}
+
+/// Drivers the safe mode disables features of, see [`RenderContextDescriptor::safe_mode`].
+///
+/// Every entry cites the tracked wgpu or driver bug it works around in its `reason`, the list
+/// starts empty until one is reported against a driver cgtoy runs on.
+///
+/// [`RenderContextDescriptor::safe_mode`]: super::RenderContextDescriptor::safe_mode
+pub const DRIVER_BLOCKLIST: &[BlocklistEntry] = &[];
+
+/// Removes the features blocklisted for an adapter from `features`, logging every match.
+pub fn filter_features(info: &wgpu::AdapterInfo, features: wgpu::Features) -> wgpu::Features {
+    DRIVER_BLOCKLIST
+        .iter()
+        .filter(|entry| entry.matches(info))
+        .fold(features, |features, entry| {
+            let blocked = features & entry.features;
+            if !blocked.is_empty() {
+                log::warn!(
+                    "Safe mode disables {:?} on {}: {}.",
+                    blocked,
+                    info.name,
+                    entry.reason
+                );
+            }
+            features - blocked
+        })
+}
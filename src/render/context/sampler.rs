@@ -1,21 +1,21 @@
 use super::types::CompareFunction;
-use std::num::NonZeroU8;
-use uuid::Uuid;
+use std::{
+    hash::{Hash, Hasher},
+    num::NonZeroU8,
+};
 
-///
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-pub struct SamplerId(Uuid);
-
-impl SamplerId {
-    ///
-    pub fn new() -> Self {
-        Self(Uuid::new_v4())
-    }
-}
+resource_id!(SamplerId);
 
 /// Describes a Sampler.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Descriptors are compared and hashed by the bits of their floats, so identical descriptors
+/// can share a sampler, see [`ResourceContext::create_sampler`].
+///
+/// [`ResourceContext::create_sampler`]: super::ResourceContext::create_sampler
+#[derive(Clone, Debug)]
 pub struct SamplerDescriptor {
+    /// Debug label of the sampler. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
     /// How to deal with out of bounds accesses in the u (i.e. x) direction.
     pub address_mode_u: AddressMode,
     /// How to deal with out of bounds accesses in the v (i.e. y) direction.
@@ -32,6 +32,17 @@ pub struct SamplerDescriptor {
     pub lod_min_clamp: f32,
     /// Maximum level of detail (i.e. mip level) to use.
     pub lod_max_clamp: f32,
+    /// Offset added to the level of detail, negative values sharpen and positive values soften.
+    ///
+    /// wgpu samplers have no bias, so it is not applied by the sampler itself. Shaders read it
+    /// back through [`ResourceContext::sampler_lod_bias`] and pass it to `textureSampleBias`:
+    ///
+    /// ```wgsl
+    /// textureSampleBias(t, s, uv, uniforms.lod_bias)
+    /// ```
+    ///
+    /// [`ResourceContext::sampler_lod_bias`]: super::ResourceContext::sampler_lod_bias
+    pub lod_bias: f32,
     /// If this is enabled, this is a comparison sampler using the given comparison function.
     pub compare: Option<CompareFunction>,
     /// Valid values: 1, 2, 4, 8, and 16.
@@ -43,6 +54,7 @@ pub struct SamplerDescriptor {
 impl Default for SamplerDescriptor {
     fn default() -> Self {
         Self {
+            label: None,
             address_mode_u: Default::default(),
             address_mode_v: Default::default(),
             address_mode_w: Default::default(),
@@ -51,6 +63,7 @@ impl Default for SamplerDescriptor {
             mipmap_filter: Default::default(),
             lod_min_clamp: 0.0,
             lod_max_clamp: f32::MAX,
+            lod_bias: 0.0,
             compare: None,
             anisotropy_clamp: None,
             border_color: None,
@@ -58,10 +71,56 @@ impl Default for SamplerDescriptor {
     }
 }
 
+impl SamplerDescriptor {
+    /// Gets the floats of the descriptor as bits, `0.0` and `-0.0` differ while NaNs of the same
+    /// bits are equal.
+    fn lod_bits(&self) -> [u32; 3] {
+        [
+            self.lod_min_clamp.to_bits(),
+            self.lod_max_clamp.to_bits(),
+            self.lod_bias.to_bits(),
+        ]
+    }
+}
+
+impl PartialEq for SamplerDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+            && self.address_mode_u == other.address_mode_u
+            && self.address_mode_v == other.address_mode_v
+            && self.address_mode_w == other.address_mode_w
+            && self.mag_filter == other.mag_filter
+            && self.min_filter == other.min_filter
+            && self.mipmap_filter == other.mipmap_filter
+            && self.lod_bits() == other.lod_bits()
+            && self.compare == other.compare
+            && self.anisotropy_clamp == other.anisotropy_clamp
+            && self.border_color == other.border_color
+    }
+}
+
+impl Eq for SamplerDescriptor {}
+
+impl Hash for SamplerDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.address_mode_u.hash(state);
+        self.address_mode_v.hash(state);
+        self.address_mode_w.hash(state);
+        self.mag_filter.hash(state);
+        self.min_filter.hash(state);
+        self.mipmap_filter.hash(state);
+        self.lod_bits().hash(state);
+        self.compare.hash(state);
+        self.anisotropy_clamp.hash(state);
+        self.border_color.hash(state);
+    }
+}
+
 impl From<&SamplerDescriptor> for wgpu::SamplerDescriptor<'_> {
     fn from(desc: &SamplerDescriptor) -> Self {
         Self {
-            label: None,
+            label: desc.label,
             address_mode_u: desc.address_mode_u.into(),
             address_mode_v: desc.address_mode_v.into(),
             address_mode_w: desc.address_mode_w.into(),
@@ -78,11 +137,12 @@ impl From<&SamplerDescriptor> for wgpu::SamplerDescriptor<'_> {
 }
 
 /// How edges should be handled in texture addressing.
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
 pub enum AddressMode {
     /// Clamp the value to the edge of the texture.
     ///
     /// -0.25 -> 0.0, 1.25 -> 1.0
+    #[default]
     ClampToEdge,
     /// Repeat the texture in a tiling fashion
     ///
@@ -98,12 +158,6 @@ pub enum AddressMode {
     ClampToBorder,
 }
 
-impl Default for AddressMode {
-    fn default() -> Self {
-        Self::ClampToEdge
-    }
-}
-
 impl From<AddressMode> for wgpu::AddressMode {
     fn from(mode: AddressMode) -> Self {
         match mode {
@@ -116,11 +170,12 @@ impl From<AddressMode> for wgpu::AddressMode {
 }
 
 /// Texel mixing mode when sampling between texels.
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
 pub enum FilterMode {
     /// Nearest neighbor sampling.
     ///
     /// This creates a pixelated effect when used as a mag filter.
+    #[default]
     Nearest,
     /// Linear Interpolation.
     ///
@@ -128,12 +183,6 @@ pub enum FilterMode {
     Linear,
 }
 
-impl Default for FilterMode {
-    fn default() -> Self {
-        Self::Nearest
-    }
-}
-
 impl From<FilterMode> for wgpu::FilterMode {
     fn from(mode: FilterMode) -> Self {
         match mode {
@@ -0,0 +1,223 @@
+use super::{
+    bind_group::{
+        BindGroupLayoutEntry, BindGroupLayoutId, BindingType, BufferBindingType,
+        StorageTextureAccess, TextureSampleType,
+    },
+    texture::{TextureFormat, TextureViewDimension},
+    types::ShaderStages,
+};
+use std::{num::NonZeroU64, path::PathBuf};
+
+resource_id!(ShaderModuleId);
+
+/// Error of a shader module creation or reflection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ShaderError {
+    /// The WGSL source does not parse, holds the parser message.
+    Parse(String),
+    /// The module is invalid, holds the validator message.
+    Validation(String),
+    /// The shader module does not exist.
+    ShaderModuleNotFound(ShaderModuleId),
+    /// A bind group layout of a pipeline does not exist.
+    BindGroupLayoutNotFound(BindGroupLayoutId),
+    /// A global resource has a type no bind group layout entry can describe, such as a push
+    /// constant or a binding array.
+    UnsupportedBinding { group: u32, binding: u32 },
+    /// An included file could not be read, holds the file and line of the directive if it is
+    /// in a file.
+    Include {
+        path: PathBuf,
+        from: Option<(PathBuf, usize)>,
+        message: String,
+    },
+    /// An include directive is not followed by a quoted path, the line counts from 1.
+    InvalidInclude { file: Option<PathBuf>, line: usize },
+    /// Files include each other, holds the files from the first one back to itself.
+    IncludeCycle(Vec<PathBuf>),
+}
+
+/// Derives the bind group layout entries of every group a module binds resources in.
+///
+/// The layout of group `i` is at index `i`, groups the module skips get no entries so the
+/// layouts can be used for a pipeline layout as is. Each entry is visible to the stages of the
+/// entry points using it, or to every stage of the module if none does.
+///
+/// Some properties are not written in WGSL, the reflected entries assume the most common:
+/// float textures are filterable, samplers filter and buffers have no dynamic offset.
+pub fn reflect_layouts(
+    module: &naga::Module,
+    info: &naga::valid::ModuleInfo,
+) -> Result<Vec<Vec<BindGroupLayoutEntry>>, ShaderError> {
+    let module_stages = module
+        .entry_points
+        .iter()
+        .fold(ShaderStages::NONE, |stages, entry_point| {
+            stages | shader_stage(entry_point.stage)
+        });
+    let mut layouts: Vec<Vec<BindGroupLayoutEntry>> = Vec::new();
+    for (handle, global) in module.global_variables.iter() {
+        let resource_binding = match &global.binding {
+            Some(resource_binding) => resource_binding,
+            None => continue,
+        };
+        let (group, binding) = (resource_binding.group, resource_binding.binding);
+        let ty = binding_type(module, global)
+            .ok_or(ShaderError::UnsupportedBinding { group, binding })?;
+        // Only the stages using the resource need to see it.
+        let visibility = module
+            .entry_points
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| !info.get_entry_point(index)[handle].is_empty())
+            .fold(ShaderStages::NONE, |stages, (_, entry_point)| {
+                stages | shader_stage(entry_point.stage)
+            });
+        let visibility = if visibility.is_empty() {
+            module_stages
+        } else {
+            visibility
+        };
+        if layouts.len() <= group as usize {
+            layouts.resize_with(group as usize + 1, Vec::new);
+        }
+        layouts[group as usize].push(BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty,
+            count: None,
+        });
+    }
+    for entries in &mut layouts {
+        entries.sort_by_key(|entry| entry.binding);
+    }
+    Ok(layouts)
+}
+
+fn shader_stage(stage: naga::ShaderStage) -> ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => ShaderStages::COMPUTE,
+    }
+}
+
+/// Gets the size of the structure a buffer binding declares, a runtime-sized array counting
+/// one element.
+fn buffer_binding_size(module: &naga::Module, global: &naga::GlobalVariable) -> Option<NonZeroU64> {
+    NonZeroU64::new(u64::from(
+        module.types[global.ty].inner.span(&module.constants),
+    ))
+}
+
+/// Gets the binding type of a global resource, `None` if it can not be bound through a bind
+/// group.
+fn binding_type(module: &naga::Module, global: &naga::GlobalVariable) -> Option<BindingType> {
+    match global.class {
+        naga::StorageClass::Uniform => Some(BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: buffer_binding_size(module, global),
+        }),
+        naga::StorageClass::Storage { access } => Some(BindingType::Buffer {
+            ty: BufferBindingType::Storage {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: buffer_binding_size(module, global),
+        }),
+        naga::StorageClass::Handle => match module.types[global.ty].inner {
+            naga::TypeInner::Sampler { comparison } => Some(BindingType::Sampler {
+                filtering: true,
+                comparison,
+            }),
+            naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let view_dimension = view_dimension(dim, arrayed)?;
+                Some(match class {
+                    naga::ImageClass::Sampled { kind, multi } => BindingType::Texture {
+                        sample_type: match kind {
+                            naga::ScalarKind::Sint => TextureSampleType::Sint,
+                            naga::ScalarKind::Uint => TextureSampleType::Uint,
+                            // Multisampled textures can not be filtered.
+                            _ => TextureSampleType::Float { filterable: !multi },
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Depth { multi } => BindingType::Texture {
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Storage { format, access } => BindingType::StorageTexture {
+                        access: match (
+                            access.contains(naga::StorageAccess::LOAD),
+                            access.contains(naga::StorageAccess::STORE),
+                        ) {
+                            (true, true) => StorageTextureAccess::ReadWrite,
+                            (true, false) => StorageTextureAccess::ReadOnly,
+                            _ => StorageTextureAccess::WriteOnly,
+                        },
+                        format: storage_format(format),
+                        view_dimension,
+                    },
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn view_dimension(dim: naga::ImageDimension, arrayed: bool) -> Option<TextureViewDimension> {
+    Some(match (dim, arrayed) {
+        (naga::ImageDimension::D1, false) => TextureViewDimension::D1,
+        (naga::ImageDimension::D2, false) => TextureViewDimension::D2,
+        (naga::ImageDimension::D2, true) => TextureViewDimension::D2Array,
+        (naga::ImageDimension::D3, false) => TextureViewDimension::D3,
+        (naga::ImageDimension::Cube, false) => TextureViewDimension::Cube,
+        (naga::ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+        _ => return None,
+    })
+}
+
+fn storage_format(format: naga::StorageFormat) -> TextureFormat {
+    match format {
+        naga::StorageFormat::R8Unorm => TextureFormat::R8Unorm,
+        naga::StorageFormat::R8Snorm => TextureFormat::R8Snorm,
+        naga::StorageFormat::R8Uint => TextureFormat::R8Uint,
+        naga::StorageFormat::R8Sint => TextureFormat::R8Sint,
+        naga::StorageFormat::R16Uint => TextureFormat::R16Uint,
+        naga::StorageFormat::R16Sint => TextureFormat::R16Sint,
+        naga::StorageFormat::R16Float => TextureFormat::R16Float,
+        naga::StorageFormat::Rg8Unorm => TextureFormat::Rg8Unorm,
+        naga::StorageFormat::Rg8Snorm => TextureFormat::Rg8Snorm,
+        naga::StorageFormat::Rg8Uint => TextureFormat::Rg8Uint,
+        naga::StorageFormat::Rg8Sint => TextureFormat::Rg8Sint,
+        naga::StorageFormat::R32Uint => TextureFormat::R32Uint,
+        naga::StorageFormat::R32Sint => TextureFormat::R32Sint,
+        naga::StorageFormat::R32Float => TextureFormat::R32Float,
+        naga::StorageFormat::Rg16Uint => TextureFormat::Rg16Uint,
+        naga::StorageFormat::Rg16Sint => TextureFormat::Rg16Sint,
+        naga::StorageFormat::Rg16Float => TextureFormat::Rg16Float,
+        naga::StorageFormat::Rgba8Unorm => TextureFormat::Rgba8Unorm,
+        naga::StorageFormat::Rgba8Snorm => TextureFormat::Rgba8Snorm,
+        naga::StorageFormat::Rgba8Uint => TextureFormat::Rgba8Uint,
+        naga::StorageFormat::Rgba8Sint => TextureFormat::Rgba8Sint,
+        naga::StorageFormat::Rgb10a2Unorm => TextureFormat::Rgb10a2Unorm,
+        naga::StorageFormat::Rg11b10Float => TextureFormat::Rg11b10Float,
+        naga::StorageFormat::Rg32Uint => TextureFormat::Rg32Uint,
+        naga::StorageFormat::Rg32Sint => TextureFormat::Rg32Sint,
+        naga::StorageFormat::Rg32Float => TextureFormat::Rg32Float,
+        naga::StorageFormat::Rgba16Uint => TextureFormat::Rgba16Uint,
+        naga::StorageFormat::Rgba16Sint => TextureFormat::Rgba16Sint,
+        naga::StorageFormat::Rgba16Float => TextureFormat::Rgba16Float,
+        naga::StorageFormat::Rgba32Uint => TextureFormat::Rgba32Uint,
+        naga::StorageFormat::Rgba32Sint => TextureFormat::Rgba32Sint,
+        naga::StorageFormat::Rgba32Float => TextureFormat::Rgba32Float,
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Waker},
+};
+
+/// Index of a submission made through [`ResourceContext::submit`], later submissions have
+/// greater indices.
+///
+/// [`ResourceContext::submit`]: super::ResourceContext::submit
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SubmissionIndex(u64);
+
+/// Future resolving once the GPU is done with a submission.
+type WorkDone = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Numbers the submissions to a queue and tracks which ones the GPU completed.
+///
+/// wgpu does not expose the index of a submission, so every submission keeps the
+/// [`wgpu::Queue::on_submitted_work_done`] future completing its index instead. The futures
+/// only resolve while the device is polled.
+#[derive(Default)]
+pub(super) struct SubmissionTracker {
+    /// Index of the last submission, locked while submitting or writing through the queue so
+    /// indices follow the queue order.
+    submitted: Mutex<u64>,
+    /// Submissions the GPU may not have completed yet, oldest first.
+    pending: Mutex<VecDeque<(u64, WorkDone)>>,
+    /// Index of the last submission the GPU completed, every earlier one is complete too.
+    completed: AtomicU64,
+}
+
+impl SubmissionTracker {
+    /// Submits `command_buffers` to `queue`.
+    pub fn submit<I: IntoIterator<Item = wgpu::CommandBuffer>>(
+        &self,
+        queue: &wgpu::Queue,
+        command_buffers: I,
+    ) -> SubmissionIndex {
+        let mut submitted = self.submitted.lock().unwrap();
+        *submitted += 1;
+        queue.submit(command_buffers);
+        // Completes once the GPU is done with every submission so far, this one included.
+        let done = queue.on_submitted_work_done();
+        self.pending
+            .lock()
+            .unwrap()
+            .push_back((*submitted, Box::pin(done)));
+        SubmissionIndex(*submitted)
+    }
+
+    /// Stages a write on the queue through `write`, returning the index of the submission it
+    /// lands in.
+    pub fn write(&self, write: impl FnOnce()) -> SubmissionIndex {
+        let submitted = self.submitted.lock().unwrap();
+        write();
+        SubmissionIndex(*submitted + 1)
+    }
+
+    /// Whether `index` is submitted yet, writes land in a submission not made yet.
+    pub fn is_submitted(&self, index: SubmissionIndex) -> bool {
+        *self.submitted.lock().unwrap() >= index.0
+    }
+
+    /// Whether the GPU completed the submission, as of the last device poll.
+    pub fn is_done(&self, index: SubmissionIndex) -> bool {
+        if self.completed.load(Ordering::Acquire) >= index.0 {
+            return true;
+        }
+        // Pops the submissions whose future resolved, the callbacks fired on the last poll.
+        let mut pending = self.pending.lock().unwrap();
+        let mut cx = Context::from_waker(Waker::noop());
+        while let Some((submission, done)) = pending.front_mut() {
+            if done.as_mut().poll(&mut cx).is_pending() {
+                break;
+            }
+            self.completed.fetch_max(*submission, Ordering::AcqRel);
+            pending.pop_front();
+        }
+        self.completed.load(Ordering::Acquire) >= index.0
+    }
+}
@@ -0,0 +1,80 @@
+use winit::window::WindowId;
+
+/// How a surface trades input latency against power usage when presenting frames.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum LatencyMode {
+    /// Presents the latest ready frame on vertical blank without tearing, frames are rendered
+    /// without waiting and older ones are dropped.
+    ///
+    /// Maps to [`PresentMode::Mailbox`].
+    LowLatency,
+    /// Waits for vertical blank and never drops frames, capping the frame rate to the display.
+    ///
+    /// Maps to [`PresentMode::Fifo`].
+    #[default]
+    Balanced,
+    /// Waits for vertical blank and never drops frames like [`Balanced`](Self::Balanced).
+    ///
+    /// Maps to [`PresentMode::Fifo`], wgpu 0.10 has no mode presenting less often.
+    PowerSaving,
+}
+
+impl LatencyMode {
+    /// Gets the present mode used to configure surfaces.
+    pub fn present_mode(self) -> PresentMode {
+        match self {
+            LatencyMode::LowLatency => PresentMode::Mailbox,
+            LatencyMode::Balanced | LatencyMode::PowerSaving => PresentMode::Fifo,
+        }
+    }
+}
+
+/// Behavior of the presentation engine based on frame rate.
+///
+/// Only `Fifo` is guaranteed to be supported, when the surface does not support the
+/// requested mode wgpu falls back to `Fifo` while configuring it.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum PresentMode {
+    /// The presentation engine does **not** wait for a vertical blanking period and
+    /// the request is presented immediately. This is a low-latency presentation mode,
+    /// but visible tearing may be observed.
+    Immediate,
+    /// The presentation engine waits for the next vertical blanking period to update
+    /// the current image, but frames may be submitted without delay. This is a low-latency
+    /// presentation mode and visible tearing will **not** be observed.
+    Mailbox,
+    /// The presentation engine waits for the next vertical blanking period to update
+    /// the current image. The framerate will be capped at the display refresh rate,
+    /// corresponding to the `VSync`. Tearing cannot be observed.
+    Fifo,
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(mode: PresentMode) -> Self {
+        match mode {
+            PresentMode::Immediate => Self::Immediate,
+            PresentMode::Mailbox => Self::Mailbox,
+            PresentMode::Fifo => Self::Fifo,
+        }
+    }
+}
+
+/// Presents a surface texture got from [`ResourceContext::surface_current_texture`].
+///
+/// Call it once the commands drawing into the texture are submitted. wgpu 0.10 presents a
+/// surface texture when it is dropped, presenting through here keeps the point explicit instead
+/// of depending on where the texture goes out of scope.
+///
+/// [`ResourceContext::surface_current_texture`]: super::ResourceContext::surface_current_texture
+pub fn present(texture: wgpu::SurfaceTexture) {
+    drop(texture);
+}
+
+/// Error of a surface configuration.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SurfaceError {
+    /// The window has no surface.
+    SurfaceNotFound(WindowId),
+    /// The adapter can not present to the window.
+    UnsupportedSurface(WindowId),
+}
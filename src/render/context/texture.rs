@@ -1,23 +1,266 @@
-use uuid::Uuid;
+use bitflags::bitflags;
+use std::num::NonZeroU32;
 
-///
+resource_id!(TextureId);
+
+resource_id!(TextureViewId);
+
+/// Describes a Texture.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-pub struct TextureId(Uuid);
+pub struct TextureDescriptor {
+    /// Debug label of the texture. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
+    /// Size of the texture. All components must be greater than zero. For a
+    /// regular 1D/2D texture, the unused sizes will be 1. For 2DArray textures,
+    /// Z is the number of 2D textures in that array.
+    pub size: Extent3d,
+    /// Mip count of texture. For a texture with no extra mips, this must be 1.
+    pub mip_level_count: u32,
+    /// Sample count of texture. If this is not 1, texture must have multisampled binding set to true.
+    pub sample_count: u32,
+    /// Dimensions of the texture.
+    pub dimension: TextureDimension,
+    /// Format of the texture.
+    pub format: TextureFormat,
+    /// Allowed usages of the texture. If used in other ways, the operation will panic.
+    pub usage: TextureUsages,
+}
+
+impl From<&TextureDescriptor> for wgpu::TextureDescriptor<'_> {
+    fn from(desc: &TextureDescriptor) -> Self {
+        Self {
+            label: desc.label,
+            size: desc.size.into(),
+            mip_level_count: desc.mip_level_count,
+            sample_count: desc.sample_count,
+            dimension: desc.dimension.into(),
+            format: desc.format.into(),
+            usage: desc.usage.into(),
+        }
+    }
+}
 
-impl TextureId {
+bitflags! {
+    /// Different ways that you can use a texture.
+    ///
+    /// The usages determine what kind of memory the texture is allocated from and what actions the texture can partake in.
+    pub struct TextureUsages: u32 {
+        /// Allows a texture to be the source in a [`CommandEncoder::copy_texture_to_buffer`] or
+        /// [`CommandEncoder::copy_texture_to_texture`] operation.
+        const COPY_SRC = 1 << 0;
+        /// Allows a texture to be the destination in a  [`CommandEncoder::copy_texture_to_buffer`],
+        /// [`CommandEncoder::copy_texture_to_texture`], or [`Queue::write_texture`] operation.
+        const COPY_DST = 1 << 1;
+        /// Allows a texture to be a [`BindingType::Texture`] in a bind group.
+        const TEXTURE_BINDING = 1 << 2;
+        /// Allows a texture to be a [`BindingType::StorageTexture`] in a bind group.
+        const STORAGE_BINDING = 1 << 3;
+        /// Allows a texture to be an output attachment of a renderpass.
+        const RENDER_ATTACHMENT = 1 << 4;
+    }
+}
+
+impl From<TextureUsages> for wgpu::TextureUsages {
+    fn from(usages: TextureUsages) -> Self {
+        Self::from_bits_truncate(usages.bits)
+    }
+}
+
+impl From<wgpu::TextureUsages> for TextureUsages {
+    fn from(usages: wgpu::TextureUsages) -> Self {
+        Self::from_bits_truncate(usages.bits())
+    }
+}
+
+bitflags! {
+    /// Feature flags for a texture format.
+    pub struct TextureFormatFeatureFlags: u32 {
+        /// A texture with this format can be sampled with a filtering sampler.
+        const FILTERABLE = 1 << 0;
+        /// When used as a render attachment, a texture with this format can be blended into.
+        const BLENDABLE = 1 << 1;
+        /// When used as a STORAGE texture, then a texture with this format can be bound with
+        /// [`StorageTextureAccess::ReadOnly`] or [`StorageTextureAccess::ReadWrite`].
+        const STORAGE_READ_WRITE = 1 << 2;
+        /// When used as a STORAGE texture, then a texture with this format can be written to with atomics.
+        const STORAGE_ATOMICS = 1 << 3;
+    }
+}
+
+/// Features supported by a given texture format.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct TextureFormatFeatures {
+    /// Valid bits for `TextureDescriptor::usage` provided for format creation.
+    pub allowed_usages: TextureUsages,
+    /// Additional property flags for the format.
+    pub flags: TextureFormatFeatureFlags,
+}
+
+impl TextureFormatFeatures {
+    /// Whether a texture with this format can be sampled with a filtering sampler.
+    pub fn filterable(&self) -> bool {
+        self.flags.contains(TextureFormatFeatureFlags::FILTERABLE)
+    }
+
+    /// Whether a texture with this format can be used as a render attachment.
+    pub fn renderable(&self) -> bool {
+        self.allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+    }
+
+    /// Whether a render attachment with this format supports blending.
+    pub fn blendable(&self) -> bool {
+        self.flags.contains(TextureFormatFeatureFlags::BLENDABLE)
+    }
+
+    /// Whether a texture with this format can be bound as a storage texture.
+    pub fn storage(&self) -> bool {
+        self.allowed_usages.contains(TextureUsages::STORAGE_BINDING)
+    }
+}
+
+impl From<wgpu::TextureFormatFeatures> for TextureFormatFeatures {
+    fn from(features: wgpu::TextureFormatFeatures) -> Self {
+        let allowed_usages = TextureUsages::from(features.allowed_usages);
+        let mut flags = TextureFormatFeatureFlags::empty();
+        flags.set(TextureFormatFeatureFlags::FILTERABLE, features.filterable);
+        // Every renderable color format sampled as filterable float supports blending
+        flags.set(
+            TextureFormatFeatureFlags::BLENDABLE,
+            features.filterable && allowed_usages.contains(TextureUsages::RENDER_ATTACHMENT),
+        );
+        flags.set(
+            TextureFormatFeatureFlags::STORAGE_READ_WRITE,
+            features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE),
+        );
+        flags.set(
+            TextureFormatFeatureFlags::STORAGE_ATOMICS,
+            features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::STORAGE_ATOMICS),
+        );
+        Self {
+            allowed_usages,
+            flags,
+        }
+    }
+}
+
+/// Describes a TextureView.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct TextureViewDescriptor {
+    /// Debug label of the texture view. This will show up in graphics debuggers for easy identification.
+    pub label: Option<&'static str>,
+    /// Format of the texture view. At this time, it must be the same as the underlying format of the texture.
+    pub format: Option<TextureFormat>,
+    /// The dimension of the texture view. For 1D textures, this must be `1D`. For 2D textures it must be one of
+    /// `D2`, `D2Array`, `Cube`, and `CubeArray`. For 3D textures it must be `3D`
+    pub dimension: Option<TextureViewDimension>,
+    /// Aspect of the texture. Color textures must be [`TextureAspect::All`].
+    pub aspect: TextureAspect,
+    /// Base mip level.
+    pub base_mip_level: u32,
+    /// Mip level count.
+    /// If `Some(count)`, `base_mip_level + count` must be less or equal to underlying texture mip count.
+    /// If `None`, considered to include the rest of the mipmap levels, but at least 1 in total.
+    pub mip_level_count: Option<NonZeroU32>,
+    /// Base array layer.
+    pub base_array_layer: u32,
+    /// Layer count.
+    /// If `Some(count)`, `base_array_layer + count` must be less or equal to the underlying array count.
+    /// If `None`, considered to include the rest of the array layers, but at least 1 in total.
+    pub array_layer_count: Option<NonZeroU32>,
+}
+
+impl TextureViewDescriptor {
+    /// Describes a view of the depth aspect only.
     ///
-    pub fn new() -> Self {
-        Self(Uuid::new_v4())
+    /// Such a view can be sampled while the texture is bound as a read-only depth attachment.
+    pub fn depth_only(label: Option<&'static str>) -> Self {
+        Self {
+            label,
+            aspect: TextureAspect::DepthOnly,
+            ..Default::default()
+        }
+    }
+
+    /// Describes a 2D view of a single mip level and array layer.
+    ///
+    /// Render passes only accept views of one mip level and one layer, such a view renders into
+    /// a mip of a texture or a face of a cubemap. Its size is given by [`Extent3d::mip_level_size`].
+    pub fn attachment(label: Option<&'static str>, mip_level: u32, array_layer: u32) -> Self {
+        Self {
+            label,
+            dimension: Some(TextureViewDimension::D2),
+            base_mip_level: mip_level,
+            mip_level_count: NonZeroU32::new(1),
+            base_array_layer: array_layer,
+            array_layer_count: NonZeroU32::new(1),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&TextureViewDescriptor> for wgpu::TextureViewDescriptor<'_> {
+    fn from(desc: &TextureViewDescriptor) -> Self {
+        Self {
+            label: desc.label,
+            format: desc.format.map(Into::into),
+            dimension: desc.dimension.map(Into::into),
+            aspect: desc.aspect.into(),
+            base_mip_level: desc.base_mip_level,
+            mip_level_count: desc.mip_level_count,
+            base_array_layer: desc.base_array_layer,
+            array_layer_count: desc.array_layer_count,
+        }
+    }
+}
+
+/// Kind of data the texture holds.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum TextureAspect {
+    /// Depth, Stencil, and Color.
+    #[default]
+    All,
+    /// Stencil.
+    StencilOnly,
+    /// Depth.
+    DepthOnly,
+}
+
+impl From<TextureAspect> for wgpu::TextureAspect {
+    fn from(aspect: TextureAspect) -> Self {
+        match aspect {
+            TextureAspect::All => Self::All,
+            TextureAspect::StencilOnly => Self::StencilOnly,
+            TextureAspect::DepthOnly => Self::DepthOnly,
+        }
     }
 }
 
 /// Extent of a texture related operation.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Extent3d {
     pub width: u32,
     pub height: u32,
     pub depth_or_array_layers: u32,
 }
 
+impl Extent3d {
+    /// Gets the size of the 2D mip level `level`, halving the width and height down to 1.
+    ///
+    /// The array layers are kept, they are not mipmapped. Levels past the last one are 1 by 1.
+    pub fn mip_level_size(&self, level: u32) -> Self {
+        Self {
+            width: self.width.checked_shr(level).unwrap_or(0).max(1),
+            height: self.height.checked_shr(level).unwrap_or(0).max(1),
+            depth_or_array_layers: self.depth_or_array_layers,
+        }
+    }
+}
+
 impl From<Extent3d> for wgpu::Extent3d {
     fn from(extent_3d: Extent3d) -> Self {
         Self {
@@ -28,7 +271,104 @@ impl From<Extent3d> for wgpu::Extent3d {
     }
 }
 
+/// Origin of a texture related operation, in texels of the mip level.
+///
+/// `z` is the first array layer of 2D texture arrays and the first slice of 3D textures.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Origin3d {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl From<Origin3d> for wgpu::Origin3d {
+    fn from(origin_3d: Origin3d) -> Self {
+        Self {
+            x: origin_3d.x,
+            y: origin_3d.y,
+            z: origin_3d.z,
+        }
+    }
+}
+
+/// Region of a texture a copy, upload or readback starts at.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct TexelCopyInfo {
+    /// Texture to copy to or from.
+    pub texture: TextureId,
+    /// Mip level of the texture.
+    pub mip_level: u32,
+    /// Texel of the mip level the region starts at.
+    pub origin: Origin3d,
+    /// Aspect of the texture. Color textures must be [`TextureAspect::All`].
+    pub aspect: TextureAspect,
+}
+
+impl TexelCopyInfo {
+    /// Covers the first mip level of a texture from its corner.
+    pub fn new(texture: TextureId) -> Self {
+        Self {
+            texture,
+            mip_level: 0,
+            origin: Origin3d::default(),
+            aspect: TextureAspect::All,
+        }
+    }
+
+    /// Gets the wgpu copy info once `texture` is retrieved.
+    pub(super) fn to_wgpu<'a>(self, texture: &'a wgpu::Texture) -> wgpu::ImageCopyTexture<'a> {
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: self.mip_level,
+            origin: self.origin.into(),
+            aspect: self.aspect.into(),
+        }
+    }
+}
+
+/// Error of a texture upload, copy or readback.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TextureError {
+    /// The texture does not exist.
+    TextureNotFound(TextureId),
+    /// The texture lacks usages the operation requires.
+    MissingUsages(TextureUsages),
+    /// The data does not cover exactly the written region.
+    DataSizeMismatch { expected: u64, actual: u64 },
+    /// The staging buffer of a readback could not be mapped.
+    MapFailed(TextureId),
+    /// The format needs features the device does not have enabled, see
+    /// [`TextureFormat::required_features`].
+    UnsupportedFormat {
+        format: TextureFormat,
+        missing: wgpu::Features,
+    },
+    /// The operation only supports color formats, depth and stencil need to be read one
+    /// aspect at a time.
+    DepthStencilFormat(TextureFormat),
+}
+
+/// Checks tightly packed `data` of `texel_size` bytes per texel covers exactly a `width` by
+/// `height` image, wgpu panics otherwise.
+pub fn check_image_data(
+    data: &[u8],
+    texel_size: u64,
+    width: u32,
+    height: u32,
+) -> Result<(), TextureError> {
+    let expected = u64::from(width) * u64::from(height) * texel_size;
+    if data.len() as u64 == expected {
+        Ok(())
+    } else {
+        Err(TextureError::DataSizeMismatch {
+            expected,
+            actual: data.len() as u64,
+        })
+    }
+}
+
 /// Dimensionality of a texture.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum TextureDimension {
     /// 1D texture.
     D1,
@@ -48,11 +388,43 @@ impl From<TextureDimension> for wgpu::TextureDimension {
     }
 }
 
+/// Dimensions of a particular texture view.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum TextureViewDimension {
+    /// A one dimensional texture. `texture1D` in glsl shaders.
+    D1,
+    /// A two dimensional texture. `texture2D` in glsl shaders.
+    #[default]
+    D2,
+    /// A two dimensional array texture. `texture2DArray` in glsl shaders.
+    D2Array,
+    /// A cubemap texture. `textureCube` in glsl shaders.
+    Cube,
+    /// A cubemap array texture. `textureCubeArray` in glsl shaders.
+    CubeArray,
+    /// A three dimensional texture. `texture3D` in glsl shaders.
+    D3,
+}
+
+impl From<TextureViewDimension> for wgpu::TextureViewDimension {
+    fn from(dimension: TextureViewDimension) -> Self {
+        match dimension {
+            TextureViewDimension::D1 => Self::D1,
+            TextureViewDimension::D2 => Self::D2,
+            TextureViewDimension::D2Array => Self::D2Array,
+            TextureViewDimension::Cube => Self::Cube,
+            TextureViewDimension::CubeArray => Self::CubeArray,
+            TextureViewDimension::D3 => Self::D3,
+        }
+    }
+}
+
 /// Underlying texture data format.
 ///
 /// If there is a conversion in the format (such as srgb -> linear),
 /// The conversion listed is for loading from texture in a shader.
 /// When writing to the texture, the opposite conversion takes place.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum TextureFormat {
     /// Red channel only. 8 bit integer per channel. [0, 255] converted to/from float [0, 1] in shader.
     R8Unorm,
@@ -427,6 +799,201 @@ pub enum TextureFormat {
     Astc12x12RgbaUnormSrgb,
 }
 
+impl TextureFormat {
+    /// Gets the features every adapter supports for this format.
+    ///
+    /// Adapters may support more, which can be queried once
+    /// `Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` is enabled.
+    pub fn guaranteed_format_features(self) -> TextureFormatFeatures {
+        wgpu::TextureFormat::from(self)
+            .describe()
+            .guaranteed_format_features
+            .into()
+    }
+
+    /// Gets the features the device must have enabled to create a texture of this format.
+    ///
+    /// Block compressed formats each need the feature of their family, the other formats need
+    /// none.
+    pub fn required_features(self) -> wgpu::Features {
+        match self {
+            TextureFormat::Bc1RgbaUnorm
+            | TextureFormat::Bc1RgbaUnormSrgb
+            | TextureFormat::Bc2RgbaUnorm
+            | TextureFormat::Bc2RgbaUnormSrgb
+            | TextureFormat::Bc3RgbaUnorm
+            | TextureFormat::Bc3RgbaUnormSrgb
+            | TextureFormat::Bc4RUnorm
+            | TextureFormat::Bc4RSnorm
+            | TextureFormat::Bc5RgUnorm
+            | TextureFormat::Bc5RgSnorm
+            | TextureFormat::Bc6hRgbUfloat
+            | TextureFormat::Bc6hRgbSfloat
+            | TextureFormat::Bc7RgbaUnorm
+            | TextureFormat::Bc7RgbaUnormSrgb => wgpu::Features::TEXTURE_COMPRESSION_BC,
+            TextureFormat::Etc2RgbUnorm
+            | TextureFormat::Etc2RgbUnormSrgb
+            | TextureFormat::Etc2RgbA1Unorm
+            | TextureFormat::Etc2RgbA1UnormSrgb
+            | TextureFormat::EacRUnorm
+            | TextureFormat::EacRSnorm
+            | TextureFormat::EacRgUnorm
+            | TextureFormat::EacRgSnorm => wgpu::Features::TEXTURE_COMPRESSION_ETC2,
+            TextureFormat::Astc4x4RgbaUnorm
+            | TextureFormat::Astc4x4RgbaUnormSrgb
+            | TextureFormat::Astc5x4RgbaUnorm
+            | TextureFormat::Astc5x4RgbaUnormSrgb
+            | TextureFormat::Astc5x5RgbaUnorm
+            | TextureFormat::Astc5x5RgbaUnormSrgb
+            | TextureFormat::Astc6x5RgbaUnorm
+            | TextureFormat::Astc6x5RgbaUnormSrgb
+            | TextureFormat::Astc6x6RgbaUnorm
+            | TextureFormat::Astc6x6RgbaUnormSrgb
+            | TextureFormat::Astc8x5RgbaUnorm
+            | TextureFormat::Astc8x5RgbaUnormSrgb
+            | TextureFormat::Astc8x6RgbaUnorm
+            | TextureFormat::Astc8x6RgbaUnormSrgb
+            | TextureFormat::Astc10x5RgbaUnorm
+            | TextureFormat::Astc10x5RgbaUnormSrgb
+            | TextureFormat::Astc10x6RgbaUnorm
+            | TextureFormat::Astc10x6RgbaUnormSrgb
+            | TextureFormat::Astc8x8RgbaUnorm
+            | TextureFormat::Astc8x8RgbaUnormSrgb
+            | TextureFormat::Astc10x8RgbaUnorm
+            | TextureFormat::Astc10x8RgbaUnormSrgb
+            | TextureFormat::Astc10x10RgbaUnorm
+            | TextureFormat::Astc10x10RgbaUnormSrgb
+            | TextureFormat::Astc12x10RgbaUnorm
+            | TextureFormat::Astc12x10RgbaUnormSrgb
+            | TextureFormat::Astc12x12RgbaUnorm
+            | TextureFormat::Astc12x12RgbaUnormSrgb => wgpu::Features::TEXTURE_COMPRESSION_ASTC_LDR,
+            _ => wgpu::Features::empty(),
+        }
+    }
+
+    /// Gets the sample counts a texture of this format can be created with on `adapter`.
+    ///
+    /// wgpu does not report which counts a backend supports, so only the counts every adapter
+    /// guarantees are returned: 1, plus 4 for renderable formats that can be multisampled.
+    /// Higher counts may be rejected by the driver without any validation error.
+    pub fn supported_sample_counts(self, adapter: &wgpu::Adapter) -> Vec<u32> {
+        let renderable = adapter
+            .get_texture_format_features(self.into())
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+        // 32 bit integer and wide formats can not be multisampled
+        let multisampled = !matches!(
+            self,
+            TextureFormat::R32Uint
+                | TextureFormat::R32Sint
+                | TextureFormat::Rg32Uint
+                | TextureFormat::Rg32Sint
+                | TextureFormat::Rg32Float
+                | TextureFormat::Rgba32Uint
+                | TextureFormat::Rgba32Sint
+                | TextureFormat::Rgba32Float
+        );
+        if renderable && multisampled {
+            vec![1, 4]
+        } else {
+            vec![1]
+        }
+    }
+
+    /// Gets the size of a row of `width` texels, tightly packed.
+    ///
+    /// Rows of block compressed formats are rows of blocks, covering several rows of texels.
+    pub fn unpadded_bytes_per_row(self, width: u32) -> u32 {
+        let info = wgpu::TextureFormat::from(self).describe();
+        let block_width = info.block_dimensions.0 as u32;
+        width.div_ceil(block_width) * info.block_size as u32
+    }
+
+    /// Gets the size of a row of `width` texels rounded up to
+    /// [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`], as texture to buffer copies require.
+    pub fn aligned_bytes_per_row(self, width: u32) -> u32 {
+        self.row_pitch(width).padded
+    }
+
+    /// Gets the unpadded and padded sizes of a row of `width` texels.
+    pub fn row_pitch(self, width: u32) -> RowPitch {
+        let unpadded = self.unpadded_bytes_per_row(width);
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        RowPitch {
+            unpadded,
+            padded: unpadded.div_ceil(align) * align,
+        }
+    }
+
+    /// Whether the format has a depth aspect, it is sampled as a `texture_depth_*`.
+    pub fn is_depth(self) -> bool {
+        wgpu::TextureFormat::from(self).describe().sample_type == wgpu::TextureSampleType::Depth
+    }
+
+    /// Whether the format is converted from srgb to linear when sampled.
+    pub fn is_srgb(self) -> bool {
+        self.remove_srgb_suffix() != self
+    }
+
+    /// Gets the srgb variant of the format, formats without one are returned unchanged.
+    pub fn add_srgb_suffix(self) -> Self {
+        match self {
+            TextureFormat::Rgba8Unorm => TextureFormat::Rgba8UnormSrgb,
+            TextureFormat::Bgra8Unorm => TextureFormat::Bgra8UnormSrgb,
+            TextureFormat::Bc1RgbaUnorm => TextureFormat::Bc1RgbaUnormSrgb,
+            TextureFormat::Bc2RgbaUnorm => TextureFormat::Bc2RgbaUnormSrgb,
+            TextureFormat::Bc3RgbaUnorm => TextureFormat::Bc3RgbaUnormSrgb,
+            TextureFormat::Bc7RgbaUnorm => TextureFormat::Bc7RgbaUnormSrgb,
+            TextureFormat::Etc2RgbUnorm => TextureFormat::Etc2RgbUnormSrgb,
+            TextureFormat::Etc2RgbA1Unorm => TextureFormat::Etc2RgbA1UnormSrgb,
+            TextureFormat::Astc4x4RgbaUnorm => TextureFormat::Astc4x4RgbaUnormSrgb,
+            TextureFormat::Astc5x4RgbaUnorm => TextureFormat::Astc5x4RgbaUnormSrgb,
+            TextureFormat::Astc5x5RgbaUnorm => TextureFormat::Astc5x5RgbaUnormSrgb,
+            TextureFormat::Astc6x5RgbaUnorm => TextureFormat::Astc6x5RgbaUnormSrgb,
+            TextureFormat::Astc6x6RgbaUnorm => TextureFormat::Astc6x6RgbaUnormSrgb,
+            TextureFormat::Astc8x5RgbaUnorm => TextureFormat::Astc8x5RgbaUnormSrgb,
+            TextureFormat::Astc8x6RgbaUnorm => TextureFormat::Astc8x6RgbaUnormSrgb,
+            TextureFormat::Astc10x5RgbaUnorm => TextureFormat::Astc10x5RgbaUnormSrgb,
+            TextureFormat::Astc10x6RgbaUnorm => TextureFormat::Astc10x6RgbaUnormSrgb,
+            TextureFormat::Astc8x8RgbaUnorm => TextureFormat::Astc8x8RgbaUnormSrgb,
+            TextureFormat::Astc10x8RgbaUnorm => TextureFormat::Astc10x8RgbaUnormSrgb,
+            TextureFormat::Astc10x10RgbaUnorm => TextureFormat::Astc10x10RgbaUnormSrgb,
+            TextureFormat::Astc12x10RgbaUnorm => TextureFormat::Astc12x10RgbaUnormSrgb,
+            TextureFormat::Astc12x12RgbaUnorm => TextureFormat::Astc12x12RgbaUnormSrgb,
+            _ => self,
+        }
+    }
+
+    /// Gets the linear variant of the format, formats without one are returned unchanged.
+    pub fn remove_srgb_suffix(self) -> Self {
+        match self {
+            TextureFormat::Rgba8UnormSrgb => TextureFormat::Rgba8Unorm,
+            TextureFormat::Bgra8UnormSrgb => TextureFormat::Bgra8Unorm,
+            TextureFormat::Bc1RgbaUnormSrgb => TextureFormat::Bc1RgbaUnorm,
+            TextureFormat::Bc2RgbaUnormSrgb => TextureFormat::Bc2RgbaUnorm,
+            TextureFormat::Bc3RgbaUnormSrgb => TextureFormat::Bc3RgbaUnorm,
+            TextureFormat::Bc7RgbaUnormSrgb => TextureFormat::Bc7RgbaUnorm,
+            TextureFormat::Etc2RgbUnormSrgb => TextureFormat::Etc2RgbUnorm,
+            TextureFormat::Etc2RgbA1UnormSrgb => TextureFormat::Etc2RgbA1Unorm,
+            TextureFormat::Astc4x4RgbaUnormSrgb => TextureFormat::Astc4x4RgbaUnorm,
+            TextureFormat::Astc5x4RgbaUnormSrgb => TextureFormat::Astc5x4RgbaUnorm,
+            TextureFormat::Astc5x5RgbaUnormSrgb => TextureFormat::Astc5x5RgbaUnorm,
+            TextureFormat::Astc6x5RgbaUnormSrgb => TextureFormat::Astc6x5RgbaUnorm,
+            TextureFormat::Astc6x6RgbaUnormSrgb => TextureFormat::Astc6x6RgbaUnorm,
+            TextureFormat::Astc8x5RgbaUnormSrgb => TextureFormat::Astc8x5RgbaUnorm,
+            TextureFormat::Astc8x6RgbaUnormSrgb => TextureFormat::Astc8x6RgbaUnorm,
+            TextureFormat::Astc10x5RgbaUnormSrgb => TextureFormat::Astc10x5RgbaUnorm,
+            TextureFormat::Astc10x6RgbaUnormSrgb => TextureFormat::Astc10x6RgbaUnorm,
+            TextureFormat::Astc8x8RgbaUnormSrgb => TextureFormat::Astc8x8RgbaUnorm,
+            TextureFormat::Astc10x8RgbaUnormSrgb => TextureFormat::Astc10x8RgbaUnorm,
+            TextureFormat::Astc10x10RgbaUnormSrgb => TextureFormat::Astc10x10RgbaUnorm,
+            TextureFormat::Astc12x10RgbaUnormSrgb => TextureFormat::Astc12x10RgbaUnorm,
+            TextureFormat::Astc12x12RgbaUnormSrgb => TextureFormat::Astc12x12RgbaUnorm,
+            _ => self,
+        }
+    }
+}
+
 impl From<TextureFormat> for wgpu::TextureFormat {
     fn from(format: TextureFormat) -> Self {
         match format {
@@ -522,3 +1089,168 @@ impl From<TextureFormat> for wgpu::TextureFormat {
         }
     }
 }
+
+/// Sizes of a row of a texture copy, see [`TextureFormat::row_pitch`].
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct RowPitch {
+    /// Size of the texels of a row.
+    pub unpadded: u32,
+    /// Size of a row in the buffer, the `bytes_per_row` of the copy.
+    pub padded: u32,
+}
+
+impl RowPitch {
+    /// Strips the padding of every row of data read back from a buffer.
+    pub fn unpad(&self, data: &[u8]) -> Vec<u8> {
+        let rows = data.len() / self.padded as usize;
+        let mut unpadded = Vec::with_capacity(rows * self.unpadded as usize);
+        for row in data.chunks(self.padded as usize) {
+            unpadded.extend_from_slice(&row[..self.unpadded as usize]);
+        }
+        unpadded
+    }
+}
+
+impl From<wgpu::TextureFormat> for TextureFormat {
+    fn from(format: wgpu::TextureFormat) -> Self {
+        match format {
+            wgpu::TextureFormat::R8Unorm => Self::R8Unorm,
+            wgpu::TextureFormat::R8Snorm => Self::R8Snorm,
+            wgpu::TextureFormat::R8Uint => Self::R8Uint,
+            wgpu::TextureFormat::R8Sint => Self::R8Sint,
+            wgpu::TextureFormat::R16Uint => Self::R16Uint,
+            wgpu::TextureFormat::R16Sint => Self::R16Sint,
+            wgpu::TextureFormat::R16Float => Self::R16Float,
+            wgpu::TextureFormat::Rg8Unorm => Self::Rg8Unorm,
+            wgpu::TextureFormat::Rg8Snorm => Self::Rg8Snorm,
+            wgpu::TextureFormat::Rg8Uint => Self::Rg8Uint,
+            wgpu::TextureFormat::Rg8Sint => Self::Rg8Sint,
+            wgpu::TextureFormat::R32Uint => Self::R32Uint,
+            wgpu::TextureFormat::R32Sint => Self::R32Sint,
+            wgpu::TextureFormat::R32Float => Self::R32Float,
+            wgpu::TextureFormat::Rg16Uint => Self::Rg16Uint,
+            wgpu::TextureFormat::Rg16Sint => Self::Rg16Sint,
+            wgpu::TextureFormat::Rg16Float => Self::Rg16Float,
+            wgpu::TextureFormat::Rgba8Unorm => Self::Rgba8Unorm,
+            wgpu::TextureFormat::Rgba8UnormSrgb => Self::Rgba8UnormSrgb,
+            wgpu::TextureFormat::Rgba8Snorm => Self::Rgba8Snorm,
+            wgpu::TextureFormat::Rgba8Uint => Self::Rgba8Uint,
+            wgpu::TextureFormat::Rgba8Sint => Self::Rgba8Sint,
+            wgpu::TextureFormat::Bgra8Unorm => Self::Bgra8Unorm,
+            wgpu::TextureFormat::Bgra8UnormSrgb => Self::Bgra8UnormSrgb,
+            wgpu::TextureFormat::Rgb10a2Unorm => Self::Rgb10a2Unorm,
+            wgpu::TextureFormat::Rg11b10Float => Self::Rg11b10Float,
+            wgpu::TextureFormat::Rg32Uint => Self::Rg32Uint,
+            wgpu::TextureFormat::Rg32Sint => Self::Rg32Sint,
+            wgpu::TextureFormat::Rg32Float => Self::Rg32Float,
+            wgpu::TextureFormat::Rgba16Uint => Self::Rgba16Uint,
+            wgpu::TextureFormat::Rgba16Sint => Self::Rgba16Sint,
+            wgpu::TextureFormat::Rgba16Float => Self::Rgba16Float,
+            wgpu::TextureFormat::Rgba32Uint => Self::Rgba32Uint,
+            wgpu::TextureFormat::Rgba32Sint => Self::Rgba32Sint,
+            wgpu::TextureFormat::Rgba32Float => Self::Rgba32Float,
+            wgpu::TextureFormat::Depth32Float => Self::Depth32Float,
+            wgpu::TextureFormat::Depth24Plus => Self::Depth24Plus,
+            wgpu::TextureFormat::Depth24PlusStencil8 => Self::Depth24PlusStencil8,
+            wgpu::TextureFormat::Rgb9e5Ufloat => Self::Rgb9e5Ufloat,
+            wgpu::TextureFormat::Bc1RgbaUnorm => Self::Bc1RgbaUnorm,
+            wgpu::TextureFormat::Bc1RgbaUnormSrgb => Self::Bc1RgbaUnormSrgb,
+            wgpu::TextureFormat::Bc2RgbaUnorm => Self::Bc2RgbaUnorm,
+            wgpu::TextureFormat::Bc2RgbaUnormSrgb => Self::Bc2RgbaUnormSrgb,
+            wgpu::TextureFormat::Bc3RgbaUnorm => Self::Bc3RgbaUnorm,
+            wgpu::TextureFormat::Bc3RgbaUnormSrgb => Self::Bc3RgbaUnormSrgb,
+            wgpu::TextureFormat::Bc4RUnorm => Self::Bc4RUnorm,
+            wgpu::TextureFormat::Bc4RSnorm => Self::Bc4RSnorm,
+            wgpu::TextureFormat::Bc5RgUnorm => Self::Bc5RgUnorm,
+            wgpu::TextureFormat::Bc5RgSnorm => Self::Bc5RgSnorm,
+            wgpu::TextureFormat::Bc6hRgbUfloat => Self::Bc6hRgbUfloat,
+            wgpu::TextureFormat::Bc6hRgbSfloat => Self::Bc6hRgbSfloat,
+            wgpu::TextureFormat::Bc7RgbaUnorm => Self::Bc7RgbaUnorm,
+            wgpu::TextureFormat::Bc7RgbaUnormSrgb => Self::Bc7RgbaUnormSrgb,
+            wgpu::TextureFormat::Etc2RgbUnorm => Self::Etc2RgbUnorm,
+            wgpu::TextureFormat::Etc2RgbUnormSrgb => Self::Etc2RgbUnormSrgb,
+            wgpu::TextureFormat::Etc2RgbA1Unorm => Self::Etc2RgbA1Unorm,
+            wgpu::TextureFormat::Etc2RgbA1UnormSrgb => Self::Etc2RgbA1UnormSrgb,
+            wgpu::TextureFormat::EacRUnorm => Self::EacRUnorm,
+            wgpu::TextureFormat::EacRSnorm => Self::EacRSnorm,
+            wgpu::TextureFormat::EacRgUnorm => Self::EacRgUnorm,
+            wgpu::TextureFormat::EacRgSnorm => Self::EacRgSnorm,
+            wgpu::TextureFormat::Astc4x4RgbaUnorm => Self::Astc4x4RgbaUnorm,
+            wgpu::TextureFormat::Astc4x4RgbaUnormSrgb => Self::Astc4x4RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc5x4RgbaUnorm => Self::Astc5x4RgbaUnorm,
+            wgpu::TextureFormat::Astc5x4RgbaUnormSrgb => Self::Astc5x4RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc5x5RgbaUnorm => Self::Astc5x5RgbaUnorm,
+            wgpu::TextureFormat::Astc5x5RgbaUnormSrgb => Self::Astc5x5RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc6x5RgbaUnorm => Self::Astc6x5RgbaUnorm,
+            wgpu::TextureFormat::Astc6x5RgbaUnormSrgb => Self::Astc6x5RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc6x6RgbaUnorm => Self::Astc6x6RgbaUnorm,
+            wgpu::TextureFormat::Astc6x6RgbaUnormSrgb => Self::Astc6x6RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc8x5RgbaUnorm => Self::Astc8x5RgbaUnorm,
+            wgpu::TextureFormat::Astc8x5RgbaUnormSrgb => Self::Astc8x5RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc8x6RgbaUnorm => Self::Astc8x6RgbaUnorm,
+            wgpu::TextureFormat::Astc8x6RgbaUnormSrgb => Self::Astc8x6RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc10x5RgbaUnorm => Self::Astc10x5RgbaUnorm,
+            wgpu::TextureFormat::Astc10x5RgbaUnormSrgb => Self::Astc10x5RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc10x6RgbaUnorm => Self::Astc10x6RgbaUnorm,
+            wgpu::TextureFormat::Astc10x6RgbaUnormSrgb => Self::Astc10x6RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc8x8RgbaUnorm => Self::Astc8x8RgbaUnorm,
+            wgpu::TextureFormat::Astc8x8RgbaUnormSrgb => Self::Astc8x8RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc10x8RgbaUnorm => Self::Astc10x8RgbaUnorm,
+            wgpu::TextureFormat::Astc10x8RgbaUnormSrgb => Self::Astc10x8RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc10x10RgbaUnorm => Self::Astc10x10RgbaUnorm,
+            wgpu::TextureFormat::Astc10x10RgbaUnormSrgb => Self::Astc10x10RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc12x10RgbaUnorm => Self::Astc12x10RgbaUnorm,
+            wgpu::TextureFormat::Astc12x10RgbaUnormSrgb => Self::Astc12x10RgbaUnormSrgb,
+            wgpu::TextureFormat::Astc12x12RgbaUnorm => Self::Astc12x12RgbaUnorm,
+            wgpu::TextureFormat::Astc12x12RgbaUnormSrgb => Self::Astc12x12RgbaUnormSrgb,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mip_level_size_halves_down_to_one() {
+        let size = Extent3d {
+            width: 256,
+            height: 64,
+            depth_or_array_layers: 6,
+        };
+        let level = |level| {
+            let level_size = size.mip_level_size(level);
+            (
+                level_size.width,
+                level_size.height,
+                level_size.depth_or_array_layers,
+            )
+        };
+        assert_eq!(level(0), (256, 64, 6));
+        assert_eq!(level(3), (32, 8, 6));
+        assert_eq!(level(8), (1, 1, 6));
+        assert_eq!(level(32), (1, 1, 6));
+        assert_eq!(level(40), (1, 1, 6));
+    }
+
+    #[test]
+    fn check_image_data_rejects_mismatched_sizes() {
+        assert_eq!(check_image_data(&[0; 4], 1, 2, 2), Ok(()));
+        assert_eq!(check_image_data(&[0; 16], 4, 2, 2), Ok(()));
+        assert_eq!(check_image_data(&[], 4, 0, 3), Ok(()));
+        assert_eq!(
+            check_image_data(&[0; 3], 1, 2, 2),
+            Err(TextureError::DataSizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            check_image_data(&[0; 8], 2, 2, 3),
+            Err(TextureError::DataSizeMismatch {
+                expected: 12,
+                actual: 8
+            })
+        );
+    }
+}
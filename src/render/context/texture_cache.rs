@@ -0,0 +1,150 @@
+use super::texture::{TextureDescriptor, TextureDimension, TextureId, TextureViewId};
+use std::{collections::HashMap, sync::Arc};
+
+/// Called with every texture evicted to fit the texture budget, see
+/// [`ResourceContext::set_texture_budget`].
+///
+/// [`ResourceContext::set_texture_budget`]: super::ResourceContext::set_texture_budget
+pub type EvictionCallback = Arc<dyn Fn(TextureId) + Send + Sync>;
+
+/// Size of a texture and the last time it was used, in ticks of [`TextureCache`].
+#[derive(Copy, Clone, Debug)]
+struct CacheEntry {
+    size: u64,
+    last_used: u64,
+}
+
+/// Tracks the memory and the last use of every texture, to evict the least recently used ones
+/// when a budget is set.
+///
+/// The lock of the cache is never held while taking another lock, it can be taken while
+/// holding any.
+#[derive(Default)]
+pub(super) struct TextureCache {
+    budget: Option<u64>,
+    on_evict: Option<EvictionCallback>,
+    used_bytes: u64,
+    clock: u64,
+    entries: HashMap<TextureId, CacheEntry>,
+    /// Texture of every view, using a view uses its texture.
+    views: HashMap<TextureViewId, TextureId>,
+}
+
+impl TextureCache {
+    pub fn set_budget(&mut self, budget: Option<u64>, on_evict: Option<EvictionCallback>) {
+        self.budget = budget;
+        self.on_evict = on_evict;
+    }
+
+    pub fn on_evict(&self) -> Option<EvictionCallback> {
+        self.on_evict.clone()
+    }
+
+    pub fn used_bytes(&self) -> u64 {
+        self.used_bytes
+    }
+
+    /// Tracks a new texture, it counts as just used.
+    pub fn insert(&mut self, texture: TextureId, size: u64) {
+        self.clock += 1;
+        let entry = CacheEntry {
+            size,
+            last_used: self.clock,
+        };
+        if let Some(previous) = self.entries.insert(texture, entry) {
+            self.used_bytes -= previous.size;
+        }
+        self.used_bytes += size;
+    }
+
+    pub fn insert_view(&mut self, view: TextureViewId, texture: TextureId) {
+        self.views.insert(view, texture);
+        self.touch(texture);
+    }
+
+    /// Stops tracking a texture, returning the views created from it.
+    pub fn remove(&mut self, texture: TextureId) -> Vec<TextureViewId> {
+        if let Some(entry) = self.entries.remove(&texture) {
+            self.used_bytes -= entry.size;
+        }
+        let views: Vec<_> = self
+            .views
+            .iter()
+            .filter(|&(_, &view_texture)| view_texture == texture)
+            .map(|(&view, _)| view)
+            .collect();
+        for view in &views {
+            self.views.remove(view);
+        }
+        views
+    }
+
+    pub fn remove_view(&mut self, view: TextureViewId) {
+        self.views.remove(&view);
+    }
+
+    /// Marks a texture as just used, untracked textures are ignored.
+    pub fn touch(&mut self, texture: TextureId) {
+        if let Some(entry) = self.entries.get_mut(&texture) {
+            self.clock += 1;
+            entry.last_used = self.clock;
+        }
+    }
+
+    /// Marks the texture of a view as just used.
+    pub fn touch_view(&mut self, view: TextureViewId) {
+        if let Some(&texture) = self.views.get(&view) {
+            self.touch(texture);
+        }
+    }
+
+    /// Picks the textures to evict for `size` more bytes to fit the budget, least recently used
+    /// first, and stops tracking them.
+    ///
+    /// Every texture is picked if `size` alone is over the budget. Nothing is picked without a
+    /// budget.
+    pub fn evict_for(&mut self, size: u64) -> Vec<(TextureId, Vec<TextureViewId>)> {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return Vec::new(),
+        };
+        let mut by_age: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(&texture, entry)| (entry.last_used, texture))
+            .collect();
+        by_age.sort_unstable_by_key(|&(last_used, _)| last_used);
+        let mut evicted = Vec::new();
+        for (_, texture) in by_age {
+            if self.used_bytes + size <= budget {
+                break;
+            }
+            let views = self.remove(texture);
+            evicted.push((texture, views));
+        }
+        evicted
+    }
+}
+
+/// Estimates the memory of a texture from the size of its texels over every mip level, layer
+/// and sample.
+///
+/// Drivers may pad or compress textures, the actual memory can differ.
+pub fn texture_size(desc: &TextureDescriptor) -> u64 {
+    let info = wgpu::TextureFormat::from(desc.format).describe();
+    let block_height = info.block_dimensions.1 as u32;
+    (0..desc.mip_level_count)
+        .map(|level| {
+            let size = desc.size.mip_level_size(level);
+            // 3D textures are mipmapped along their depth too.
+            let depth = match desc.dimension {
+                TextureDimension::D3 => (desc.size.depth_or_array_layers >> level).max(1),
+                _ => size.depth_or_array_layers,
+            };
+            desc.format.unpadded_bytes_per_row(size.width) as u64
+                * size.height.div_ceil(block_height) as u64
+                * depth as u64
+        })
+        .sum::<u64>()
+        * desc.sample_count as u64
+}
@@ -1,3 +1,5 @@
+use bitflags::bitflags;
+
 /// Comparison function used for depth and stencil operations.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum CompareFunction {
@@ -28,8 +30,56 @@ impl From<CompareFunction> for wgpu::CompareFunction {
             CompareFunction::LessEqual => Self::LessEqual,
             CompareFunction::Greater => Self::Greater,
             CompareFunction::NotEqual => Self::NotEqual,
-            CompareFunction::GreaterEqual => Self::Never,
+            CompareFunction::GreaterEqual => Self::GreaterEqual,
             CompareFunction::Always => Self::Always,
         }
     }
-}
\ No newline at end of file
+}
+
+bitflags! {
+    /// Describes the shader stages that a binding will be visible from.
+    ///
+    /// These can be combined so something that is visible from both vertex and fragment shaders can be defined as:
+    ///
+    /// `ShaderStages::VERTEX | ShaderStages::FRAGMENT`
+    pub struct ShaderStages: u32 {
+        /// Binding is not visible from any shader stage.
+        const NONE = 0;
+        /// Binding is visible from the vertex shader of a render pipeline.
+        const VERTEX = 1 << 0;
+        /// Binding is visible from the fragment shader of a render pipeline.
+        const FRAGMENT = 1 << 1;
+        /// Binding is visible from the compute shader of a compute pipeline.
+        const COMPUTE = 1 << 2;
+        /// Binding is visible from the vertex and fragment shaders of a render pipeline.
+        const VERTEX_FRAGMENT = Self::VERTEX.bits | Self::FRAGMENT.bits;
+    }
+}
+
+impl From<ShaderStages> for wgpu::ShaderStages {
+    fn from(stages: ShaderStages) -> Self {
+        Self::from_bits_truncate(stages.bits)
+    }
+}
+
+/// RGBA double precision color.
+///
+/// This is not to be used as a generic color type, only for specific wgpu interfaces.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl From<Color> for wgpu::Color {
+    fn from(color: Color) -> Self {
+        Self {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+/// Whether a vertex buffer is indexed by vertex or by instance.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum VertexStepMode {
+    /// Vertex data is advanced every vertex.
+    #[default]
+    Vertex,
+    /// Vertex data is advanced every instance.
+    Instance,
+}
+
+impl From<VertexStepMode> for wgpu::VertexStepMode {
+    fn from(mode: VertexStepMode) -> Self {
+        match mode {
+            VertexStepMode::Vertex => Self::Vertex,
+            VertexStepMode::Instance => Self::Instance,
+        }
+    }
+}
+
+/// Vertex inputs (attributes) to shaders.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct VertexAttribute {
+    /// Format of the input
+    pub format: VertexFormat,
+    /// Byte offset of the start of the input
+    pub offset: u64,
+    /// Location for this input. Must match the location in the shader.
+    pub shader_location: u32,
+}
+
+impl From<&VertexAttribute> for wgpu::VertexAttribute {
+    fn from(attribute: &VertexAttribute) -> Self {
+        Self {
+            format: attribute.format.into(),
+            offset: attribute.offset,
+            shader_location: attribute.shader_location,
+        }
+    }
+}
+
+/// Vertex Format for a Vertex Attribute (input).
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum VertexFormat {
+    /// Two unsigned bytes (u8). `uvec2` in shaders.
+    Uint8x2,
+    /// Four unsigned bytes (u8). `uvec4` in shaders.
+    Uint8x4,
+    /// Two signed bytes (i8). `ivec2` in shaders.
+    Sint8x2,
+    /// Four signed bytes (i8). `ivec4` in shaders.
+    Sint8x4,
+    /// Two unsigned bytes (u8). [0, 255] converted to float [0, 1] `vec2` in shaders.
+    Unorm8x2,
+    /// Four unsigned bytes (u8). [0, 255] converted to float [0, 1] `vec4` in shaders.
+    Unorm8x4,
+    /// Two signed bytes (i8). [-127, 127] converted to float [-1, 1] `vec2` in shaders.
+    Snorm8x2,
+    /// Four signed bytes (i8). [-127, 127] converted to float [-1, 1] `vec4` in shaders.
+    Snorm8x4,
+    /// Two unsigned shorts (u16). `uvec2` in shaders.
+    Uint16x2,
+    /// Four unsigned shorts (u16). `uvec4` in shaders.
+    Uint16x4,
+    /// Two signed shorts (i16). `ivec2` in shaders.
+    Sint16x2,
+    /// Four signed shorts (i16). `ivec4` in shaders.
+    Sint16x4,
+    /// Two unsigned shorts (u16). [0, 65535] converted to float [0, 1] `vec2` in shaders.
+    Unorm16x2,
+    /// Four unsigned shorts (u16). [0, 65535] converted to float [0, 1] `vec4` in shaders.
+    Unorm16x4,
+    /// Two signed shorts (i16). [-32767, 32767] converted to float [-1, 1] `vec2` in shaders.
+    Snorm16x2,
+    /// Four signed shorts (i16). [-32767, 32767] converted to float [-1, 1] `vec4` in shaders.
+    Snorm16x4,
+    /// Two half-precision floats (no Rust equiv). `vec2` in shaders.
+    Float16x2,
+    /// Four half-precision floats (no Rust equiv). `vec4` in shaders.
+    Float16x4,
+    /// One single-precision float (f32). `float` in shaders.
+    Float32,
+    /// Two single-precision floats (f32). `vec2` in shaders.
+    Float32x2,
+    /// Three single-precision floats (f32). `vec3` in shaders.
+    Float32x3,
+    /// Four single-precision floats (f32). `vec4` in shaders.
+    Float32x4,
+    /// One unsigned int (u32). `uint` in shaders.
+    Uint32,
+    /// Two unsigned ints (u32). `uvec2` in shaders.
+    Uint32x2,
+    /// Three unsigned ints (u32). `uvec3` in shaders.
+    Uint32x3,
+    /// Four unsigned ints (u32). `uvec4` in shaders.
+    Uint32x4,
+    /// One signed int (i32). `int` in shaders.
+    Sint32,
+    /// Two signed ints (i32). `ivec2` in shaders.
+    Sint32x2,
+    /// Three signed ints (i32). `ivec3` in shaders.
+    Sint32x3,
+    /// Four signed ints (i32). `ivec4` in shaders.
+    Sint32x4,
+    /// One double-precision float (f64). `double` in shaders. Requires VERTEX_ATTRIBUTE_64BIT features.
+    Float64,
+    /// Two double-precision floats (f64). `dvec2` in shaders. Requires VERTEX_ATTRIBUTE_64BIT features.
+    Float64x2,
+    /// Three double-precision floats (f64). `dvec3` in shaders. Requires VERTEX_ATTRIBUTE_64BIT features.
+    Float64x3,
+    /// Four double-precision floats (f64). `dvec4` in shaders. Requires VERTEX_ATTRIBUTE_64BIT features.
+    Float64x4,
+}
+
+impl From<VertexFormat> for wgpu::VertexFormat {
+    fn from(format: VertexFormat) -> Self {
+        match format {
+            VertexFormat::Uint8x2 => Self::Uint8x2,
+            VertexFormat::Uint8x4 => Self::Uint8x4,
+            VertexFormat::Sint8x2 => Self::Sint8x2,
+            VertexFormat::Sint8x4 => Self::Sint8x4,
+            VertexFormat::Unorm8x2 => Self::Unorm8x2,
+            VertexFormat::Unorm8x4 => Self::Unorm8x4,
+            VertexFormat::Snorm8x2 => Self::Snorm8x2,
+            VertexFormat::Snorm8x4 => Self::Snorm8x4,
+            VertexFormat::Uint16x2 => Self::Uint16x2,
+            VertexFormat::Uint16x4 => Self::Uint16x4,
+            VertexFormat::Sint16x2 => Self::Sint16x2,
+            VertexFormat::Sint16x4 => Self::Sint16x4,
+            VertexFormat::Unorm16x2 => Self::Unorm16x2,
+            VertexFormat::Unorm16x4 => Self::Unorm16x4,
+            VertexFormat::Snorm16x2 => Self::Snorm16x2,
+            VertexFormat::Snorm16x4 => Self::Snorm16x4,
+            VertexFormat::Float16x2 => Self::Float16x2,
+            VertexFormat::Float16x4 => Self::Float16x4,
+            VertexFormat::Float32 => Self::Float32,
+            VertexFormat::Float32x2 => Self::Float32x2,
+            VertexFormat::Float32x3 => Self::Float32x3,
+            VertexFormat::Float32x4 => Self::Float32x4,
+            VertexFormat::Uint32 => Self::Uint32,
+            VertexFormat::Uint32x2 => Self::Uint32x2,
+            VertexFormat::Uint32x3 => Self::Uint32x3,
+            VertexFormat::Uint32x4 => Self::Uint32x4,
+            VertexFormat::Sint32 => Self::Sint32,
+            VertexFormat::Sint32x2 => Self::Sint32x2,
+            VertexFormat::Sint32x3 => Self::Sint32x3,
+            VertexFormat::Sint32x4 => Self::Sint32x4,
+            VertexFormat::Float64 => Self::Float64,
+            VertexFormat::Float64x2 => Self::Float64x2,
+            VertexFormat::Float64x3 => Self::Float64x3,
+            VertexFormat::Float64x4 => Self::Float64x4,
+        }
+    }
+}
+
+/// Describes how the vertex buffer bound to one slot is interpreted.
+///
+/// A pipeline takes one layout per vertex buffer slot, the layout at index `n` describes the
+/// buffer set with `slot: n` in the render pass. Each slot steps independently, so per-vertex
+/// and per-instance buffers can be mixed.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct VertexBufferLayout<'a> {
+    /// The stride, in bytes, between elements of this buffer.
+    pub array_stride: u64,
+    /// How often this vertex buffer is "stepped" forward.
+    pub step_mode: VertexStepMode,
+    /// The list of attributes which comprise a single vertex.
+    pub attributes: &'a [VertexAttribute],
+}
+
+impl VertexBufferLayout<'_> {
+    /// Converts the attributes, the wgpu layout borrows them so they are kept by the caller.
+    pub fn wgpu_attributes(&self) -> Vec<wgpu::VertexAttribute> {
+        self.attributes.iter().map(Into::into).collect()
+    }
+
+    /// Gets the wgpu layout of the buffer using attributes from [`Self::wgpu_attributes`].
+    pub fn wgpu_layout<'b>(
+        &self,
+        attributes: &'b [wgpu::VertexAttribute],
+    ) -> wgpu::VertexBufferLayout<'b> {
+        wgpu::VertexBufferLayout {
+            array_stride: self.array_stride,
+            step_mode: self.step_mode.into(),
+            attributes,
+        }
+    }
+}
+
+/// Format of indices used with pipeline.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum IndexFormat {
+    /// Indices are 16 bit unsigned integers.
+    Uint16,
+    /// Indices are 32 bit unsigned integers.
+    #[default]
+    Uint32,
+}
+
+impl From<IndexFormat> for wgpu::IndexFormat {
+    fn from(format: IndexFormat) -> Self {
+        match format {
+            IndexFormat::Uint16 => Self::Uint16,
+            IndexFormat::Uint32 => Self::Uint32,
+        }
+    }
+}
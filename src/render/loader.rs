@@ -0,0 +1,275 @@
+use super::context::{
+    buffer::{BufferId, BufferInitDescriptor, BufferUsages},
+    texture::{
+        Extent3d, TextureDescriptor, TextureDimension, TextureError, TextureFormat, TextureId,
+        TextureUsages,
+    },
+    vertex::{VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode},
+    ResourceContext,
+};
+use bytemuck::{Pod, Zeroable};
+use std::path::PathBuf;
+use tokio::{
+    sync::{mpsc, watch},
+    task::{self, JoinHandle},
+};
+
+/// Collects load jobs and processes them concurrently on tokio tasks.
+pub struct LoadQueue {
+    resource: ResourceContext,
+    jobs: Vec<LoadJob>,
+}
+
+impl LoadQueue {
+    ///
+    pub fn new(resource: ResourceContext) -> Self {
+        Self {
+            resource,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Adds a job to the queue, jobs are not processed until [`LoadQueue::start`] is called.
+    pub fn push(&mut self, job: LoadJob) {
+        self.jobs.push(job);
+    }
+
+    /// Spawns every queued job and returns a handle to follow their progress.
+    pub fn start(self) -> LoadHandle {
+        let total = self.jobs.len();
+        let (progress_tx, progress_rx) = watch::channel(LoadProgress { loaded: 0, total });
+        let resource = self.resource;
+        let jobs = self.jobs;
+        let results = tokio::spawn(async move {
+            // Spawns a task for each job, they report back with their index.
+            let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+            for (index, job) in jobs.into_iter().enumerate() {
+                let resource = resource.clone();
+                let done_tx = done_tx.clone();
+                tokio::spawn(async move {
+                    let result = job.load(&resource).await;
+                    let _ = done_tx.send((index, result));
+                });
+            }
+            drop(done_tx);
+            // Collects results in job order and reports progress as they come in.
+            let mut results: Vec<Option<Result<LoadedResource, LoadError>>> =
+                (0..total).map(|_| None).collect();
+            let mut loaded = 0;
+            while let Some((index, result)) = done_rx.recv().await {
+                results[index] = Some(result);
+                loaded += 1;
+                let _ = progress_tx.send(LoadProgress { loaded, total });
+            }
+            // Jobs whose task panicked never reported back.
+            results
+                .into_iter()
+                .map(|result| result.unwrap_or(Err(LoadError::Aborted)))
+                .collect()
+        });
+        LoadHandle {
+            progress: progress_rx,
+            results,
+        }
+    }
+}
+
+/// A resource to load from disk.
+#[derive(Clone, Debug)]
+pub enum LoadJob {
+    /// Decodes an image file into a RGBA8 sRGB texture.
+    Image(PathBuf),
+    /// Imports every mesh primitive of a glTF file into vertex and index buffers.
+    Mesh(PathBuf),
+}
+
+impl LoadJob {
+    async fn load(self, resource: &ResourceContext) -> Result<LoadedResource, LoadError> {
+        match self {
+            LoadJob::Image(path) => {
+                // Decoding is CPU bound, keeps it off the async workers.
+                let image = task::spawn_blocking(move || image::open(path))
+                    .await
+                    .map_err(|_| LoadError::Aborted)?
+                    .map_err(LoadError::Image)?
+                    .into_rgba8();
+                let desc = TextureDescriptor {
+                    label: None,
+                    size: Extent3d {
+                        width: image.width(),
+                        height: image.height(),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format: TextureFormat::Rgba8UnormSrgb,
+                    usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                };
+                let texture = resource
+                    .create_texture_with_data(&desc, &image)
+                    .await
+                    .map_err(LoadError::Texture)?;
+                Ok(LoadedResource::Texture(texture))
+            }
+            LoadJob::Mesh(path) => {
+                let primitives = task::spawn_blocking(move || read_mesh_primitives(path))
+                    .await
+                    .map_err(|_| LoadError::Aborted)??;
+                let mut meshes = Vec::with_capacity(primitives.len());
+                for (vertices, indices) in primitives {
+                    let vertex_buffer = resource
+                        .create_buffer_with_data(&BufferInitDescriptor {
+                            label: None,
+                            contents: bytemuck::cast_slice(&vertices),
+                            usage: BufferUsages::VERTEX,
+                        })
+                        .await;
+                    let index_buffer = match indices {
+                        Some(ref indices) => Some(
+                            resource
+                                .create_buffer_with_data(&BufferInitDescriptor {
+                                    label: None,
+                                    contents: bytemuck::cast_slice(indices),
+                                    usage: BufferUsages::INDEX,
+                                })
+                                .await,
+                        ),
+                        None => None,
+                    };
+                    meshes.push(MeshPrimitive {
+                        vertex_buffer,
+                        vertex_count: vertices.len() as u32,
+                        index_buffer,
+                        index_count: indices.map_or(0, |indices| indices.len() as u32),
+                    });
+                }
+                Ok(LoadedResource::Mesh(meshes))
+            }
+        }
+    }
+}
+
+/// Vertices and optional indices of a mesh primitive read on the CPU.
+type PrimitiveData = (Vec<MeshVertex>, Option<Vec<u32>>);
+
+/// Reads the vertices and indices of every primitive in a glTF file.
+fn read_mesh_primitives(path: PathBuf) -> Result<Vec<PrimitiveData>, LoadError> {
+    let (document, buffers, _) = gltf::import(path).map_err(LoadError::Gltf)?;
+    let mut primitives = Vec::new();
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            // Primitives without positions have nothing to draw.
+            let positions = match reader.read_positions() {
+                Some(positions) => positions,
+                None => continue,
+            };
+            let mut normals = reader.read_normals();
+            let mut tex_coords = reader.read_tex_coords(0).map(|coords| coords.into_f32());
+            let vertices = positions
+                .map(|position| MeshVertex {
+                    position,
+                    normal: normals
+                        .as_mut()
+                        .and_then(Iterator::next)
+                        .unwrap_or([0.0, 0.0, 1.0]),
+                    tex_coord: tex_coords
+                        .as_mut()
+                        .and_then(Iterator::next)
+                        .unwrap_or([0.0, 0.0]),
+                })
+                .collect();
+            let indices = reader
+                .read_indices()
+                .map(|indices| indices.into_u32().collect());
+            primitives.push((vertices, indices));
+        }
+    }
+    Ok(primitives)
+}
+
+/// Interleaved vertex layout of the buffers produced by [`LoadJob::Mesh`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct MeshVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub tex_coord: [f32; 2],
+}
+
+impl MeshVertex {
+    /// Attributes of the vertex at shader locations 0, 1 and 2.
+    const ATTRIBUTES: [VertexAttribute; 3] = [
+        VertexAttribute {
+            format: VertexFormat::Float32x3,
+            offset: 0,
+            shader_location: 0,
+        },
+        VertexAttribute {
+            format: VertexFormat::Float32x3,
+            offset: 12,
+            shader_location: 1,
+        },
+        VertexAttribute {
+            format: VertexFormat::Float32x2,
+            offset: 24,
+            shader_location: 2,
+        },
+    ];
+
+    /// Gets the per-vertex layout of a mesh vertex buffer.
+    pub fn layout() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as u64,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// GPU buffers of a single mesh primitive.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MeshPrimitive {
+    /// Vertex buffer laid out as [`MeshVertex`].
+    pub vertex_buffer: BufferId,
+    ///
+    pub vertex_count: u32,
+    /// Index buffer of `u32` indices if the primitive is indexed.
+    pub index_buffer: Option<BufferId>,
+    ///
+    pub index_count: u32,
+}
+
+/// A resource inserted into the [`ResourceContext`] by a finished job.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoadedResource {
+    Texture(TextureId),
+    Mesh(Vec<MeshPrimitive>),
+}
+
+///
+#[derive(Debug)]
+pub enum LoadError {
+    Image(image::ImageError),
+    Gltf(gltf::Error),
+    /// The texture of an image could not be created.
+    Texture(TextureError),
+    /// The task processing the job panicked or was cancelled.
+    Aborted,
+}
+
+/// Number of finished jobs out of all the jobs of a queue.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LoadProgress {
+    pub loaded: usize,
+    pub total: usize,
+}
+
+/// Handle to a started [`LoadQueue`].
+pub struct LoadHandle {
+    /// Updated every time a job finishes, successfully or not.
+    pub progress: watch::Receiver<LoadProgress>,
+    /// Resolves to the result of every job, in the order they were pushed.
+    pub results: JoinHandle<Vec<Result<LoadedResource, LoadError>>>,
+}
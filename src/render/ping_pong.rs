@@ -0,0 +1,84 @@
+use super::context::{
+    bind_group::{
+        BindGroupDescriptor, BindGroupEntry, BindGroupError, BindGroupId, BindGroupLayoutId,
+        BindingResource,
+    },
+    ResourceContext,
+};
+
+/// Two targets alternating between being read and written, as in iterative simulations.
+///
+/// Each iteration reads [`PingPong::current`], writes [`PingPong::next`] and then calls
+/// [`PingPong::swap`] so the freshly written target is read by the next iteration.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct PingPong<T> {
+    targets: [T; 2],
+    index: usize,
+}
+
+impl<T> PingPong<T> {
+    /// Creates a ping pong with `first` as its current target.
+    pub fn new(first: T, second: T) -> Self {
+        Self {
+            targets: [first, second],
+            index: 0,
+        }
+    }
+
+    /// Gets the target to read from.
+    pub fn current(&self) -> &T {
+        &self.targets[self.index]
+    }
+
+    /// Gets the target to write to.
+    pub fn next(&self) -> &T {
+        &self.targets[1 - self.index]
+    }
+
+    /// Exchanges the current and next targets.
+    pub fn swap(&mut self) {
+        self.index = 1 - self.index;
+    }
+}
+
+impl<T: Copy + Into<BindingResource>> PingPong<T> {
+    /// Pairs each target with a bind group built for the orientation where it is current.
+    ///
+    /// The bind group paired with a target binds it at `read_binding` and the other target at
+    /// `write_binding`, both bind groups are created once so swapping never rebuilds them.
+    pub async fn with_bind_groups(
+        self,
+        resource: &ResourceContext,
+        layout: BindGroupLayoutId,
+        read_binding: u32,
+        write_binding: u32,
+    ) -> Result<PingPong<(T, BindGroupId)>, BindGroupError> {
+        let mut bind_groups = Vec::with_capacity(2);
+        for (read, write) in [(self.current(), self.next()), (self.next(), self.current())] {
+            let bind_group = resource
+                .create_bind_group(&BindGroupDescriptor {
+                    label: None,
+                    layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: read_binding,
+                            resource: (*read).into(),
+                        },
+                        BindGroupEntry {
+                            binding: write_binding,
+                            resource: (*write).into(),
+                        },
+                    ],
+                })
+                .await?;
+            bind_groups.push(bind_group);
+        }
+        Ok(PingPong {
+            targets: [
+                (*self.current(), bind_groups[0]),
+                (*self.next(), bind_groups[1]),
+            ],
+            index: 0,
+        })
+    }
+}
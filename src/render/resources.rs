@@ -1,5 +1,3 @@
-use uuid::Uuid;
-
 ///
 pub struct ResourceManager {}
 
@@ -18,8 +16,3 @@ enum ResourceType {
     Buffer,
     QuerySet, // Not supported for now
 }
-
-
-
-
-
@@ -0,0 +1,242 @@
+use crate::{
+    frame_time::{FrameTimeHistogram, FrameTimeLog},
+    metrics::Metrics,
+    pipeline::PipelineState,
+    FIXED_TIMESTEP,
+};
+use std::{sync::Arc, thread, time};
+use tokio::{
+    runtime::Handle,
+    sync::mpsc::{self, error::TryRecvError},
+};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::VirtualKeyCode,
+    window::Window,
+};
+
+/// Window event forwarded by the event loop to the render thread.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Command {
+    /// The window was resized to the inner size, or may have been.
+    Resize(PhysicalSize<u32>),
+    /// The window moved to a monitor of another scale factor and was resized to the inner size.
+    ScaleFactorChanged {
+        scale_factor: f64,
+        new_size: PhysicalSize<u32>,
+    },
+    /// A key the event loop does not handle itself was pressed.
+    Key(VirtualKeyCode),
+    /// The cursor moved over the window.
+    CursorMoved(PhysicalPosition<f64>),
+    /// The left button was pressed if true, released otherwise.
+    LeftButton(bool),
+    /// Stops the render loop, dropping the pipeline state.
+    Exit,
+}
+
+/// Thread owning the pipeline state and running the render loop, so a slow frame does not hold
+/// the window events back.
+///
+/// The event loop forwards the events through [`Self::send`], the render loop handles the
+/// commands queued since the last frame before rendering the next one, and sleeps until a
+/// command comes while the window is minimized or the loop paused.
+///
+/// winit only lets the window be created on the thread running the event loop, and some
+/// platforms only let its surface be created there too: create the pipeline state, which
+/// creates the surface, on the event loop thread before moving it to [`Self::spawn`].
+pub struct RenderThread {
+    commands: mpsc::UnboundedSender<Command>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl RenderThread {
+    /// Starts rendering `state` into `window` from a new thread, which blocks on the futures
+    /// of the state with the current tokio runtime.
+    pub fn spawn(
+        state: PipelineState,
+        window: Arc<Window>,
+        metrics: Arc<Metrics>,
+        frame_time_log: Option<FrameTimeLog>,
+    ) -> Self {
+        let runtime = Handle::current();
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let render_loop = RenderLoop {
+            state,
+            window,
+            metrics,
+            // Frame times of the last 10s at 60fps, in 1ms buckets up to 50ms
+            frame_times: FrameTimeHistogram::new(600, time::Duration::from_millis(1), 50),
+            frame_time_log,
+            time_last: time::Instant::now(),
+            paused: false,
+            step_requested: false,
+        };
+        let thread = thread::Builder::new()
+            .name("CGToy - Render".to_owned())
+            .spawn(move || {
+                // Tasks spawned by the state, such as the frame scope cleanups, go to the runtime
+                let _guard = runtime.enter();
+                render_loop.run(&runtime, receiver);
+            })
+            .expect("Fail to spawn render thread");
+        Self {
+            commands,
+            thread: Some(thread),
+        }
+    }
+
+    /// Queues `command` for the render loop, commands sent once it stopped are dropped.
+    pub fn send(&self, command: Command) {
+        if self.thread.is_some() && self.commands.send(command).is_err() {
+            log::warn!("Render thread stopped, dropping {:?}.", command);
+        }
+    }
+
+    /// Stops the render loop once the commands sent before are handled, and waits for it.
+    pub fn join(&mut self) {
+        self.send(Command::Exit);
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("Render thread panicked.");
+            }
+        }
+    }
+}
+
+/// State of the render loop, owned by the render thread.
+struct RenderLoop {
+    state: PipelineState,
+    window: Arc<Window>,
+    metrics: Arc<Metrics>,
+    frame_times: FrameTimeHistogram,
+    frame_time_log: Option<FrameTimeLog>,
+    time_last: time::Instant,
+    // Pause with P and step a single frame with period while paused
+    paused: bool,
+    step_requested: bool,
+}
+
+impl RenderLoop {
+    fn run(mut self, runtime: &Handle, mut commands: mpsc::UnboundedReceiver<Command>) {
+        loop {
+            // Nothing is drawn while minimized or paused, wait for a command instead of spinning
+            if self.state.is_minimized() || (self.paused && !self.step_requested) {
+                match commands.blocking_recv() {
+                    Some(command) if self.handle(runtime, command) => (),
+                    _ => return,
+                }
+                // Time spent waiting is not a frame time
+                self.time_last = time::Instant::now();
+                continue;
+            }
+            loop {
+                match commands.try_recv() {
+                    Ok(command) if self.handle(runtime, command) => (),
+                    Err(TryRecvError::Empty) => break,
+                    _ => return,
+                }
+            }
+            if !self.state.is_minimized() {
+                self.frame(runtime);
+            }
+        }
+    }
+
+    /// Handles a command, returning false once the loop should stop.
+    fn handle(&mut self, runtime: &Handle, command: Command) -> bool {
+        match command {
+            Command::Resize(new_size) => runtime.block_on(self.state.resize(new_size)),
+            Command::ScaleFactorChanged {
+                scale_factor,
+                new_size,
+            } => {
+                log::info!(
+                    "Scale factor changed from {} to {}",
+                    self.state.scale_factor(),
+                    scale_factor
+                );
+                runtime.block_on(self.state.set_scale_factor(scale_factor, new_size))
+            }
+            Command::Key(key) => match key {
+                VirtualKeyCode::P => self.paused = !self.paused,
+                VirtualKeyCode::Period => self.step_requested = self.paused,
+                // Read the edited shaders again with R, a rejected shader shows magenta until fixed
+                VirtualKeyCode::R => {
+                    if let Err(error) = self.state.reload() {
+                        log::warn!("Fail to reload pipeline: {:?}", error);
+                    }
+                }
+                VirtualKeyCode::H => {
+                    let snapshot = self.metrics.snapshot();
+                    log::info!(
+                        "Frames:{} avg:{:?} min:{:?} max:{:?}",
+                        snapshot.frames,
+                        snapshot.average,
+                        snapshot.min,
+                        snapshot.max
+                    );
+                    log::info!(
+                        "Frame times p50:{:?} p95:{:?} p99:{:?} buckets(1ms):{:?}",
+                        self.frame_times.p50(),
+                        self.frame_times.p95(),
+                        self.frame_times.p99(),
+                        self.frame_times.bucket_counts()
+                    );
+                    if let Some(error) = self.state.pipeline_error() {
+                        log::warn!("Scene shader is broken: {:?}", error);
+                    }
+                }
+                _ => runtime.block_on(self.state.handle_key(key)),
+            },
+            Command::CursorMoved(position) => self.state.handle_cursor_moved(position),
+            Command::LeftButton(pressed) => self.state.handle_left_button(pressed),
+            Command::Exit => return false,
+        }
+        true
+    }
+
+    fn frame(&mut self, runtime: &Handle) {
+        // Calculate fps
+        let time_now = time::Instant::now();
+        let render_time = time_now - self.time_last;
+        self.time_last = time_now;
+        // A paused loop only gets here to advance by a fixed timestep when stepped
+        let dt = if self.paused {
+            FIXED_TIMESTEP
+        } else {
+            render_time
+        };
+        self.step_requested = false;
+        // Time spent paused is not a frame time
+        if !self.paused {
+            self.frame_times.record(render_time);
+            self.metrics.record(render_time);
+        }
+        self.state.update(dt);
+        let stats = runtime.block_on(self.state.render());
+        if let Some(log) = &mut self.frame_time_log {
+            if let Err(error) = log.record(render_time, stats.cpu_time) {
+                log::warn!("Fail to write frame times: {}", error);
+                self.frame_time_log = None;
+            }
+        }
+        // No frame time is measured until a frame is recorded, such as while paused from the start
+        let snapshot = self.metrics.snapshot();
+        let fps = match snapshot.frames {
+            0 => "–".to_string(),
+            _ => (1_000_000 / snapshot.current.as_micros().max(1)).to_string(),
+        };
+        self.window.set_title(
+            format!(
+                "CGToy - fps:{} p99:{:.2}ms cpu:{:.2}ms draws:{} tris:{}",
+                fps,
+                self.frame_times.p99().as_secs_f64() * 1000.0,
+                stats.cpu_time.as_secs_f64() * 1000.0,
+                stats.draw_calls,
+                stats.triangles
+            )
+            .as_str(),
+        );
+    }
+}
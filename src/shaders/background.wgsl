@@ -0,0 +1,41 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] tex_coord: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.tex_coord = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+// Mirrors `BackgroundUniforms` in `pipeline.rs`.
+[[block]]
+struct Background {
+    color_a: vec4<f32>;
+    color_b: vec4<f32>;
+    // 0 for a vertical gradient, 1 for a checker.
+    mode: u32;
+    // Side of the checker cells in pixels.
+    cell_size: f32;
+};
+
+[[group(0), binding(0)]]
+var<uniform> background: Background;
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    if (background.mode == 1u) {
+        let cell = floor(in.position.xy / background.cell_size);
+        let odd = (i32(cell.x) + i32(cell.y)) & 1;
+        return select(background.color_a, background.color_b, odd == 1);
+    }
+    // From color_a at the top to color_b at the bottom.
+    return mix(background.color_a, background.color_b, in.tex_coord.y);
+}
@@ -0,0 +1,23 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var source_texture: texture_2d<f32>;
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // The source and the target have the same size, so texels are loaded one to one.
+    return textureLoad(source_texture, vec2<i32>(in.position.xy), 0);
+}
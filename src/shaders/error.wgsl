@@ -0,0 +1,14 @@
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> [[builtin(position)]] vec4<f32> {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.5, 1.0);
+}
+
+[[stage(fragment)]]
+fn main() -> [[location(0)]] vec4<f32> {
+    // Bright magenta, which no working shader is likely to fill the screen with.
+    return vec4<f32>(1.0, 0.0, 1.0, 1.0);
+}
@@ -0,0 +1,31 @@
+// Trails following the cursor, load with `--fragment` and enable the frame feedback with `T`.
+[[block]]
+struct ToyUniforms {
+    iResolution: vec2<f32>;
+    iTime: f32;
+    iMouse: vec4<f32>;
+    // Top left corner of the viewport, and size of the whole scene in pixels.
+    iOrigin: vec2<f32>;
+    iSceneResolution: vec2<f32>;
+};
+
+[[group(1), binding(0)]]
+var<uniform> toy: ToyUniforms;
+// Previous frame, black while the frame feedback is disabled.
+[[group(1), binding(2)]]
+var previous_frame: texture_2d<f32>;
+[[group(1), binding(3)]]
+var previous_sampler: sampler;
+
+[[stage(fragment)]]
+fn main([[builtin(position)]] frag_coord: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let uv = frag_coord.xy / toy.iSceneResolution;
+    // Fade the last frame out, then draw a dot under the cursor while a button is held.
+    var color = textureSample(previous_frame, previous_sampler, uv).rgb * 0.95;
+    let pixel = frag_coord.xy - toy.iOrigin;
+    let coord = vec2<f32>(pixel.x, toy.iResolution.y - pixel.y);
+    if (toy.iMouse.z > 0.0 && distance(coord, toy.iMouse.xy) < 12.0) {
+        color = 0.5 + 0.5 * cos(vec3<f32>(toy.iTime) + vec3<f32>(0.0, 2.0, 4.0));
+    }
+    return vec4<f32>(color, 1.0);
+}
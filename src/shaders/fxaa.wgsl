@@ -0,0 +1,123 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] tex_coord: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.tex_coord = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var source_texture: texture_2d<f32>;
+[[group(0), binding(1)]]
+var source_sampler: sampler;
+
+// Smallest luma contrast smoothed, to leave dark areas alone.
+let EDGE_THRESHOLD_MIN: f32 = 0.0312;
+// Smallest luma contrast smoothed, relative to the brightest neighbour.
+let EDGE_THRESHOLD_MAX: f32 = 0.125;
+// How much isolated texels are blended with their neighbours.
+let SUBPIXEL_QUALITY: f32 = 0.75;
+// Texels searched along an edge in each direction to find its ends.
+let SEARCH_STEPS: i32 = 8;
+
+fn luma_at(uv: vec2<f32>) -> f32 {
+    let color = textureSampleLevel(source_texture, source_sampler, uv, 0.0).rgb;
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+// FXAA 3.11 quality preset with a fixed step search: the texel is shifted across the edge it
+// is on, proportionally to how close it is to the end of the edge.
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let dimensions = textureDimensions(source_texture);
+    let texel = vec2<f32>(1.0 / f32(dimensions.x), 1.0 / f32(dimensions.y));
+    let uv = in.tex_coord;
+    let center = textureSampleLevel(source_texture, source_sampler, uv, 0.0);
+    let c = dot(center.rgb, vec3<f32>(0.299, 0.587, 0.114));
+    let n = luma_at(uv + vec2<f32>(0.0, -texel.y));
+    let s = luma_at(uv + vec2<f32>(0.0, texel.y));
+    let e = luma_at(uv + vec2<f32>(texel.x, 0.0));
+    let w = luma_at(uv + vec2<f32>(-texel.x, 0.0));
+    let luma_min = min(c, min(min(n, s), min(e, w)));
+    let luma_max = max(c, max(max(n, s), max(e, w)));
+    let range = luma_max - luma_min;
+    if (range < max(EDGE_THRESHOLD_MIN, luma_max * EDGE_THRESHOLD_MAX)) {
+        return center;
+    }
+    let nw = luma_at(uv + vec2<f32>(-texel.x, -texel.y));
+    let ne = luma_at(uv + vec2<f32>(texel.x, -texel.y));
+    let sw = luma_at(uv + vec2<f32>(-texel.x, texel.y));
+    let se = luma_at(uv + vec2<f32>(texel.x, texel.y));
+    // Finds if the edge is horizontal or vertical.
+    let edge_horizontal = abs(n + s - 2.0 * c) * 2.0 + abs(ne + se - 2.0 * e) + abs(nw + sw - 2.0 * w);
+    let edge_vertical = abs(e + w - 2.0 * c) * 2.0 + abs(ne + nw - 2.0 * n) + abs(se + sw - 2.0 * s);
+    let horizontal = edge_horizontal >= edge_vertical;
+    // Picks the side of the edge with the steepest gradient.
+    let luma1 = select(w, n, horizontal);
+    let luma2 = select(e, s, horizontal);
+    let gradient1 = abs(luma1 - c);
+    let gradient2 = abs(luma2 - c);
+    let steepest_first = gradient1 >= gradient2;
+    let gradient_scaled = 0.25 * max(gradient1, gradient2);
+    let step_length = select(texel.x, texel.y, horizontal) * select(1.0, -1.0, steepest_first);
+    let luma_local_average = 0.5 * (select(luma2, luma1, steepest_first) + c);
+    var across: vec2<f32>;
+    var along: vec2<f32>;
+    if (horizontal) {
+        across = vec2<f32>(0.0, step_length);
+        along = vec2<f32>(texel.x, 0.0);
+    } else {
+        across = vec2<f32>(step_length, 0.0);
+        along = vec2<f32>(0.0, texel.y);
+    }
+    // Walks along the edge, half a texel across it, until the luma leaves the edge average.
+    let edge_uv = uv + across * 0.5;
+    var uv1 = edge_uv - along;
+    var uv2 = edge_uv + along;
+    var end1 = luma_at(uv1) - luma_local_average;
+    var end2 = luma_at(uv2) - luma_local_average;
+    var done1 = abs(end1) >= gradient_scaled;
+    var done2 = abs(end2) >= gradient_scaled;
+    var steps = 1;
+    loop {
+        if ((done1 && done2) || steps >= SEARCH_STEPS) {
+            break;
+        }
+        if (!done1) {
+            uv1 = uv1 - along;
+            end1 = luma_at(uv1) - luma_local_average;
+            done1 = abs(end1) >= gradient_scaled;
+        }
+        if (!done2) {
+            uv2 = uv2 + along;
+            end2 = luma_at(uv2) - luma_local_average;
+            done2 = abs(end2) >= gradient_scaled;
+        }
+        steps = steps + 1;
+    }
+    // Shifts more the closer the texel is to the end of the edge, if that end goes the same
+    // way as the texel.
+    let distance1 = length(edge_uv - uv1);
+    let distance2 = length(uv2 - edge_uv);
+    let closest1 = distance1 < distance2;
+    let pixel_offset = 0.5 - min(distance1, distance2) / (distance1 + distance2);
+    let center_smaller = c < luma_local_average;
+    let correct_variation = select((end2 < 0.0) != center_smaller, (end1 < 0.0) != center_smaller, closest1);
+    let edge_offset = select(0.0, pixel_offset, correct_variation);
+    // Blends isolated texels with their neighbours.
+    let luma_average = (2.0 * (n + s + e + w) + nw + ne + sw + se) / 12.0;
+    let contrast = clamp(abs(luma_average - c) / range, 0.0, 1.0);
+    let subpixel = (-2.0 * contrast + 3.0) * contrast * contrast;
+    let subpixel_offset = subpixel * subpixel * SUBPIXEL_QUALITY;
+    let offset = max(edge_offset, subpixel_offset);
+    return textureSampleLevel(source_texture, source_sampler, uv + across * offset, 0.0);
+}
@@ -0,0 +1,45 @@
+[[block]]
+struct Camera {
+    view_proj: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> camera: Camera;
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] position: vec3<f32>;
+    [[location(1)]] normal: vec3<f32>;
+    [[location(2)]] albedo: vec3<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[location(0)]] position: vec3<f32>,
+    [[location(1)]] normal: vec3<f32>,
+    [[location(2)]] albedo: vec3<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+    out.position = position;
+    out.normal = normal;
+    out.albedo = albedo;
+    return out;
+}
+
+// One output per color attachment of the geometry pass.
+struct GBuffer {
+    [[location(0)]] albedo: vec4<f32>;
+    [[location(1)]] normal: vec4<f32>;
+    [[location(2)]] position: vec4<f32>;
+};
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> GBuffer {
+    var out: GBuffer;
+    // The alpha of the albedo marks the texels covered by geometry.
+    out.albedo = vec4<f32>(in.albedo, 1.0);
+    out.normal = vec4<f32>(normalize(in.normal), 0.0);
+    out.position = vec4<f32>(in.position, 1.0);
+    return out;
+}
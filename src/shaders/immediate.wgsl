@@ -0,0 +1,28 @@
+[[block]]
+struct Immediate {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> immediate: Immediate;
+
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[location(0)]] position: vec3<f32>,
+    [[location(1)]] color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = immediate.transform * vec4<f32>(position, 1.0);
+    out.color = color;
+    return out;
+}
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return in.color;
+}
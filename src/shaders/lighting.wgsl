@@ -0,0 +1,37 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var albedo_texture: texture_2d<f32>;
+[[group(0), binding(1)]]
+var normal_texture: texture_2d<f32>;
+[[group(0), binding(2)]]
+var position_texture: texture_2d<f32>;
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // The G-buffer matches the target texel for texel.
+    let texel = vec2<i32>(in.position.xy);
+    let albedo = textureLoad(albedo_texture, texel, 0);
+    let normal = textureLoad(normal_texture, texel, 0).xyz;
+    let position = textureLoad(position_texture, texel, 0).xyz;
+    // A point light with a quadratic falloff and some ambient light.
+    let to_light = vec3<f32>(3.0, 5.0, 4.0) - position;
+    let light_distance = length(to_light);
+    let diffuse = max(dot(normal, to_light / light_distance), 0.0) * 30.0 / (light_distance * light_distance);
+    let color = albedo.rgb * (0.1 + diffuse);
+    // Texels without geometry are transparent and keep the forward rendered scene.
+    return vec4<f32>(color, albedo.a);
+}
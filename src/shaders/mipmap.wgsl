@@ -0,0 +1,29 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var source_texture: texture_2d<f32>;
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Averages the 2x2 source texels under the target texel, the last row and column of odd
+    // sizes are repeated.
+    let last = textureDimensions(source_texture, 0) - vec2<i32>(1, 1);
+    let base = vec2<i32>(in.position.xy) * 2;
+    return (textureLoad(source_texture, min(base, last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(1, 0), last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(0, 1), last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(1, 1), last), 0)) * 0.25;
+}
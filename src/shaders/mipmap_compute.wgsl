@@ -0,0 +1,23 @@
+[[group(0), binding(0)]]
+var source_texture: texture_2d<f32>;
+// The storage format is substituted when the pipeline of a format is created.
+[[group(0), binding(1)]]
+var target_texture: texture_storage_2d<{format}, write>;
+
+[[stage(compute), workgroup_size(8, 8, 1)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let size = textureDimensions(target_texture);
+    let position = vec2<i32>(id.xy);
+    if (position.x >= size.x || position.y >= size.y) {
+        return;
+    }
+    // Averages the 2x2 source texels under the target texel, the last row and column of odd
+    // sizes are repeated.
+    let last = textureDimensions(source_texture, 0) - vec2<i32>(1, 1);
+    let base = position * 2;
+    let color = (textureLoad(source_texture, min(base, last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(1, 0), last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(0, 1), last), 0)
+        + textureLoad(source_texture, min(base + vec2<i32>(1, 1), last), 0)) * 0.25;
+    textureStore(target_texture, position, color);
+}
@@ -0,0 +1,32 @@
+[[block]]
+struct Quad {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> quad: Quad;
+[[group(0), binding(1)]]
+var quad_texture: texture_2d<f32>;
+[[group(0), binding(2)]]
+var quad_sampler: sampler;
+
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] tex_coord: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn main(
+    [[location(0)]] corner: vec2<f32>,
+) -> VertexOutput {
+    // The corners of the unit quad are also its texture coordinates.
+    var out: VertexOutput;
+    out.position = quad.transform * vec4<f32>(corner, 0.0, 1.0);
+    out.tex_coord = corner;
+    return out;
+}
+
+[[stage(fragment)]]
+fn main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return textureSample(quad_texture, quad_sampler, in.tex_coord);
+}
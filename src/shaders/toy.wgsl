@@ -0,0 +1,47 @@
+[[block]]
+struct ToyUniforms {
+    iResolution: vec2<f32>;
+    iTime: f32;
+    iMouse: vec4<f32>;
+    // Top left corner of the viewport, and size of the whole scene in pixels.
+    iOrigin: vec2<f32>;
+    iSceneResolution: vec2<f32>;
+};
+
+// Parameters set from the host with `PipelineState::set_param`.
+[[block]]
+struct ToyParams {
+    intensity: f32;
+    speed: f32;
+    scale: f32;
+    offset: f32;
+    color: vec4<f32>;
+};
+
+[[group(1), binding(0)]]
+var<uniform> toy: ToyUniforms;
+[[group(1), binding(1)]]
+var<uniform> params: ToyParams;
+
+[[stage(vertex)]]
+fn vs_main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> [[builtin(position)]] vec4<f32> {
+    // One triangle covering the whole screen.
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.5, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] frag_coord: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    // Shadertoy measures fragments from the bottom left corner of the viewport.
+    let pixel = frag_coord.xy - toy.iOrigin;
+    let coord = vec2<f32>(pixel.x, toy.iResolution.y - pixel.y);
+    let uv = coord / toy.iResolution * params.scale + params.offset;
+    var color = 0.5 + 0.5 * cos(vec3<f32>(toy.iTime * params.speed) + uv.xyx + vec3<f32>(0.0, 2.0, 4.0));
+    // Highlight the fragments around the mouse while a button is held.
+    if (toy.iMouse.z > 0.0 && distance(coord, toy.iMouse.xy) < 20.0) {
+        color = vec3<f32>(1.0) - color;
+    }
+    return vec4<f32>(color * params.color.rgb * params.intensity, 1.0);
+}
@@ -0,0 +1,21 @@
+// Adds two vectors of floats component-wise, `cgtoy --vector-add` runs it headlessly.
+[[block]]
+struct Values {
+    data: array<f32>;
+};
+
+[[group(0), binding(0)]]
+var<storage, read> a: Values;
+[[group(0), binding(1)]]
+var<storage, read> b: Values;
+[[group(0), binding(2)]]
+var<storage, read_write> sum: Values;
+
+[[stage(compute), workgroup_size(64)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    // The last workgroup may run past the end of the vectors.
+    if (id.x >= arrayLength(&sum.data)) {
+        return;
+    }
+    sum.data[id.x] = a.data[id.x] + b.data[id.x];
+}
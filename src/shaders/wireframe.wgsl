@@ -0,0 +1,7 @@
+// Color of the edges drawn over the scene in hidden-line mode.
+let WIREFRAME_COLOR: vec4<f32> = vec4<f32>(0.95, 0.95, 0.95, 1.0);
+
+[[stage(fragment)]]
+fn main() -> [[location(0)]] vec4<f32> {
+    return WIREFRAME_COLOR;
+}